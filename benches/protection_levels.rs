@@ -0,0 +1,191 @@
+//! Protection Level Benchmarks
+//!
+//! Measures the overhead of `debug`, `standard` and `paranoid` against the
+//! same functions compiled natively, then prints slowdown factors.
+//!
+//! ## Running
+//! ```bash
+//! cargo bench --bench protection_levels
+//! ```
+
+use aegis_vm::vm_protect;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// =============================================================================
+// Arithmetic
+// =============================================================================
+
+fn arith_native(x: u64) -> u64 {
+    ((x + 10) * 3) ^ 0xFF
+}
+
+#[vm_protect(level = "debug")]
+fn arith_debug(x: u64) -> u64 {
+    ((x + 10) * 3) ^ 0xFF
+}
+
+#[vm_protect(level = "standard")]
+fn arith_standard(x: u64) -> u64 {
+    ((x + 10) * 3) ^ 0xFF
+}
+
+#[vm_protect(level = "paranoid")]
+fn arith_paranoid(x: u64) -> u64 {
+    ((x + 10) * 3) ^ 0xFF
+}
+
+// =============================================================================
+// Loop
+// =============================================================================
+
+fn loop_native(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+#[vm_protect(level = "debug")]
+fn loop_debug(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+#[vm_protect(level = "standard")]
+fn loop_standard(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+#[vm_protect(level = "paranoid")]
+fn loop_paranoid(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+// =============================================================================
+// Match
+// =============================================================================
+
+fn match_native(x: u64) -> u64 {
+    match x {
+        0 => 10,
+        1 => 20,
+        2 => 30,
+        3 => 40,
+        _ => 50,
+    }
+}
+
+#[vm_protect(level = "debug")]
+fn match_debug(x: u64) -> u64 {
+    match x {
+        0 => 10,
+        1 => 20,
+        2 => 30,
+        3 => 40,
+        _ => 50,
+    }
+}
+
+#[vm_protect(level = "standard")]
+fn match_standard(x: u64) -> u64 {
+    match x {
+        0 => 10,
+        1 => 20,
+        2 => 30,
+        3 => 40,
+        _ => 50,
+    }
+}
+
+#[vm_protect(level = "paranoid")]
+fn match_paranoid(x: u64) -> u64 {
+    match x {
+        0 => 10,
+        1 => 20,
+        2 => 30,
+        3 => 40,
+        _ => 50,
+    }
+}
+
+// =============================================================================
+// Harness
+// =============================================================================
+
+type Variant = (&'static str, fn(u64) -> u64);
+
+/// (workload, input, [native, debug, standard, paranoid])
+const WORKLOADS: &[(&str, u64, [Variant; 4])] = &[
+    ("arithmetic", 7, [
+        ("native", arith_native),
+        ("debug", arith_debug),
+        ("standard", arith_standard),
+        ("paranoid", arith_paranoid),
+    ]),
+    ("loop", 100, [
+        ("native", loop_native),
+        ("debug", loop_debug),
+        ("standard", loop_standard),
+        ("paranoid", loop_paranoid),
+    ]),
+    ("match", 3, [
+        ("native", match_native),
+        ("debug", match_debug),
+        ("standard", match_standard),
+        ("paranoid", match_paranoid),
+    ]),
+];
+
+fn bench_levels(c: &mut Criterion) {
+    for &(workload, input, variants) in WORKLOADS {
+        let expected = variants[0].1(input);
+        let mut group = c.benchmark_group(workload);
+        for (level, f) in variants {
+            assert_eq!(f(input), expected, "{workload}/{level}");
+            group.bench_function(level, |b| b.iter(|| f(black_box(input))));
+        }
+        group.finish();
+    }
+}
+
+/// Average time per call over `iterations`
+fn time_per_call(f: fn(u64) -> u64, input: u64, iterations: u32) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f(black_box(input)));
+    }
+    start.elapsed() / iterations
+}
+
+fn slowdown_report(_: &mut Criterion) {
+    const ITERATIONS: u32 = 2_000;
+
+    println!("\n=== Slowdown vs native ({} calls each) ===", ITERATIONS);
+    for &(workload, input, variants) in WORKLOADS {
+        let native = time_per_call(variants[0].1, input, ITERATIONS);
+        print!("{:<12} native {:>10?}", workload, native);
+        for (level, f) in &variants[1..] {
+            let t = time_per_call(*f, input, ITERATIONS);
+            let factor = t.as_secs_f64() / native.as_secs_f64().max(f64::EPSILON);
+            print!(" | {} {:.1}x", level, factor);
+        }
+        println!();
+    }
+}
+
+criterion_group!(benches, bench_levels, slowdown_report);
+criterion_main!(benches);
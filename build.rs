@@ -11,7 +11,7 @@
 use std::env;
 use std::fs::File;
 use std::io::{Write, BufWriter};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
@@ -26,8 +26,8 @@ fn main() {
     // deterministic output and prevent unnecessary downstream recompilation.
     let timestamp = if let Ok(epoch) = env::var("SOURCE_DATE_EPOCH") {
         epoch.parse::<u64>().unwrap_or(0)
-    } else if env::var("ANTICHEAT_BUILD_KEY").is_ok() {
-        // Fixed key mode: use epoch 0 to ensure deterministic output
+    } else if env::var("ANTICHEAT_BUILD_KEY").is_ok() || env::var("ANTICHEAT_SEED_FILE").is_ok() {
+        // Fixed key/seed mode: use epoch 0 to ensure deterministic output
         0
     } else {
         SystemTime::now()
@@ -206,8 +206,12 @@ fn main() {
     writeln!(f, "pub const YIELD_MASK: u64 = 0x{:02x};", yield_mask).unwrap();
     writeln!(f).unwrap();
 
-    // Generate shuffled opcode table
-    let opcode_table = generate_opcode_table(&build_seed);
+    // Generate shuffled opcode table (canonical numbering with stable_opcodes)
+    let opcode_table = if env::var("CARGO_FEATURE_STABLE_OPCODES").is_ok() {
+        stable_opcode_table()
+    } else {
+        generate_opcode_table(&build_seed)
+    };
     write_opcode_table(&mut f, &opcode_table);
 
     // CRITICAL: Write opcode table to shared file for vm-macro to read
@@ -259,11 +263,22 @@ fn main() {
         &magic_bytes, &native_ids, &register_map, &fnv_constants, xor_key, &flag_bits
     );
 
+    // Machine-readable copy of the same data for tooling (opt-in)
+    if env::var("ANTICHEAT_EXPORT_CONFIG").is_ok_and(|v| !v.is_empty() && v != "0") {
+        write_config_json(
+            build_id, timestamp, &customer_id, &watermark, &opcode_table,
+            &magic_bytes, &native_ids, &register_map, &fnv_constants, xor_key, &flag_bits
+        );
+    }
+
     // Rerun conditions
     println!("cargo:rerun-if-env-changed=ANTICHEAT_BUILD_KEY");
+    println!("cargo:rerun-if-env-changed=ANTICHEAT_SEED_FILE");
     println!("cargo:rerun-if-env-changed=ANTICHEAT_PROTECTION_LEVEL");
     println!("cargo:rerun-if-env-changed=ANTICHEAT_CUSTOMER_ID");
     println!("cargo:rerun-if-env-changed=ANTICHEAT_BUILD_SEQ");
+    println!("cargo:rerun-if-env-changed=ANTICHEAT_EXPORT_CONFIG");
+    println!("cargo:rerun-if-env-changed=CARGO_TARGET_DIR");
     println!("cargo:rerun-if-changed=build.rs");
 
     // NOTE: Removed rerun-if-changed for .anticheat_build_seed
@@ -311,6 +326,17 @@ fn generate_yield_mask(build_seed: &[u8; 32]) -> u64 {
 /// Generate build seed from environment or random
 /// The seed is also written to a shared file so vm-macro can read it
 fn generate_build_seed() -> [u8; 32] {
+    // Pinned seed file is authoritative (committed seed for reproducible CI)
+    if let Ok(path) = env::var("ANTICHEAT_SEED_FILE") {
+        println!("cargo:rerun-if-changed={}", path);
+        let contents = std::fs::read(&path)
+            .unwrap_or_else(|e| panic!("ANTICHEAT_SEED_FILE: cannot read {}: {}", path, e));
+        let seed = parse_seed_file(&contents)
+            .unwrap_or_else(|e| panic!("ANTICHEAT_SEED_FILE: malformed seed in {}: {}", path, e));
+        write_shared_seed(&seed);
+        return seed;
+    }
+
     // Check for explicit build key (for reproducible builds)
     if let Ok(key) = env::var("ANTICHEAT_BUILD_KEY") {
         // Use HMAC(build_key, seed_domain)
@@ -320,57 +346,161 @@ fn generate_build_seed() -> [u8; 32] {
         return seed;
     }
 
-    // No explicit key - generate random seed for this build
-    // Each build will have unique opcodes, encryption, etc.
+    // No explicit key - reuse the seed already shared in this target
+    // directory. Cargo runs this script once per profile/feature set, and
+    // every crate in the workspace must agree with vm-macro on one seed.
+    // `cargo clean` (or deleting the file) rotates it.
+    if let Some(seed) = read_shared_seed() {
+        return seed;
+    }
+
+    // First build - generate random seed
+    // Each clean build will have unique opcodes, encryption, etc.
     let seed = generate_random_seed();
     write_shared_seed(&seed);
     seed
 }
 
-/// Write seed to shared location for vm-macro to read
-fn write_shared_seed(seed: &[u8; 32]) {
-    // Write to target directory so vm-macro can find it
-    if let Ok(out_dir) = env::var("OUT_DIR") {
-        // OUT_DIR is like:
-        //   target/debug/build/anticheat-vm-xxx/out (native)
-        //   target/aarch64-linux-android/debug/build/xxx/out (cross-compile)
-        // Find the actual "target" directory (not debug/release inside it)
-        let target_dir = Path::new(&out_dir)
-            .ancestors()
-            .find(|p| p.file_name().is_some_and(|n| n == "target"));
+/// Parse a pinned seed file (`aegis.seed`)
+///
+/// Accepted formats:
+/// - 64 hex characters (surrounding whitespace ignored), same as `.anticheat_build_seed`
+/// - exactly 32 raw bytes
+fn parse_seed_file(contents: &[u8]) -> Result<[u8; 32], String> {
+    let mut seed = [0u8; 32];
 
-        if let Some(target) = target_dir {
-            let seed_file = target.join(".anticheat_build_seed");
-            if let Ok(f) = File::create(&seed_file) {
-                let mut f = BufWriter::new(f);
-                // Write as hex
-                for byte in seed {
-                    let _ = write!(f, "{:02x}", byte);
-                }
+    if let Ok(text) = std::str::from_utf8(contents) {
+        let hex = text.trim();
+        if hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            for (i, byte) in seed.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                    .map_err(|e| e.to_string())?;
             }
+            return Ok(seed);
         }
     }
+
+    if contents.len() == 32 {
+        seed.copy_from_slice(contents);
+        return Ok(seed);
+    }
+
+    Err(format!(
+        "expected 64 hex characters or 32 raw bytes, got {} bytes",
+        contents.len()
+    ))
+}
+
+/// Read a previously written shared seed, if present and well-formed
+fn read_shared_seed() -> Option<[u8; 32]> {
+    let path = resolve_shared_dir()?.join(".anticheat_build_seed");
+    let hex = std::fs::read_to_string(path).ok()?;
+    let hex = hex.trim();
+    if hex.len() != 64 {
+        return None;
+    }
+
+    let mut seed = [0u8; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(seed)
+}
+
+/// Write seed to shared location for vm-macro to read
+fn write_shared_seed(seed: &[u8; 32]) {
+    write_shared_file(".anticheat_build_seed", seed);
 }
 
 /// Write opcode encode table to shared location for vm-macro to read
 /// This ensures macro and runtime use EXACTLY the same opcode mapping
 fn write_shared_opcode_table(table: &OpcodeTable) {
+    write_shared_file(".anticheat_opcode_table", &table.encode);
+}
+
+/// Write bytes as hex to `name` inside the shared directory
+///
+/// A macro that can't read these files would encode bytecode with a
+/// different seed and opcode table than this runtime decodes, so every
+/// failure aborts the build instead of producing mismatched binaries.
+fn write_shared_file(name: &str, bytes: &[u8]) {
+    let Some(dir) = resolve_shared_dir() else {
+        panic!(
+            "aegis_vm: could not determine shared directory for {}; \
+             set CARGO_TARGET_DIR so vm_protect uses the same opcode table",
+            name
+        );
+    };
+
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        panic!("aegis_vm: could not create {}: {}", dir.display(), e);
+    }
+
+    let path = dir.join(name);
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    if let Err(e) = std::fs::write(&path, hex) {
+        panic!("aegis_vm: could not write {}: {}", path.display(), e);
+    }
+}
+
+/// Resolve the directory shared between build.rs and vm-macro
+///
+/// Follows the order vm-macro searches in, so the files land in the first
+/// place it looks:
+/// 1. `CARGO_TARGET_DIR` - a relative path is taken from the `OUT_DIR`
+///    layout, which is where cargo resolved it to
+/// 2. Nearest `OUT_DIR` ancestor named "target"
+/// 3. `target/` in the nearest `CARGO_MANIFEST_DIR` ancestor that has one
+fn resolve_shared_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("CARGO_TARGET_DIR") {
+        let dir = PathBuf::from(dir);
+        if dir.is_absolute() {
+            return Some(dir);
+        }
+        if let Some(dir) = target_dir_from_out_dir() {
+            return Some(dir);
+        }
+    }
+
     if let Ok(out_dir) = env::var("OUT_DIR") {
-        let target_dir = Path::new(&out_dir)
+        let target = Path::new(&out_dir)
             .ancestors()
             .find(|p| p.file_name().is_some_and(|n| n == "target"));
+        if let Some(target) = target {
+            return Some(target.to_path_buf());
+        }
+    }
 
-        if let Some(target) = target_dir {
-            let table_file = target.join(".anticheat_opcode_table");
-            if let Ok(f) = File::create(&table_file) {
-                let mut f = BufWriter::new(f);
-                // Write encode table as hex (256 bytes)
-                for byte in &table.encode {
-                    let _ = write!(f, "{:02x}", byte);
-                }
-            }
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").ok()?);
+    manifest_dir
+        .ancestors()
+        .map(|p| p.join("target"))
+        .find(|p| p.is_dir())
+}
+
+/// Target directory from the `OUT_DIR` layout,
+/// `<target>/[<triple>/]<profile>/build/<pkg>-<hash>/out`
+fn target_dir_from_out_dir() -> Option<PathBuf> {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").ok()?);
+
+    // out -> <pkg>-<hash> -> build -> <profile>
+    let build_dir = out_dir.parent()?.parent()?;
+    if build_dir.file_name().is_none_or(|n| n != "build") {
+        return None;
+    }
+    let profile_dir = build_dir.parent()?;
+    let mut target_dir = profile_dir.parent()?;
+
+    // Skip the <triple> component when building with --target
+    if let Ok(triple) = env::var("TARGET") {
+        if target_dir.file_name().is_some_and(|n| n == triple.as_str()) {
+            target_dir = target_dir.parent()?;
         }
     }
+    Some(target_dir.to_path_buf())
 }
 
 /// Write build history to file for debugging/inspection
@@ -509,6 +639,102 @@ fn write_build_history(
     writeln!(file).ok();
 }
 
+/// Write `aegis_build_config.json` to the shared directory
+///
+/// Structured form of what `build_history.txt` records, for leak tracing
+/// and diffing builds. The build seed is deliberately left out.
+#[allow(clippy::too_many_arguments)]
+fn write_config_json(
+    build_id: u64,
+    timestamp: u64,
+    customer_id: &str,
+    watermark: &[u8; 16],
+    opcode_table: &OpcodeTable,
+    magic_bytes: &[u8; 4],
+    native_ids: &NativeIdMap,
+    register_map: &RegisterMap,
+    fnv_constants: &FnvConstants,
+    xor_key: u8,
+    flag_bits: &FlagBits,
+) {
+    let Some(dir) = resolve_shared_dir() else {
+        println!("cargo:warning=aegis_vm: could not determine shared directory for aegis_build_config.json");
+        return;
+    };
+
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    let list = |bytes: &[u8]| bytes.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(", ");
+
+    let mut json = String::new();
+    json.push_str("{\n");
+    json.push_str("  \"format_version\": 1,\n");
+    json.push_str(&format!("  \"build_id\": \"0x{:016x}\",\n", build_id));
+    json.push_str(&format!("  \"timestamp\": {},\n", timestamp));
+    json.push_str(&format!("  \"customer_id\": {},\n", json_string(customer_id)));
+    json.push_str(&format!("  \"watermark\": \"{}\",\n", hex(watermark)));
+    json.push_str(&format!("  \"magic\": \"{}\",\n", hex(magic_bytes)));
+
+    // Opcodes: module/name, base value, primary encoding and aliases
+    json.push_str("  \"opcodes\": [\n");
+    for (i, &(module, name, base)) in BASE_OPCODES.iter().enumerate() {
+        let aliases = opcode_table.aliases.get(&base).map(|a| list(a)).unwrap_or_default();
+        json.push_str(&format!(
+            "    {{\"module\": \"{}\", \"name\": \"{}\", \"base\": {}, \"encoded\": {}, \"aliases\": [{}]}}{}\n",
+            module, name, base, opcode_table.encode[base as usize], aliases,
+            if i + 1 < BASE_OPCODES.len() { "," } else { "" }
+        ));
+    }
+    json.push_str("  ],\n");
+
+    let natives = [
+        ("CHECK_ROOT", native_ids.check_root),
+        ("CHECK_EMULATOR", native_ids.check_emulator),
+        ("CHECK_HOOKS", native_ids.check_hooks),
+        ("CHECK_DEBUGGER", native_ids.check_debugger),
+        ("CHECK_TAMPER", native_ids.check_tamper),
+        ("GET_TIMESTAMP", native_ids.get_timestamp),
+        ("HASH_FNV1A", native_ids.hash_fnv1a),
+        ("READ_MEMORY", native_ids.read_memory),
+        ("GET_DEVICE_HASH", native_ids.get_device_hash),
+        ("CUSTOM_START", native_ids.custom_start),
+    ];
+    let natives: Vec<String> = natives.iter().map(|(n, id)| format!("\"{}\": {}", n, id)).collect();
+    json.push_str(&format!("  \"native_ids\": {{{}}},\n", natives.join(", ")));
+
+    json.push_str(&format!("  \"register_map\": [{}],\n", list(&register_map.map)));
+    json.push_str(&format!(
+        "  \"fnv\": {{\"basis_64\": \"0x{:016x}\", \"prime_64\": \"0x{:016x}\", \"basis_32\": \"0x{:08x}\", \"prime_32\": \"0x{:08x}\"}},\n",
+        fnv_constants.basis_64, fnv_constants.prime_64, fnv_constants.basis_32, fnv_constants.prime_32
+    ));
+    json.push_str(&format!("  \"xor_key\": {},\n", xor_key));
+    json.push_str(&format!(
+        "  \"flag_bits\": {{\"zero\": {}, \"carry\": {}, \"overflow\": {}, \"sign\": {}}}\n",
+        flag_bits.zero, flag_bits.carry, flag_bits.overflow, flag_bits.sign
+    ));
+    json.push_str("}\n");
+
+    let path = dir.join("aegis_build_config.json");
+    if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, json)) {
+        println!("cargo:warning=aegis_vm: could not write {}: {}", path.display(), e);
+    }
+}
+
+/// Quote and escape a string for JSON output
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 /// Format unix timestamp as human readable string
 fn format_timestamp(timestamp: u64) -> String {
     let secs = timestamp;
@@ -670,10 +896,18 @@ const BASE_OPCODES: &[(& str, &str, u8)] = &[
     ("arithmetic", "ROR", 0x2A),
     ("arithmetic", "INC", 0x2B),
     ("arithmetic", "DEC", 0x2C),
+    ("arithmetic", "MULH", 0x2D),
+    ("arithmetic", "IMULH", 0x2E),
     ("arithmetic", "DIV", 0x46),
     ("arithmetic", "MOD", 0x47),
     ("arithmetic", "IDIV", 0x48),
     ("arithmetic", "IMOD", 0x49),
+    ("arithmetic", "POW", 0x4A),
+    ("arithmetic", "ADC", 0x4B),
+    ("arithmetic", "SBB", 0x4C),
+    ("arithmetic", "BFX", 0x4D),
+    ("arithmetic", "BFI", 0x4E),
+    ("arithmetic", "IMOD_EUCLID", 0x4F),
     // Control flow
     ("control", "CMP", 0x30),
     ("control", "JMP", 0x31),
@@ -685,6 +919,7 @@ const BASE_OPCODES: &[(& str, &str, u8)] = &[
     ("control", "JLE", 0x37),
     ("control", "CALL", 0x38),
     ("control", "RET", 0x39),
+    ("control", "CMOV", 0x3A),
     // Special operations
     ("special", "NOP", 0x40),
     ("special", "NOP_N", 0x41),
@@ -699,6 +934,9 @@ const BASE_OPCODES: &[(& str, &str, u8)] = &[
     ("convert", "TRUNC8", 0x53),
     ("convert", "TRUNC16", 0x54),
     ("convert", "TRUNC32", 0x55),
+    ("convert", "BSWAP16", 0x56),
+    ("convert", "BSWAP32", 0x57),
+    ("convert", "BSWAP", 0x58),
     // Memory operations
     ("memory", "LOAD8", 0x60),
     ("memory", "LOAD16", 0x61),
@@ -720,6 +958,17 @@ const BASE_OPCODES: &[(& str, &str, u8)] = &[
     ("heap", "HEAP_STORE32", 0x78),
     ("heap", "HEAP_STORE64", 0x79),
     ("heap", "HEAP_SIZE", 0x7A),
+    ("heap", "HEAP_STORE8_GROW", 0x7B),
+    ("heap", "HEAP_STORE16_GROW", 0x7C),
+    ("heap", "HEAP_STORE32_GROW", 0x7D),
+    ("heap", "HEAP_STORE64_GROW", 0x7E),
+    ("heap", "HEAP_LOAD16_BE", 0xA0),
+    ("heap", "HEAP_LOAD32_BE", 0xA1),
+    ("heap", "HEAP_LOAD64_BE", 0xA2),
+    ("heap", "HEAP_STORE16_BE", 0xA3),
+    ("heap", "HEAP_STORE32_BE", 0xA4),
+    ("heap", "HEAP_STORE64_BE", 0xA5),
+    ("heap", "HEAP_COPY", 0xA6),
     // Vector operations
     ("vector", "VEC_NEW", 0x80),
     ("vector", "VEC_LEN", 0x81),
@@ -837,6 +1086,18 @@ fn generate_opcode_table(seed: &[u8; 32]) -> OpcodeTable {
     OpcodeTable { encode, decode, aliases }
 }
 
+/// Identity opcode table for the `stable_opcodes` feature
+///
+/// Every opcode keeps its canonical BASE_OPCODES value and there are no
+/// aliases, so tools outside this build can produce bytecode for it.
+fn stable_opcode_table() -> OpcodeTable {
+    let mut encode = [0u8; 256];
+    for (i, slot) in encode.iter_mut().enumerate() {
+        *slot = i as u8;
+    }
+    OpcodeTable { encode, decode: encode, aliases: std::collections::HashMap::new() }
+}
+
 /// Opcode table with handler duplication support
 struct OpcodeTable {
     encode: [u8; 256], // base opcode -> shuffled opcode (for compiler)
@@ -895,6 +1156,49 @@ fn write_opcode_table(f: &mut BufWriter<File>, table: &OpcodeTable) {
             writeln!(f, "];").unwrap();
         }
     }
+
+    // Alias table for verify_opcode_tables()
+    writeln!(f, "    /// All (base, aliases) pairs").unwrap();
+    writeln!(f, "    pub const ALL: &[(u8, &[u8])] = &[").unwrap();
+    for &base in DUPLICATED_OPCODES {
+        if table.aliases.contains_key(&base) {
+            writeln!(f, "        (0x{:02x}, {}_ALIASES),", base, get_name(base)).unwrap();
+        }
+    }
+    writeln!(f, "    ];").unwrap();
+    writeln!(f, "}}").unwrap();
+    writeln!(f).unwrap();
+
+    // Base opcode list for verify_opcode_tables()
+    writeln!(f, "/// All base (unshuffled) opcode values known to this build").unwrap();
+    write!(f, "pub const BASE_OPCODES: [u8; {}] = [", BASE_OPCODES.len()).unwrap();
+    for (i, (_, _, base_val)) in BASE_OPCODES.iter().enumerate() {
+        if i % 16 == 0 {
+            write!(f, "\n    ").unwrap();
+        }
+        write!(f, "0x{:02x}, ", base_val).unwrap();
+    }
+    writeln!(f, "\n];").unwrap();
+    writeln!(f).unwrap();
+
+    writeln!(f, "/// Check OPCODE_ENCODE/OPCODE_DECODE consistency").unwrap();
+    writeln!(f, "///").unwrap();
+    writeln!(f, "/// Every base opcode must round-trip through encode/decode, and every").unwrap();
+    writeln!(f, "/// handler-duplication alias must decode to its base opcode.").unwrap();
+    writeln!(f, "pub fn verify_opcode_tables() -> bool {{").unwrap();
+    writeln!(f, "    for &base in BASE_OPCODES.iter() {{").unwrap();
+    writeln!(f, "        if OPCODE_DECODE[OPCODE_ENCODE[base as usize] as usize] != base {{").unwrap();
+    writeln!(f, "            return false;").unwrap();
+    writeln!(f, "        }}").unwrap();
+    writeln!(f, "    }}").unwrap();
+    writeln!(f, "    for &(base, aliases) in opcode_aliases::ALL.iter() {{").unwrap();
+    writeln!(f, "        for &alias in aliases.iter() {{").unwrap();
+    writeln!(f, "            if alias == OPCODE_ENCODE[base as usize] || OPCODE_DECODE[alias as usize] != base {{").unwrap();
+    writeln!(f, "                return false;").unwrap();
+    writeln!(f, "            }}").unwrap();
+    writeln!(f, "        }}").unwrap();
+    writeln!(f, "    }}").unwrap();
+    writeln!(f, "    true").unwrap();
     writeln!(f, "}}").unwrap();
     writeln!(f).unwrap();
 
@@ -1326,6 +1630,7 @@ fn generate_add_handler(f: &mut BufWriter<File>, variant: u64, rng: &mut [u8; 32
     insert_junk(f, rng, post_junk, 20);
 
     writeln!(f, "    state.set_zero_flag(result);").unwrap();
+    writeln!(f, "    state.set_carry_flag(result < a);").unwrap();
     writeln!(f, "    state.push(result)").unwrap();
     writeln!(f, "}}").unwrap();
     writeln!(f).unwrap();
@@ -1367,6 +1672,7 @@ fn generate_sub_handler(f: &mut BufWriter<File>, variant: u64, rng: &mut [u8; 32
     insert_junk(f, rng, post_junk, 20);
 
     writeln!(f, "    state.set_zero_flag(result);").unwrap();
+    writeln!(f, "    state.set_carry_flag(a < b);").unwrap();
     writeln!(f, "    state.push(result)").unwrap();
     writeln!(f, "}}").unwrap();
     writeln!(f).unwrap();
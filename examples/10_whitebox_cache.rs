@@ -0,0 +1,46 @@
+//! Whitebox Table Cache Benchmark
+//!
+//! Compares key derivation with freshly reconstructed tables against the
+//! process-global cache used by the convenience functions.
+//!
+//! ## Running
+//! ```bash
+//! cargo run --release --example 10_whitebox_cache
+//! ```
+
+use aegis_vm::build_config::whitebox_config::get_bytecode_domain_hash;
+use aegis_vm::whitebox::{derive_bytecode_key, derive_key_from_hash_with_tables, init_tables};
+use std::time::Instant;
+
+const ITERATIONS: u32 = 100;
+
+fn main() {
+    println!("=== Aegis VM - Whitebox Table Cache ===\n");
+
+    let domain_hash = get_bytecode_domain_hash();
+
+    // Fresh tables on every derivation (pre-cache behavior)
+    let start = Instant::now();
+    let mut reference = [0u8; 32];
+    for _ in 0..ITERATIONS {
+        reference = derive_key_from_hash_with_tables(&domain_hash, &init_tables());
+    }
+    let fresh = start.elapsed();
+
+    // Cached tables (first call pays for reconstruction)
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(derive_bytecode_key());
+    }
+    let cached = start.elapsed();
+
+    assert_eq!(derive_bytecode_key(), reference);
+
+    println!("[*] {} derivations", ITERATIONS);
+    println!("    fresh tables:  {:?} ({:?}/call)", fresh, fresh / ITERATIONS);
+    println!("    cached tables: {:?} ({:?}/call)", cached, cached / ITERATIONS);
+    println!(
+        "    speedup:       {:.1}x",
+        fresh.as_secs_f64() / cached.as_secs_f64().max(f64::EPSILON)
+    );
+}
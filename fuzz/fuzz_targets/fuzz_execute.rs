@@ -0,0 +1,20 @@
+//! Raw bytecode fuzz target
+//!
+//! Arbitrary bytes are split into bytecode and input and run through
+//! `engine::execute`. Any panic is a bug: malformed code must surface as
+//! a `VmError`, and the instruction budget bounds every run.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // First byte picks how much of the tail is input
+    let Some((&split, rest)) = data.split_first() else {
+        return;
+    };
+    let input_len = (split as usize).min(rest.len());
+    let (code, input) = rest.split_at(rest.len() - input_len);
+
+    let _ = aegis_vm::engine::execute(code, input);
+});
@@ -0,0 +1,157 @@
+//! Structured fuzz target
+//!
+//! Generates well-formed instruction sequences (valid opcodes, operands of
+//! the right width, branch targets on instruction boundaries) so inputs get
+//! past the decoder and exercise handler logic instead of failing on the
+//! first byte.
+
+#![no_main]
+
+use aegis_vm::build_config::opcodes::{
+    arithmetic, control, convert, exec, heap, native, register, special, stack, string, vector,
+};
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+/// Opcodes without operands
+const SIMPLE: &[u8] = &[
+    stack::DUP, stack::SWAP, stack::DROP,
+    arithmetic::ADD, arithmetic::SUB, arithmetic::MUL,
+    arithmetic::XOR, arithmetic::AND, arithmetic::OR,
+    arithmetic::SHL, arithmetic::SHR, arithmetic::NOT,
+    arithmetic::ROL, arithmetic::ROR, arithmetic::INC, arithmetic::DEC,
+    arithmetic::DIV, arithmetic::MOD, arithmetic::IDIV, arithmetic::IMOD,
+    arithmetic::POW, arithmetic::ADC, arithmetic::SBB, arithmetic::IMOD_EUCLID,
+    arithmetic::MULH, arithmetic::IMULH,
+    control::CMP, control::RET, control::CMOV,
+    special::NOP, special::OPAQUE_TRUE, special::OPAQUE_FALSE,
+    convert::SEXT8, convert::SEXT16, convert::SEXT32,
+    convert::TRUNC8, convert::TRUNC16, convert::TRUNC32,
+    vector::VEC_NEW, vector::VEC_LEN, vector::VEC_CAP,
+    vector::VEC_PUSH, vector::VEC_POP, vector::VEC_GET, vector::VEC_SET,
+    vector::VEC_REPEAT, vector::VEC_CLEAR, vector::VEC_RESERVE,
+    string::STR_NEW, string::STR_LEN, string::STR_PUSH,
+    string::STR_GET, string::STR_SET, string::STR_CMP,
+    string::STR_EQ, string::STR_HASH, string::STR_CONCAT,
+    heap::HEAP_ALLOC, heap::HEAP_FREE,
+    heap::HEAP_LOAD8, heap::HEAP_LOAD16, heap::HEAP_LOAD32, heap::HEAP_LOAD64,
+    heap::HEAP_STORE8, heap::HEAP_STORE16, heap::HEAP_STORE32, heap::HEAP_STORE64,
+    heap::HEAP_SIZE,
+    heap::HEAP_STORE8_GROW, heap::HEAP_STORE16_GROW,
+    heap::HEAP_STORE32_GROW, heap::HEAP_STORE64_GROW,
+    heap::HEAP_LOAD16_BE, heap::HEAP_LOAD32_BE, heap::HEAP_LOAD64_BE,
+    heap::HEAP_STORE16_BE, heap::HEAP_STORE32_BE, heap::HEAP_STORE64_BE,
+    heap::HEAP_COPY,
+    native::INPUT_LEN,
+];
+
+/// Relative branches
+const BRANCHES: &[u8] = &[
+    control::JMP, control::JZ, control::JNZ,
+    control::JGT, control::JLT, control::JGE, control::JLE,
+    control::CALL,
+];
+
+#[derive(Debug, Arbitrary)]
+enum Insn {
+    Simple(u8),
+    PushImm8(u8),
+    PushImm16(u16),
+    PushImm32(u32),
+    PushImm(u64),
+    PushReg(u8),
+    PopReg(u8),
+    MovImm(u8, u64),
+    MovReg(u8, u8),
+    /// Target is an instruction index (wrapped into range)
+    Branch(u8, u16),
+    NativeRead(u16),
+    NativeWrite(u16),
+    NopN(u8),
+    Halt,
+    HaltErr(u8),
+}
+
+#[derive(Debug, Arbitrary)]
+struct Program {
+    insns: Vec<Insn>,
+    input: Vec<u8>,
+}
+
+fn len_of(insn: &Insn) -> usize {
+    match insn {
+        Insn::Simple(_) | Insn::Halt => 1,
+        Insn::PushImm8(_) | Insn::PushReg(_) | Insn::PopReg(_) | Insn::HaltErr(_) => 2,
+        Insn::PushImm16(_) | Insn::MovReg(..) | Insn::Branch(..) |
+        Insn::NativeRead(_) | Insn::NativeWrite(_) => 3,
+        Insn::PushImm32(_) => 5,
+        Insn::PushImm(_) => 9,
+        Insn::MovImm(..) => 10,
+        Insn::NopN(n) => 2 + *n as usize,
+    }
+}
+
+fn assemble(insns: &[Insn]) -> Vec<u8> {
+    // Offset of every instruction plus the end of code
+    let mut offsets = Vec::with_capacity(insns.len() + 1);
+    let mut pos = 0usize;
+    for insn in insns {
+        offsets.push(pos);
+        pos += len_of(insn);
+    }
+    offsets.push(pos);
+
+    let mut code = Vec::with_capacity(pos);
+    for (i, insn) in insns.iter().enumerate() {
+        match *insn {
+            Insn::Simple(op) => code.push(SIMPLE[op as usize % SIMPLE.len()]),
+            Insn::PushImm8(v) => code.extend_from_slice(&[stack::PUSH_IMM8, v]),
+            Insn::PushImm16(v) => {
+                code.push(stack::PUSH_IMM16);
+                code.extend_from_slice(&v.to_le_bytes());
+            }
+            Insn::PushImm32(v) => {
+                code.push(stack::PUSH_IMM32);
+                code.extend_from_slice(&v.to_le_bytes());
+            }
+            Insn::PushImm(v) => {
+                code.push(stack::PUSH_IMM);
+                code.extend_from_slice(&v.to_le_bytes());
+            }
+            Insn::PushReg(r) => code.extend_from_slice(&[stack::PUSH_REG, r]),
+            Insn::PopReg(r) => code.extend_from_slice(&[stack::POP_REG, r]),
+            Insn::MovImm(r, v) => {
+                code.extend_from_slice(&[register::MOV_IMM, r]);
+                code.extend_from_slice(&v.to_le_bytes());
+            }
+            Insn::MovReg(d, s) => code.extend_from_slice(&[register::MOV_REG, d, s]),
+            Insn::Branch(op, target) => {
+                let target = offsets[target as usize % offsets.len()] as isize;
+                let next = (offsets[i] + 3) as isize;
+                let rel = (target - next).clamp(i16::MIN as isize, i16::MAX as isize) as i16;
+                code.push(BRANCHES[op as usize % BRANCHES.len()]);
+                code.extend_from_slice(&rel.to_le_bytes());
+            }
+            Insn::NativeRead(off) => {
+                code.push(native::NATIVE_READ);
+                code.extend_from_slice(&off.to_le_bytes());
+            }
+            Insn::NativeWrite(off) => {
+                code.push(native::NATIVE_WRITE);
+                code.extend_from_slice(&off.to_le_bytes());
+            }
+            Insn::NopN(n) => {
+                code.extend_from_slice(&[special::NOP_N, n]);
+                code.extend(core::iter::repeat_n(exec::HALT, n as usize));
+            }
+            Insn::Halt => code.push(exec::HALT),
+            Insn::HaltErr(e) => code.extend_from_slice(&[exec::HALT_ERR, e]),
+        }
+    }
+    code
+}
+
+fuzz_target!(|program: Program| {
+    let code = assemble(&program.insns);
+    let _ = aegis_vm::engine::execute(&code, &program.input);
+});
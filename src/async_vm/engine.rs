@@ -3,9 +3,10 @@
 //! Async version of the VM execution loop. The async/await syntax
 //! causes Rust to generate a state machine, complicating reverse engineering.
 
-use crate::error::{VmError, VmResult};
+use crate::engine::{fetch, jump_guard};
+use crate::error::VmResult;
 use crate::native::NativeRegistry;
-use crate::state::{VmState, MAX_INSTRUCTIONS};
+use crate::state::VmState;
 use crate::handlers::dispatch::dispatch_indirect;
 
 use super::executor::block_on;
@@ -52,14 +53,10 @@ pub fn execute_async_with_native_table(
 pub async fn run_async_with_native_table(state: &mut VmState<'_>) -> VmResult<()> {
     let empty_registry = NativeRegistry::new();
     let yield_mask = state.get_yield_mask();
+    let mut guard = jump_guard(state);
 
     while !state.halted && state.ip < state.code.len() {
-        state.instruction_count += 1;
-        if state.instruction_count > MAX_INSTRUCTIONS {
-            return Err(VmError::MaxInstructionsExceeded);
-        }
-
-        let opcode = state.read_u8()?;
+        let opcode = fetch(state, &mut guard)?;
         dispatch_indirect(state, opcode, &empty_registry)?;
 
         if (state.instruction_count & yield_mask) == 0 {
@@ -91,16 +88,11 @@ pub async fn run_async_with_natives(
     // Lower bits = more frequent yields = more state transitions
     // Default: 0xFF (yield every 256 instructions)
     let yield_mask = state.get_yield_mask();
+    let mut guard = jump_guard(state);
 
     while !state.halted && state.ip < state.code.len() {
-        // Instruction count limit (DoS protection)
-        state.instruction_count += 1;
-        if state.instruction_count > MAX_INSTRUCTIONS {
-            return Err(VmError::MaxInstructionsExceeded);
-        }
-
-        // Fetch opcode
-        let opcode = state.read_u8()?;
+        // Instruction budget (DoS protection), jump guard, opcode fetch
+        let opcode = fetch(state, &mut guard)?;
 
         // Indirect dispatch (same as sync version)
         dispatch_indirect(state, opcode, registry)?;
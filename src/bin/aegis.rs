@@ -0,0 +1,387 @@
+//! aegis - assemble, disassemble, validate and run VM bytecode
+//!
+//! ```text
+//! aegis asm <file.asm> [-o <file.bc>]
+//! aegis disasm <file.bc>
+//! aegis validate <file.bc>
+//! aegis run <file.bc> [--input <hex>] [--u64 <value>]...
+//! ```
+//!
+//! Bytecode files hold raw encoded bytecode or a plaintext (optionally
+//! compressed) `BytecodePackage` from this build. Opcode values are shuffled
+//! per build, so files only round-trip through the same build of the tool.
+//!
+//! ## Assembly syntax
+//!
+//! One instruction per line, `;` starts a comment, `name:` defines a label.
+//! Operands are separated by spaces or commas and accept decimal, negative
+//! and `0x` hex numbers. Branches (`JMP`, `JZ`, ..., `CALL`) take a label or
+//! a raw i16 offset. With several operands, all but the last are single
+//! bytes and the last fills the rest of the instruction:
+//!
+//! ```text
+//!     MOV_IMM 0, 10        ; R0 = 10
+//!     PUSH_IMM8 0
+//! loop:
+//!     PUSH_REG 0
+//!     ADD
+//!     ...
+//!     JNZ loop
+//!     HALT
+//! ```
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use aegis_vm::{
+    execute_capturing, BytecodeBuilder, BytecodePackage, BytecodeStats,
+    build_config::{BASE_OPCODES, MAGIC, OPCODE_DECODE, OPCODE_ENCODE},
+    bytecode::{instruction_extent, instruction_length},
+    compress::decompress,
+    opcodes::{arithmetic, control, native, opcode_name, register, special},
+};
+
+const USAGE: &str = "usage:
+  aegis asm <file.asm> [-o <file.bc>]
+  aegis disasm <file.bc>
+  aegis validate <file.bc>
+  aegis run <file.bc> [--input <hex>] [--u64 <value>]...";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("asm") => cmd_asm(&args[1..]),
+        Some("disasm") => cmd_disasm(&args[1..]),
+        Some("validate") => cmd_validate(&args[1..]),
+        Some("run") => cmd_run(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("aegis: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+fn cmd_asm(args: &[String]) -> Result<(), String> {
+    let (input, output) = match args {
+        [input] => (PathBuf::from(input), Path::new(input).with_extension("bc")),
+        [input, flag, output] if flag == "-o" => (PathBuf::from(input), PathBuf::from(output)),
+        _ => return Err(USAGE.to_string()),
+    };
+    let source = std::fs::read_to_string(&input).map_err(|e| format!("{}: {e}", input.display()))?;
+    let code = assemble(&source)?;
+    std::fs::write(&output, &code).map_err(|e| format!("{}: {e}", output.display()))?;
+    println!("{}: {} bytes", output.display(), code.len());
+    Ok(())
+}
+
+fn cmd_disasm(args: &[String]) -> Result<(), String> {
+    let [path] = args else { return Err(USAGE.to_string()) };
+    print!("{}", disassemble(&load_code(path)?));
+    Ok(())
+}
+
+fn cmd_validate(args: &[String]) -> Result<(), String> {
+    let [path] = args else { return Err(USAGE.to_string()) };
+    let code = load_code(path)?;
+    let problems = validate(&code);
+    for problem in &problems {
+        println!("{problem}");
+    }
+    if !problems.is_empty() {
+        return Err(format!("{path}: {} problem(s)", problems.len()));
+    }
+    let stats = BytecodeStats::of(&code);
+    println!(
+        "ok: {} bytes, {} instructions, {} distinct opcodes",
+        stats.len,
+        stats.instruction_count(),
+        stats.distinct_opcodes()
+    );
+    Ok(())
+}
+
+fn cmd_run(args: &[String]) -> Result<(), String> {
+    let (path, rest) = args.split_first().ok_or(USAGE)?;
+    let mut input = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(flag) = rest.next() {
+        let value = rest.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        match flag.as_str() {
+            "--input" => input.extend(parse_hex(value)?),
+            "--u64" => input.extend(parse_number(value)?.to_le_bytes()),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let outcome = execute_capturing(&load_code(path)?, &input).map_err(|e| format!("{path}: {e}"))?;
+    println!("result: {} ({:#x})", outcome.result, outcome.result);
+    if !outcome.output.is_empty() {
+        println!("output: {}", to_hex(&outcome.output));
+    }
+    println!("instructions: {}", outcome.instruction_count);
+    Ok(())
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Raw bytecode, or the code of a plaintext package
+fn load_code(path: &str) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    if !data.starts_with(&MAGIC) {
+        return Ok(data);
+    }
+    let package = BytecodePackage::from_bytes(&data).map_err(|e| format!("{path}: bad package ({e:?})"))?;
+    if package.header.is_encrypted() {
+        return Err(format!("{path}: package is encrypted"));
+    }
+    if package.header.is_compressed() {
+        return decompress(&package.code).map_err(|e| format!("{path}: bad compressed code ({e:?})"));
+    }
+    Ok(package.code)
+}
+
+// ============================================================================
+// Instruction shapes
+// ============================================================================
+
+fn is_branch(base: u8) -> bool {
+    matches!(
+        base,
+        control::JMP | control::JZ | control::JNZ | control::JGT | control::JLT | control::JGE
+            | control::JLE | control::CALL
+    )
+}
+
+/// Operands printed as separate bytes rather than one little-endian value
+fn has_byte_operands(base: u8) -> bool {
+    matches!(
+        base,
+        register::MOV_REG | register::LOAD_MEM | register::STORE_MEM
+            | arithmetic::BFX | arithmetic::BFI | native::NATIVE_CALL
+    )
+}
+
+fn base_opcode(mnemonic: &str) -> Option<u8> {
+    BASE_OPCODES.iter().copied().find(|&base| opcode_name(base).eq_ignore_ascii_case(mnemonic))
+}
+
+fn le_value(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |acc, &b| acc << 8 | b as u64)
+}
+
+/// Absolute target of the relative branch at `pos`
+fn branch_target(code: &[u8], pos: usize) -> i64 {
+    let offset = i16::from_le_bytes([code[pos + 1], code[pos + 2]]);
+    pos as i64 + 3 + offset as i64
+}
+
+/// Instruction starts by linear sweep, stopping at the first bad byte
+fn boundaries(code: &[u8]) -> BTreeSet<usize> {
+    let mut starts = BTreeSet::new();
+    let mut ip = 0;
+    while ip < code.len() {
+        let base = OPCODE_DECODE[code[ip] as usize];
+        if instruction_length(base) == 0 || ip + instruction_extent(code, ip) > code.len() {
+            break;
+        }
+        starts.insert(ip);
+        ip += instruction_extent(code, ip);
+    }
+    starts
+}
+
+// ============================================================================
+// Disassembler
+// ============================================================================
+
+fn disassemble(code: &[u8]) -> String {
+    let starts = boundaries(code);
+    let is_target = |target: i64| {
+        usize::try_from(target).is_ok_and(|t| starts.contains(&t) || t == code.len())
+    };
+    let labels: BTreeSet<usize> = starts
+        .iter()
+        .filter(|&&pos| is_branch(OPCODE_DECODE[code[pos] as usize]))
+        .map(|&pos| branch_target(code, pos))
+        .filter(|&target| is_target(target))
+        .map(|target| target as usize)
+        .collect();
+
+    let mut out = String::new();
+    let mut ip = 0;
+    while ip < code.len() {
+        if labels.contains(&ip) {
+            out.push_str(&format!("L_{ip:04x}:\n"));
+        }
+        if !starts.contains(&ip) {
+            // Undecodable or truncated: dump the rest as data
+            out.push_str(&format!("    ; {ip:04x}: data {}\n", to_hex(&code[ip..])));
+            return out;
+        }
+
+        let base = OPCODE_DECODE[code[ip] as usize];
+        let operands = &code[ip + 1..ip + instruction_length(base)];
+        let text = if is_branch(base) && is_target(branch_target(code, ip)) {
+            format!("L_{:04x}", branch_target(code, ip))
+        } else if is_branch(base) {
+            i16::from_le_bytes([operands[0], operands[1]]).to_string()
+        } else if has_byte_operands(base) {
+            operands.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")
+        } else if base == register::MOV_IMM {
+            format!("{}, {:#x}", operands[0], le_value(&operands[1..]))
+        } else if operands.is_empty() {
+            String::new()
+        } else {
+            format!("{:#x}", le_value(operands))
+        };
+        out.push_str(format!("    {:<18}{text}", opcode_name(base)).trim_end());
+        out.push_str(&format!("    ; {ip:04x}\n"));
+        ip += instruction_extent(code, ip);
+    }
+    if labels.contains(&code.len()) {
+        out.push_str(&format!("L_{:04x}:\n", code.len()));
+    }
+    out
+}
+
+// ============================================================================
+// Assembler
+// ============================================================================
+
+fn assemble(source: &str) -> Result<Vec<u8>, String> {
+    let mut asm = BytecodeBuilder::new();
+    for (index, line) in source.lines().enumerate() {
+        assemble_line(&mut asm, line).map_err(|e| format!("line {}: {e}", index + 1))?;
+    }
+    asm.build().map_err(|e| format!("unresolved or duplicate label ({e:?})"))
+}
+
+fn assemble_line(asm: &mut BytecodeBuilder, line: &str) -> Result<(), String> {
+    let mut line = line.split(';').next().unwrap_or("").trim();
+    if let Some((label, rest)) = line.split_once(':') {
+        asm.label(label.trim());
+        line = rest.trim();
+    }
+    let mut tokens = line.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty());
+    let Some(mnemonic) = tokens.next() else { return Ok(()) };
+    let operands: Vec<&str> = tokens.collect();
+
+    let base = base_opcode(mnemonic).ok_or_else(|| format!("unknown instruction `{mnemonic}`"))?;
+    let opcode = OPCODE_ENCODE[base as usize];
+
+    if is_branch(base) {
+        let [target] = operands[..] else { return Err(format!("{mnemonic} takes one operand")) };
+        if let Ok(offset) = parse_number(target) {
+            asm.op(opcode).bytes(&(offset as i16).to_le_bytes());
+            return Ok(());
+        }
+        match base {
+            control::JMP => asm.jmp(target),
+            control::JZ => asm.jz(target),
+            control::JNZ => asm.jnz(target),
+            control::JGT => asm.jgt(target),
+            control::JLT => asm.jlt(target),
+            control::JGE => asm.jge(target),
+            control::JLE => asm.jle(target),
+            _ => asm.call(target),
+        };
+        return Ok(());
+    }
+
+    let width = instruction_length(base) - 1;
+    let values = operands.iter().map(|t| parse_number(t)).collect::<Result<Vec<_>, _>>()?;
+    let mut bytes = Vec::with_capacity(width);
+    match values.split_last() {
+        None if width == 0 => {}
+        Some((&last, leading)) if leading.len() < width => {
+            bytes.extend(leading.iter().map(|&v| v as u8));
+            bytes.extend_from_slice(&last.to_le_bytes()[..width - leading.len()]);
+        }
+        _ => return Err(format!("{mnemonic} takes {width} operand byte(s)")),
+    }
+    asm.op(opcode).bytes(&bytes);
+
+    // NOP_N skips `count` padding bytes after its header
+    if base == special::NOP_N {
+        asm.bytes(&vec![0; bytes[0] as usize]);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Validator
+// ============================================================================
+
+/// Problems found by linear sweep: bad bytes, truncation, stray branches
+///
+/// Meant for plain code: junk behind opaque predicates is reported too.
+fn validate(code: &[u8]) -> Vec<String> {
+    let starts = boundaries(code);
+    let end = starts.last().map_or(0, |&last| last + instruction_extent(code, last));
+    let mut problems = Vec::new();
+
+    if end < code.len() {
+        let base = OPCODE_DECODE[code[end] as usize];
+        if instruction_length(base) == 0 {
+            problems.push(format!("{end:04x}: undecodable byte {:#04x}", code[end]));
+        } else {
+            problems.push(format!("{end:04x}: {} runs past the end of the code", opcode_name(base)));
+        }
+    }
+
+    for &pos in &starts {
+        let base = OPCODE_DECODE[code[pos] as usize];
+        if !is_branch(base) {
+            continue;
+        }
+        let target = branch_target(code, pos);
+        let lands = usize::try_from(target).is_ok_and(|t| starts.contains(&t) || t == code.len());
+        if !lands {
+            problems.push(format!("{pos:04x}: {} target {target} is not an instruction start", opcode_name(base)));
+        }
+    }
+    problems
+}
+
+// ============================================================================
+// Parsing helpers
+// ============================================================================
+
+fn parse_number(text: &str) -> Result<u64, String> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse(),
+    }
+    .map_err(|_| format!("bad number `{text}`"))?;
+    Ok(if negative { value.wrapping_neg() } else { value })
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if !text.len().is_multiple_of(2) {
+        return Err(format!("odd-length hex `{text}`"));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| format!("bad hex `{text}`")))
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
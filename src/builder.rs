@@ -0,0 +1,480 @@
+//! Bytecode Assembler
+//!
+//! [`BytecodeBuilder`] emits instructions with this build's shuffled opcode
+//! values and resolves label-based jumps, so tests and tooling don't have to
+//! hand-count relative offsets.
+//!
+//! # Example
+//!
+//! ```rust
+//! use aegis_vm::{execute, BytecodeBuilder};
+//!
+//! // R0 = 5; sum = 0; loop { sum += R0; R0 -= 1; } while R0 != 0
+//! let mut asm = BytecodeBuilder::new();
+//! asm.mov_imm(0, 5).push_imm8(0)
+//!     .label("loop")
+//!     .push_reg(0).add()
+//!     .push_reg(0).dec().pop_reg(0)
+//!     .push_reg(0).push_imm8(0).cmp().drop().drop()
+//!     .jnz("loop")
+//!     .halt();
+//!
+//! assert_eq!(execute(&asm.build().unwrap(), &[]).unwrap(), 15);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::build_config::opcodes::{arithmetic, control, convert, exec, heap, native, register, special, stack, string};
+use crate::error::{VmError, VmResult};
+
+/// Label-aware bytecode assembler
+///
+/// Every method appends one instruction and returns `&mut Self` for
+/// chaining. Branch methods take a label name that may be defined before
+/// or after the branch; offsets are patched in [`build`](Self::build).
+#[derive(Clone, Debug, Default)]
+pub struct BytecodeBuilder {
+    code: Vec<u8>,
+    /// Label name -> code offset
+    labels: Vec<(String, usize)>,
+    /// Offset of a branch operand -> target label
+    fixups: Vec<(usize, String)>,
+    /// Emit absolute u16 branch targets instead of i16 relative offsets
+    absolute: bool,
+}
+
+impl BytecodeBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emit branch operands as absolute code offsets
+    ///
+    /// The result must run with `VmState::absolute_jumps` set (see
+    /// `execute_absolute`) and be packaged with `BytecodeFlags::AbsoluteJumps`.
+    pub fn absolute_jumps(&mut self, enabled: bool) -> &mut Self {
+        self.absolute = enabled;
+        self
+    }
+
+    /// Current code length (offset of the next instruction)
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Check if nothing has been emitted yet
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+
+    /// Resolve labels and return the finished bytecode
+    ///
+    /// Fails with `InvalidJumpTarget` for an undefined label or an offset
+    /// that doesn't fit in an i16 (u16 in absolute mode), and
+    /// `InvalidBytecode` for a label that was defined twice.
+    pub fn build(&self) -> VmResult<Vec<u8>> {
+        for (i, (name, _)) in self.labels.iter().enumerate() {
+            if self.labels[..i].iter().any(|(other, _)| other == name) {
+                return Err(VmError::InvalidBytecode);
+            }
+        }
+
+        let mut code = self.code.clone();
+        for (pos, label) in &self.fixups {
+            let target = self
+                .labels
+                .iter()
+                .find(|(name, _)| name == label)
+                .map(|&(_, offset)| offset)
+                .ok_or(VmError::InvalidJumpTarget)?;
+            let operand = if self.absolute {
+                u16::try_from(target).map_err(|_| VmError::InvalidJumpTarget)?
+            } else {
+                let next = *pos as isize + 2;
+                i16::try_from(target as isize - next).map_err(|_| VmError::InvalidJumpTarget)? as u16
+            };
+            code[*pos..*pos + 2].copy_from_slice(&operand.to_le_bytes());
+        }
+        Ok(code)
+    }
+
+    // ========== Raw emission ==========
+
+    /// Append raw bytes (opcodes without a dedicated method, data)
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.code.extend_from_slice(bytes);
+        self
+    }
+
+    /// Append a single operand-less opcode
+    pub fn op(&mut self, opcode: u8) -> &mut Self {
+        self.code.push(opcode);
+        self
+    }
+
+    /// Define a label at the current offset
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        self.labels.push((String::from(name), self.code.len()));
+        self
+    }
+
+    fn branch(&mut self, opcode: u8, label: &str) -> &mut Self {
+        self.code.push(opcode);
+        self.fixups.push((self.code.len(), String::from(label)));
+        self.code.extend_from_slice(&[0, 0]);
+        self
+    }
+
+    // ========== Stack ==========
+
+    /// Push a value using the shortest PUSH_IMM* encoding
+    pub fn push(&mut self, value: u64) -> &mut Self {
+        if let Ok(v) = u8::try_from(value) {
+            self.push_imm8(v)
+        } else if let Ok(v) = u16::try_from(value) {
+            self.push_imm16(v)
+        } else if let Ok(v) = u32::try_from(value) {
+            self.push_imm32(v)
+        } else {
+            self.push_imm(value)
+        }
+    }
+
+    /// PUSH_IMM: push a u64
+    pub fn push_imm(&mut self, value: u64) -> &mut Self {
+        self.op(stack::PUSH_IMM).bytes(&value.to_le_bytes())
+    }
+
+    /// PUSH_IMM8: push a u8
+    pub fn push_imm8(&mut self, value: u8) -> &mut Self {
+        self.bytes(&[stack::PUSH_IMM8, value])
+    }
+
+    /// PUSH_IMM16: push a u16
+    pub fn push_imm16(&mut self, value: u16) -> &mut Self {
+        self.op(stack::PUSH_IMM16).bytes(&value.to_le_bytes())
+    }
+
+    /// PUSH_IMM32: push a u32
+    pub fn push_imm32(&mut self, value: u32) -> &mut Self {
+        self.op(stack::PUSH_IMM32).bytes(&value.to_le_bytes())
+    }
+
+    /// PUSH_REG: push a register
+    pub fn push_reg(&mut self, reg: u8) -> &mut Self {
+        self.bytes(&[stack::PUSH_REG, reg])
+    }
+
+    /// POP_REG: pop into a register
+    pub fn pop_reg(&mut self, reg: u8) -> &mut Self {
+        self.bytes(&[stack::POP_REG, reg])
+    }
+
+    /// DUP
+    pub fn dup(&mut self) -> &mut Self {
+        self.op(stack::DUP)
+    }
+
+    /// SWAP
+    pub fn swap(&mut self) -> &mut Self {
+        self.op(stack::SWAP)
+    }
+
+    /// DROP
+    pub fn drop(&mut self) -> &mut Self {
+        self.op(stack::DROP)
+    }
+
+    // ========== Registers ==========
+
+    /// MOV_IMM: load a u64 into a register
+    pub fn mov_imm(&mut self, reg: u8, value: u64) -> &mut Self {
+        self.bytes(&[register::MOV_IMM, reg]).bytes(&value.to_le_bytes())
+    }
+
+    /// MOV_REG: copy `src` into `dst`
+    pub fn mov_reg(&mut self, dst: u8, src: u8) -> &mut Self {
+        self.bytes(&[register::MOV_REG, dst, src])
+    }
+
+    // ========== Arithmetic ==========
+
+    /// ADD
+    pub fn add(&mut self) -> &mut Self {
+        self.op(arithmetic::ADD)
+    }
+
+    /// SUB
+    pub fn sub(&mut self) -> &mut Self {
+        self.op(arithmetic::SUB)
+    }
+
+    /// MUL
+    pub fn mul(&mut self) -> &mut Self {
+        self.op(arithmetic::MUL)
+    }
+
+    /// MULH (high 64 bits of the unsigned 128-bit product)
+    pub fn mulh(&mut self) -> &mut Self {
+        self.op(arithmetic::MULH)
+    }
+
+    /// IMULH (high 64 bits of the signed 128-bit product)
+    pub fn imulh(&mut self) -> &mut Self {
+        self.op(arithmetic::IMULH)
+    }
+
+    /// DIV (unsigned, x / 0 = 0)
+    pub fn div(&mut self) -> &mut Self {
+        self.op(arithmetic::DIV)
+    }
+
+    /// MOD (unsigned remainder)
+    pub fn rem(&mut self) -> &mut Self {
+        self.op(arithmetic::MOD)
+    }
+
+    /// IDIV (signed)
+    pub fn idiv(&mut self) -> &mut Self {
+        self.op(arithmetic::IDIV)
+    }
+
+    /// IMOD (signed, truncated)
+    pub fn imod(&mut self) -> &mut Self {
+        self.op(arithmetic::IMOD)
+    }
+
+    /// XOR
+    pub fn xor(&mut self) -> &mut Self {
+        self.op(arithmetic::XOR)
+    }
+
+    /// AND
+    pub fn and(&mut self) -> &mut Self {
+        self.op(arithmetic::AND)
+    }
+
+    /// OR
+    pub fn or(&mut self) -> &mut Self {
+        self.op(arithmetic::OR)
+    }
+
+    /// NOT
+    pub fn not(&mut self) -> &mut Self {
+        self.op(arithmetic::NOT)
+    }
+
+    /// SHL
+    pub fn shl(&mut self) -> &mut Self {
+        self.op(arithmetic::SHL)
+    }
+
+    /// SHR
+    pub fn shr(&mut self) -> &mut Self {
+        self.op(arithmetic::SHR)
+    }
+
+    /// INC
+    pub fn inc(&mut self) -> &mut Self {
+        self.op(arithmetic::INC)
+    }
+
+    /// DEC
+    pub fn dec(&mut self) -> &mut Self {
+        self.op(arithmetic::DEC)
+    }
+
+    // ========== Conversion ==========
+
+    /// TRUNC8: keep the low 8 bits
+    pub fn trunc8(&mut self) -> &mut Self {
+        self.op(convert::TRUNC8)
+    }
+
+    /// TRUNC16: keep the low 16 bits
+    pub fn trunc16(&mut self) -> &mut Self {
+        self.op(convert::TRUNC16)
+    }
+
+    /// TRUNC32: keep the low 32 bits
+    pub fn trunc32(&mut self) -> &mut Self {
+        self.op(convert::TRUNC32)
+    }
+
+    /// SEXT8: sign-extend the low 8 bits
+    pub fn sext8(&mut self) -> &mut Self {
+        self.op(convert::SEXT8)
+    }
+
+    /// SEXT16: sign-extend the low 16 bits
+    pub fn sext16(&mut self) -> &mut Self {
+        self.op(convert::SEXT16)
+    }
+
+    /// SEXT32: sign-extend the low 32 bits
+    pub fn sext32(&mut self) -> &mut Self {
+        self.op(convert::SEXT32)
+    }
+
+    /// BSWAP16: reverse the bytes of the low 16 bits
+    pub fn bswap16(&mut self) -> &mut Self {
+        self.op(convert::BSWAP16)
+    }
+
+    /// BSWAP32: reverse the bytes of the low 32 bits
+    pub fn bswap32(&mut self) -> &mut Self {
+        self.op(convert::BSWAP32)
+    }
+
+    /// BSWAP: reverse all 8 bytes
+    pub fn bswap(&mut self) -> &mut Self {
+        self.op(convert::BSWAP)
+    }
+
+    // ========== Control flow ==========
+
+    /// CMP: set flags from the top two values (left on the stack)
+    pub fn cmp(&mut self) -> &mut Self {
+        self.op(control::CMP)
+    }
+
+    /// JMP to `label`
+    pub fn jmp(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JMP, label)
+    }
+
+    /// JZ to `label`
+    pub fn jz(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JZ, label)
+    }
+
+    /// JNZ to `label`
+    pub fn jnz(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JNZ, label)
+    }
+
+    /// JGT to `label`
+    pub fn jgt(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JGT, label)
+    }
+
+    /// JLT to `label`
+    pub fn jlt(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JLT, label)
+    }
+
+    /// JGE to `label`
+    pub fn jge(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JGE, label)
+    }
+
+    /// JLE to `label`
+    pub fn jle(&mut self, label: &str) -> &mut Self {
+        self.branch(control::JLE, label)
+    }
+
+    /// CALL the subroutine at `label`
+    pub fn call(&mut self, label: &str) -> &mut Self {
+        self.branch(control::CALL, label)
+    }
+
+    /// CMOV: [cond, a, b] -> [cond != 0 ? a : b]
+    pub fn cmov(&mut self) -> &mut Self {
+        self.op(control::CMOV)
+    }
+
+    /// RET
+    pub fn ret(&mut self) -> &mut Self {
+        self.op(control::RET)
+    }
+
+    /// NOP
+    pub fn nop(&mut self) -> &mut Self {
+        self.op(special::NOP)
+    }
+
+    // ========== Heap ==========
+
+    /// HEAP_ALLOC: [size] -> [address]
+    pub fn heap_alloc(&mut self) -> &mut Self {
+        self.op(heap::HEAP_ALLOC)
+    }
+
+    /// HEAP_FREE: [address] -> []
+    pub fn heap_free(&mut self) -> &mut Self {
+        self.op(heap::HEAP_FREE)
+    }
+
+    /// HEAP_LOAD64: [address] -> [value]
+    pub fn heap_load64(&mut self) -> &mut Self {
+        self.op(heap::HEAP_LOAD64)
+    }
+
+    /// HEAP_STORE64: [address, value] -> []
+    pub fn heap_store64(&mut self) -> &mut Self {
+        self.op(heap::HEAP_STORE64)
+    }
+
+    /// HEAP_STORE64_BE: [address, value] -> [], most significant byte first
+    pub fn heap_store64_be(&mut self) -> &mut Self {
+        self.op(heap::HEAP_STORE64_BE)
+    }
+
+    // ========== Strings ==========
+
+    /// STR_NEW: [capacity] -> [str_addr]
+    pub fn str_new(&mut self) -> &mut Self {
+        self.op(string::STR_NEW)
+    }
+
+    /// STR_LEN: [str_addr] -> [length]
+    pub fn str_len(&mut self) -> &mut Self {
+        self.op(string::STR_LEN)
+    }
+
+    /// STR_PUSH: [str_addr, byte] -> []
+    pub fn str_push(&mut self) -> &mut Self {
+        self.op(string::STR_PUSH)
+    }
+
+    /// STR_GET: [str_addr, index] -> [byte]
+    pub fn str_get(&mut self) -> &mut Self {
+        self.op(string::STR_GET)
+    }
+
+    // ========== I/O and natives ==========
+
+    /// NATIVE_READ: push the u64 at `offset` in the input
+    pub fn native_read(&mut self, offset: u16) -> &mut Self {
+        self.op(native::NATIVE_READ).bytes(&offset.to_le_bytes())
+    }
+
+    /// NATIVE_WRITE: append the low byte of the top value to the output
+    pub fn native_write(&mut self, offset: u16) -> &mut Self {
+        self.op(native::NATIVE_WRITE).bytes(&offset.to_le_bytes())
+    }
+
+    /// NATIVE_CALL: call native `id` with `arg_count` stack arguments
+    pub fn native_call(&mut self, id: u8, arg_count: u8) -> &mut Self {
+        self.bytes(&[native::NATIVE_CALL, id, arg_count])
+    }
+
+    /// INPUT_LEN: push the input length
+    pub fn input_len(&mut self) -> &mut Self {
+        self.op(native::INPUT_LEN)
+    }
+
+    // ========== Execution ==========
+
+    /// HALT: stop with the top value as result
+    pub fn halt(&mut self) -> &mut Self {
+        self.op(exec::HALT)
+    }
+
+    /// HALT_ERR: stop with an error code
+    pub fn halt_err(&mut self, code: u8) -> &mut Self {
+        self.bytes(&[exec::HALT_ERR, code])
+    }
+}
@@ -4,9 +4,9 @@
 //!
 //! ```text
 //! +------------------+------------------+------------------+
-//! | Magic (4 bytes)  | Version (2)      | Flags (2)        |
+//! | Magic (4 bytes)  | Version (2)      | ABI (2)          |
 //! +------------------+------------------+------------------+
-//! | Build ID (8)     | Timestamp (8)    |
+//! | Flags (2)        | Build ID (8)     | Timestamp (8)    |
 //! +------------------+------------------+------------------+
 //! | Nonce (12)       | Tag (16)         |
 //! +------------------+------------------+
@@ -20,15 +20,31 @@
 
 use crate::error::{VmError, VmResult};
 use crate::build_config;
+use crate::compress;
+use crate::crypto::CryptoContext;
+#[cfg(any(feature = "whitebox", feature = "whitebox_lite"))]
+use crate::crypto;
+#[cfg(any(feature = "whitebox", feature = "whitebox_lite"))]
+use crate::whitebox::WhiteboxCryptoContext;
+use crate::opcodes::{arithmetic, control, convert, exec, heap, memory, native, register, special, stack, string, vector};
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{string::String, vec, vec::Vec};
 
 /// Magic bytes for bytecode identification (randomized per build)
 pub use build_config::MAGIC;
 
 /// Current bytecode format version
-pub const FORMAT_VERSION: u16 = 1;
+///
+/// Version 2 added the ABI field; version 1 packages have none and are
+/// rejected with `AbiMismatch`.
+pub const FORMAT_VERSION: u16 = 2;
+
+/// VM ABI version: the base opcode set and its semantics
+///
+/// Bump whenever an opcode is added, removed or changes meaning, so code
+/// built against another opcode set is rejected instead of misread.
+pub const VM_ABI_VERSION: u16 = 1;
 
 /// Bytecode header flags
 #[repr(u16)]
@@ -44,6 +60,12 @@ pub enum BytecodeFlags {
     HasTimingChecks = 1 << 2,
     /// Paranoid mode (all protections)
     Paranoid = 1 << 3,
+    /// Code is a multi-entry bundle (see `BytecodeBundle`)
+    Bundle = 1 << 4,
+    /// Branch operands are absolute code offsets (see `VmState::absolute_jumps`)
+    AbsoluteJumps = 1 << 5,
+    /// Code was compressed before encryption (see `compress`)
+    Compressed = 1 << 6,
 }
 
 /// Protection level for bytecode generation
@@ -90,6 +112,8 @@ pub struct BytecodeHeader {
     pub magic: [u8; 4],
     /// Format version
     pub version: u16,
+    /// VM ABI version the code was built for
+    pub abi: u16,
     /// Protection flags
     pub flags: u16,
     /// Unique build identifier
@@ -106,13 +130,14 @@ pub struct BytecodeHeader {
 
 impl BytecodeHeader {
     /// Header size in bytes
-    pub const SIZE: usize = 4 + 2 + 2 + 8 + 8 + 12 + 16 + 4; // 56 bytes
+    pub const SIZE: usize = 4 + 2 + 2 + 2 + 8 + 8 + 12 + 16 + 4; // 58 bytes
 
     /// Create a new header
     pub fn new(build_id: u64, timestamp: u64, flags: u16) -> Self {
         Self {
             magic: MAGIC,
             version: FORMAT_VERSION,
+            abi: VM_ABI_VERSION,
             flags,
             build_id,
             timestamp,
@@ -135,6 +160,10 @@ impl BytecodeHeader {
         buf[offset..offset + 2].copy_from_slice(&self.version.to_le_bytes());
         offset += 2;
 
+        // ABI
+        buf[offset..offset + 2].copy_from_slice(&self.abi.to_le_bytes());
+        offset += 2;
+
         // Flags
         buf[offset..offset + 2].copy_from_slice(&self.flags.to_le_bytes());
         offset += 2;
@@ -162,6 +191,8 @@ impl BytecodeHeader {
     }
 
     /// Parse header from bytes
+    ///
+    /// Fails with `AbiMismatch` if the code was built for another VM ABI.
     pub fn from_bytes(data: &[u8]) -> VmResult<Self> {
         if data.len() < Self::SIZE {
             return Err(VmError::InvalidBytecode);
@@ -182,6 +213,16 @@ impl BytecodeHeader {
         if version > FORMAT_VERSION {
             return Err(VmError::InvalidBytecode);
         }
+        if version < FORMAT_VERSION {
+            return Err(VmError::AbiMismatch);
+        }
+        offset += 2;
+
+        // ABI
+        let abi = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        if abi != VM_ABI_VERSION {
+            return Err(VmError::AbiMismatch);
+        }
         offset += 2;
 
         // Flags
@@ -235,6 +276,7 @@ impl BytecodeHeader {
         Ok(Self {
             magic,
             version,
+            abi,
             flags,
             build_id,
             timestamp,
@@ -244,6 +286,14 @@ impl BytecodeHeader {
         })
     }
 
+    /// Check that the code was built for this VM's ABI
+    pub fn check_abi(&self) -> VmResult<()> {
+        if self.abi != VM_ABI_VERSION {
+            return Err(VmError::AbiMismatch);
+        }
+        Ok(())
+    }
+
     /// Check if bytecode is encrypted
     pub fn is_encrypted(&self) -> bool {
         self.flags & BytecodeFlags::Encrypted as u16 != 0
@@ -263,6 +313,16 @@ impl BytecodeHeader {
     pub fn is_paranoid(&self) -> bool {
         self.flags & BytecodeFlags::Paranoid as u16 != 0
     }
+
+    /// Check if branch operands are absolute code offsets
+    pub fn has_absolute_jumps(&self) -> bool {
+        self.flags & BytecodeFlags::AbsoluteJumps as u16 != 0
+    }
+
+    /// Check if the code is compressed
+    pub fn is_compressed(&self) -> bool {
+        self.flags & BytecodeFlags::Compressed as u16 != 0
+    }
 }
 
 /// Complete bytecode package (header + encrypted code)
@@ -282,6 +342,61 @@ impl BytecodePackage {
         Self { header, code }
     }
 
+    /// Create a package with compressed plaintext code
+    pub fn new_compressed(code: &[u8], build_id: u64) -> Self {
+        let mut package = Self::new_plaintext(compress::compress(code), build_id);
+        package.header.flags = BytecodeFlags::Compressed as u16;
+        package
+    }
+
+    /// Compress, then encrypt code into a package
+    pub fn seal_compressed(code: &[u8], ctx: &mut CryptoContext, timestamp: u64) -> VmResult<Self> {
+        let (code, nonce, tag) = ctx.encrypt(&compress::compress(code))?;
+        let flags = BytecodeFlags::Encrypted as u16 | BytecodeFlags::Compressed as u16;
+        let mut header = BytecodeHeader::new(ctx.build_id, timestamp, flags);
+        header.nonce = nonce;
+        header.tag = tag;
+        header.code_len = code.len() as u32;
+        Ok(Self { header, code })
+    }
+
+    /// Executable code: decrypted and decompressed as the header flags say
+    pub fn unpack(&self, ctx: &CryptoContext) -> VmResult<Vec<u8>> {
+        let code = if self.header.is_encrypted() {
+            ctx.decrypt(&self.code, &self.header.nonce, &self.header.tag)?
+        } else {
+            self.code.clone()
+        };
+        if self.header.is_compressed() {
+            compress::decompress(&code)
+        } else {
+            Ok(code)
+        }
+    }
+
+    /// Re-encrypt the code from `old`'s bytecode key to `new_key`
+    ///
+    /// For rotating keys on stored packages: the ciphertext is transformed
+    /// in place and the plaintext is never materialized, in memory or on
+    /// disk. The nonce and flags are kept and only the tag changes. Fails
+    /// with `InvalidBytecode` for an unencrypted package and
+    /// `DecryptionFailed` (leaving the package unchanged) if it was not
+    /// sealed under `old`'s key.
+    #[cfg(any(feature = "whitebox", feature = "whitebox_lite"))]
+    pub fn rekey(&mut self, old: &WhiteboxCryptoContext, new_key: &[u8; 32]) -> VmResult<()> {
+        if !self.header.is_encrypted() {
+            return Err(VmError::InvalidBytecode);
+        }
+        self.header.tag = crypto::rekey_bytecode(
+            old.bytecode_key(),
+            new_key,
+            &self.header.nonce,
+            &mut self.code,
+            &self.header.tag,
+        )?;
+        Ok(())
+    }
+
     /// Serialize to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(BytecodeHeader::SIZE + self.code.len());
@@ -305,6 +420,165 @@ impl BytecodePackage {
     }
 }
 
+/// Named entry point into a bundle's shared code blob
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// Entry name (at most 255 bytes of UTF-8)
+    pub name: String,
+    /// Start of the routine in the code blob
+    pub offset: u32,
+    /// Routine length in bytes
+    pub len: u32,
+}
+
+/// Several protected routines shipped as one package
+///
+/// Routine code is concatenated into a single blob, so the bundle is
+/// sealed with one key and nonce and authenticated by one GCM tag instead
+/// of paying for a header and tag per function. The entry table travels
+/// inside the encrypted payload, so entry names are not visible at rest.
+///
+/// Payload layout (before encryption):
+///
+/// ```text
+/// [entry_count u16] { [name_len u8] [name] [offset u32] [len u32] }* [code]
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BytecodeBundle {
+    entries: Vec<BundleEntry>,
+    code: Vec<u8>,
+}
+
+impl BytecodeBundle {
+    /// Create an empty bundle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a routine under `name`
+    ///
+    /// Each routine runs in isolation (relative jumps stay inside it), so it
+    /// must end in HALT/HALT_ERR like a standalone blob. Returns
+    /// `InvalidBytecode` for a duplicate or over-long name, or once the
+    /// bundle holds `u16::MAX` entries (the payload's entry count).
+    pub fn add_entry(&mut self, name: &str, code: &[u8]) -> VmResult<()> {
+        if name.len() > u8::MAX as usize || self.entry(name).is_some() {
+            return Err(VmError::InvalidBytecode);
+        }
+        u16::try_from(self.entries.len() + 1).map_err(|_| VmError::InvalidBytecode)?;
+        let offset = u32::try_from(self.code.len()).map_err(|_| VmError::InvalidBytecode)?;
+        let len = u32::try_from(code.len()).map_err(|_| VmError::InvalidBytecode)?;
+        self.entries.push(BundleEntry { name: String::from(name), offset, len });
+        self.code.extend_from_slice(code);
+        Ok(())
+    }
+
+    /// Entry table in insertion order
+    pub fn entries(&self) -> &[BundleEntry] {
+        &self.entries
+    }
+
+    /// Look up an entry by name
+    pub fn entry(&self, name: &str) -> Option<&BundleEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Code of the named routine
+    pub fn entry_code(&self, name: &str) -> Option<&[u8]> {
+        let entry = self.entry(name)?;
+        let start = entry.offset as usize;
+        self.code.get(start..start + entry.len as usize)
+    }
+
+    /// Run the named routine, `InvalidBytecode` if there is no such entry
+    pub fn execute_entry(&self, name: &str, input: &[u8]) -> VmResult<u64> {
+        let code = self.entry_code(name).ok_or(VmError::InvalidBytecode)?;
+        crate::engine::execute(code, input)
+    }
+
+    /// Serialize entry table and code into one payload
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(2 + self.entries.len() * 16 + self.code.len());
+        // add_entry keeps the count within u16
+        buf.extend_from_slice(&(self.entries.len() as u16).to_le_bytes());
+        for entry in &self.entries {
+            buf.push(entry.name.len() as u8);
+            buf.extend_from_slice(entry.name.as_bytes());
+            buf.extend_from_slice(&entry.offset.to_le_bytes());
+            buf.extend_from_slice(&entry.len.to_le_bytes());
+        }
+        buf.extend_from_slice(&self.code);
+        buf
+    }
+
+    /// Parse a payload produced by `to_payload`
+    pub fn from_payload(data: &[u8]) -> VmResult<Self> {
+        fn take<'d>(data: &'d [u8], pos: &mut usize, n: usize) -> VmResult<&'d [u8]> {
+            let bytes = data.get(*pos..*pos + n).ok_or(VmError::InvalidBytecode)?;
+            *pos += n;
+            Ok(bytes)
+        }
+        fn take_u32(data: &[u8], pos: &mut usize) -> VmResult<u32> {
+            let bytes = take(data, pos, 4)?;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        }
+
+        let mut pos = 0;
+        let count = take(data, &mut pos, 2)?;
+        let count = u16::from_le_bytes([count[0], count[1]]) as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name_len = take(data, &mut pos, 1)?[0] as usize;
+            let name = core::str::from_utf8(take(data, &mut pos, name_len)?)
+                .map_err(|_| VmError::InvalidBytecode)?;
+            let offset = take_u32(data, &mut pos)?;
+            let len = take_u32(data, &mut pos)?;
+            entries.push(BundleEntry { name: String::from(name), offset, len });
+        }
+
+        let code = data[pos..].to_vec();
+        for entry in &entries {
+            let end = (entry.offset as usize).checked_add(entry.len as usize);
+            if end.is_none_or(|end| end > code.len()) {
+                return Err(VmError::InvalidBytecode);
+            }
+        }
+        Ok(Self { entries, code })
+    }
+
+    /// Package the bundle without encryption (debug mode)
+    pub fn to_plaintext_package(&self, build_id: u64) -> BytecodePackage {
+        let mut package = BytecodePackage::new_plaintext(self.to_payload(), build_id);
+        package.header.flags = BytecodeFlags::Bundle as u16;
+        package
+    }
+
+    /// Encrypt the whole bundle into one package
+    pub fn seal(&self, ctx: &mut CryptoContext, timestamp: u64) -> VmResult<BytecodePackage> {
+        let (code, nonce, tag) = ctx.encrypt(&self.to_payload())?;
+        let flags = BytecodeFlags::Encrypted as u16 | BytecodeFlags::Bundle as u16;
+        let mut header = BytecodeHeader::new(ctx.build_id, timestamp, flags);
+        header.nonce = nonce;
+        header.tag = tag;
+        header.code_len = code.len() as u32;
+        Ok(BytecodePackage { header, code })
+    }
+
+    /// Decrypt (once, for every entry) and parse a bundle package
+    pub fn open(package: &BytecodePackage, ctx: &CryptoContext) -> VmResult<Self> {
+        if package.header.flags & BytecodeFlags::Bundle as u16 == 0 {
+            return Err(VmError::InvalidBytecode);
+        }
+        if package.header.is_encrypted() {
+            let payload = ctx.decrypt(&package.code, &package.header.nonce, &package.header.tag)?;
+            Self::from_payload(&payload)
+        } else {
+            Self::from_payload(&package.code)
+        }
+    }
+}
+
 /// Build information for watermarking
 #[derive(Clone, Debug)]
 pub struct BuildInfo {
@@ -329,3 +603,266 @@ impl BuildInfo {
         }
     }
 }
+
+/// Total instruction length (opcode + operands) for a base opcode
+///
+/// The one authoritative width table: the SMC engine, the bytecode passes
+/// and anything else that walks bytecode go through it. `NOP_N` reports
+/// its 2-byte header; the padding bytes it skips are not included.
+/// Returns 0 for bytes that are not a base opcode.
+pub fn instruction_length(base_opcode: u8) -> usize {
+    match base_opcode {
+        // 1-byte instructions (opcode only)
+        stack::DUP | stack::SWAP | stack::DROP |
+        arithmetic::ADD | arithmetic::SUB | arithmetic::MUL |
+        arithmetic::XOR | arithmetic::AND | arithmetic::OR |
+        arithmetic::SHL | arithmetic::SHR | arithmetic::NOT |
+        arithmetic::ROL | arithmetic::ROR | arithmetic::INC | arithmetic::DEC |
+        arithmetic::DIV | arithmetic::MOD | arithmetic::IDIV | arithmetic::IMOD | arithmetic::POW |
+        arithmetic::ADC | arithmetic::SBB | arithmetic::IMOD_EUCLID | arithmetic::MULH | arithmetic::IMULH |
+        control::CMP | control::RET | control::CMOV |
+        special::NOP | special::OPAQUE_TRUE | special::OPAQUE_FALSE | special::TIMING_CHECK |
+        convert::SEXT8 | convert::SEXT16 | convert::SEXT32 |
+        convert::TRUNC8 | convert::TRUNC16 | convert::TRUNC32 |
+        convert::BSWAP16 | convert::BSWAP32 | convert::BSWAP |
+        vector::VEC_NEW | vector::VEC_LEN | vector::VEC_CAP |
+        vector::VEC_PUSH | vector::VEC_POP | vector::VEC_GET | vector::VEC_SET |
+        vector::VEC_REPEAT | vector::VEC_CLEAR | vector::VEC_RESERVE |
+        string::STR_NEW | string::STR_LEN | string::STR_PUSH |
+        string::STR_GET | string::STR_SET | string::STR_CMP |
+        string::STR_EQ | string::STR_HASH | string::STR_CONCAT |
+        heap::HEAP_ALLOC | heap::HEAP_FREE |
+        heap::HEAP_LOAD8 | heap::HEAP_LOAD16 | heap::HEAP_LOAD32 | heap::HEAP_LOAD64 |
+        heap::HEAP_STORE8 | heap::HEAP_STORE16 | heap::HEAP_STORE32 | heap::HEAP_STORE64 |
+        heap::HEAP_SIZE |
+        heap::HEAP_STORE8_GROW | heap::HEAP_STORE16_GROW |
+        heap::HEAP_STORE32_GROW | heap::HEAP_STORE64_GROW |
+        heap::HEAP_LOAD16_BE | heap::HEAP_LOAD32_BE | heap::HEAP_LOAD64_BE |
+        heap::HEAP_STORE16_BE | heap::HEAP_STORE32_BE | heap::HEAP_STORE64_BE |
+        heap::HEAP_COPY |
+        native::INPUT_LEN | exec::HALT => 1,
+
+        // 2-byte instructions (opcode + u8)
+        stack::PUSH_IMM8 | stack::PUSH_REG | stack::POP_REG |
+        special::NOP_N | exec::HALT_ERR => 2,
+
+        // 3-byte instructions (opcode + u16 or 2xu8)
+        stack::PUSH_IMM16 |
+        control::JMP | control::JZ | control::JNZ |
+        control::JGT | control::JLT | control::JGE | control::JLE |
+        control::CALL |
+        register::MOV_REG | register::LOAD_MEM | register::STORE_MEM |
+        memory::LOAD8 | memory::LOAD16 | memory::LOAD32 | memory::LOAD64 |
+        memory::STORE8 | memory::STORE16 | memory::STORE32 | memory::STORE64 |
+        arithmetic::BFX | arithmetic::BFI |
+        native::NATIVE_CALL | native::NATIVE_READ | native::NATIVE_WRITE => 3,
+
+        // 5-byte instructions (opcode + u32)
+        stack::PUSH_IMM32 | special::HASH_CHECK => 5,
+
+        // 9-byte instructions (opcode + u64)
+        stack::PUSH_IMM => 9,
+
+        // 10-byte instructions (opcode + u8 + u64)
+        register::MOV_IMM => 10,
+
+        _ => 0,
+    }
+}
+
+/// Longest instruction: NOP_N with a full 255-byte payload
+const MAX_INSTRUCTION_SPAN: usize = 2 + u8::MAX as usize;
+
+/// Extent of the encoded instruction at `pos`, including NOP_N padding
+///
+/// Undecodable bytes count as 1 byte; dispatch rejects them.
+pub fn instruction_extent(code: &[u8], pos: usize) -> usize {
+    let base = build_config::OPCODE_DECODE[code[pos] as usize];
+    if base == special::NOP_N {
+        return 2 + code.get(pos + 1).copied().unwrap_or(0) as usize;
+    }
+    instruction_length(base).max(1)
+}
+
+/// Check if a base opcode carries an i16 relative offset
+pub(crate) fn is_relative_branch(base_opcode: u8) -> bool {
+    matches!(
+        base_opcode,
+        control::JMP | control::JZ | control::JNZ |
+        control::JGT | control::JLT | control::JGE | control::JLE |
+        control::CALL
+    )
+}
+
+/// Check bytecode by linear sweep before running it
+///
+/// Every byte must decode, the last instruction must end exactly at the
+/// end of the code (`InvalidBytecode` otherwise), and every branch must
+/// land on an instruction start or the end (`InvalidJumpTarget`).
+/// Branches are taken as relative; see `validate_absolute`.
+pub fn validate(code: &[u8]) -> VmResult<()> {
+    validate_branches(code, false)
+}
+
+/// `validate` for code whose branch operands are absolute code offsets
+/// (`BytecodeFlags::AbsoluteJumps`)
+pub fn validate_absolute(code: &[u8]) -> VmResult<()> {
+    validate_branches(code, true)
+}
+
+fn validate_branches(code: &[u8], absolute_jumps: bool) -> VmResult<()> {
+    let mut starts = vec![false; code.len() + 1];
+    let mut pos = 0;
+    while pos < code.len() {
+        if instruction_length(build_config::OPCODE_DECODE[code[pos] as usize]) == 0 {
+            return Err(VmError::InvalidBytecode);
+        }
+        starts[pos] = true;
+        pos += instruction_extent(code, pos);
+    }
+    if pos != code.len() {
+        return Err(VmError::InvalidBytecode);
+    }
+    starts[pos] = true;
+
+    pos = 0;
+    while pos < code.len() {
+        if is_relative_branch(build_config::OPCODE_DECODE[code[pos] as usize]) {
+            let operand = u16::from_le_bytes([code[pos + 1], code[pos + 2]]);
+            let target = if absolute_jumps {
+                Some(operand as usize)
+            } else {
+                (pos + 3).checked_add_signed(operand as i16 as isize)
+            };
+            if target.is_none_or(|t| starts.get(t) != Some(&true)) {
+                return Err(VmError::InvalidJumpTarget);
+            }
+        }
+        pos += instruction_extent(code, pos);
+    }
+    Ok(())
+}
+
+/// Extents of the instructions executed so far, for catching jumps into
+/// operands
+///
+/// The engines record each instruction the first time it runs. A new
+/// instruction start that falls inside a recorded instruction, or whose
+/// operands cover a recorded start, means control flow landed
+/// mid-instruction, and fails with `InvalidJumpTarget` instead of decoding
+/// operand bytes as opcodes. Bytes that never run (junk behind an opaque
+/// predicate, NOP_N padding) are never inspected.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionMap {
+    /// Instruction length at each recorded start, 0 elsewhere
+    lens: Vec<u16>,
+}
+
+impl InstructionMap {
+    /// Create an empty map for `code_len` bytes of code
+    pub fn new(code_len: usize) -> Self {
+        Self { lens: vec![0; code_len] }
+    }
+
+    /// Record the instruction at `ip` unless it already ran
+    ///
+    /// `len` is only evaluated for instructions seen for the first time.
+    #[inline]
+    pub fn visit(&mut self, ip: usize, len: impl FnOnce() -> usize) -> VmResult<()> {
+        if self.lens.get(ip).is_none_or(|&l| l != 0) {
+            return Ok(());
+        }
+        self.check_start(ip)?;
+        self.record(ip, len())
+    }
+
+    /// Check that `ip` doesn't fall inside a recorded instruction
+    pub fn check_start(&self, ip: usize) -> VmResult<()> {
+        if self.lens.get(ip).is_none_or(|&l| l != 0) {
+            return Ok(());
+        }
+        // Recorded instructions never overlap, so only the nearest start
+        // below `ip` can cover it
+        let floor = ip.saturating_sub(MAX_INSTRUCTION_SPAN);
+        match (floor..ip).rev().find(|&s| self.lens[s] != 0) {
+            Some(start) if start + self.lens[start] as usize > ip => Err(VmError::InvalidJumpTarget),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a `len`-byte instruction at `ip`, failing if its operands
+    /// cover a recorded start
+    pub fn record(&mut self, ip: usize, len: usize) -> VmResult<()> {
+        if ip >= self.lens.len() {
+            return Ok(());
+        }
+        let len = len.clamp(1, u16::MAX as usize);
+        let end = ip.saturating_add(len).min(self.lens.len());
+        if self.lens[ip + 1..end].iter().any(|&l| l != 0) {
+            return Err(VmError::InvalidJumpTarget);
+        }
+        self.lens[ip] = len as u16;
+        Ok(())
+    }
+}
+
+/// Size and opcode mix of encoded bytecode
+///
+/// Decoded by linear sweep, so junk behind opaque predicates is counted as
+/// whatever it happens to decode to. Useful for checking that a protection
+/// level or pass pipeline still changes the code it is applied to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytecodeStats {
+    /// Code length in bytes
+    pub len: usize,
+    /// Instruction count per base opcode
+    pub opcode_histogram: [u32; 256],
+    /// Bytes that don't decode to any instruction
+    pub undecoded: usize,
+}
+
+impl BytecodeStats {
+    /// Collect stats for encoded bytecode
+    pub fn of(code: &[u8]) -> Self {
+        let mut stats = Self {
+            len: code.len(),
+            opcode_histogram: [0; 256],
+            undecoded: 0,
+        };
+        let mut ip = 0;
+        while ip < code.len() {
+            let base = build_config::OPCODE_DECODE[code[ip] as usize];
+            if instruction_length(base) == 0 {
+                stats.undecoded += 1;
+            } else {
+                stats.opcode_histogram[base as usize] += 1;
+            }
+            ip += instruction_extent(code, ip);
+        }
+        stats
+    }
+
+    /// Number of decoded instructions
+    pub fn instruction_count(&self) -> u32 {
+        self.opcode_histogram.iter().sum()
+    }
+
+    /// Number of distinct base opcodes used
+    pub fn distinct_opcodes(&self) -> usize {
+        self.opcode_histogram.iter().filter(|&&n| n != 0).count()
+    }
+
+    /// How often a base opcode occurs
+    pub fn count(&self, base_opcode: u8) -> u32 {
+        self.opcode_histogram[base_opcode as usize]
+    }
+
+    /// Number of anti-analysis instructions (OPAQUE_TRUE, OPAQUE_FALSE,
+    /// TIMING_CHECK, HASH_CHECK)
+    pub fn anti_analysis_count(&self) -> u32 {
+        [special::OPAQUE_TRUE, special::OPAQUE_FALSE, special::TIMING_CHECK, special::HASH_CHECK]
+            .iter()
+            .map(|&op| self.count(op))
+            .sum()
+    }
+}
@@ -0,0 +1,160 @@
+//! Bytecode Compression
+//!
+//! A small byte-oriented LZ77 codec for shrinking bytecode before it is
+//! encrypted and embedded. Virtualized routines repeat the same instruction
+//! sequences (unrolled loops, inlined helpers, register shuffles), which
+//! back-references capture well; overlapping references double as RLE.
+//! The decompressor is a few dozen lines of safe code and needs only
+//! `alloc`, so it stays `no_std`.
+//!
+//! ## Format
+//!
+//! ```text
+//! [original_len u32 LE] token*
+//!
+//! token: 0x00-0x7F  literal run: (c + 1) bytes follow
+//!        0x80-0xFF  match: (c & 0x7F) + 3 bytes copied from
+//!                   [distance u16 LE] bytes back (1..=65535)
+//! ```
+//!
+//! Compress before encrypting: ciphertext doesn't compress.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::error::{VmError, VmResult};
+
+/// Shortest back-reference worth encoding (token + distance = 3 bytes)
+const MIN_MATCH: usize = 3;
+
+/// Shortest back-reference actually emitted: a 3-byte match saves nothing
+/// and splits the surrounding literal run
+const MIN_EMITTED_MATCH: usize = MIN_MATCH + 1;
+
+/// Longest back-reference a single token can encode
+const MAX_MATCH: usize = MIN_MATCH + 0x7F;
+
+/// Longest literal run a single token can encode
+const MAX_LITERALS: usize = 0x80;
+
+/// Farthest back a match can reach
+const MAX_DISTANCE: usize = u16::MAX as usize;
+
+/// Match finder hash table size (log2)
+const HASH_BITS: u32 = 12;
+
+#[inline]
+fn hash3(bytes: &[u8]) -> usize {
+    let v = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+    (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+/// Compress `data` (at most `u32::MAX` bytes)
+///
+/// Greedy single-candidate match finder: fast and deterministic, not
+/// optimal. Incompressible input grows by 4 bytes plus 1 per 128.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + data.len() + data.len() / MAX_LITERALS + 1);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+    let last_hashable = data.len().saturating_sub(MIN_MATCH - 1);
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < last_hashable {
+        let h = hash3(&data[i..]);
+        let candidate = table[h];
+        table[h] = i;
+
+        if candidate == usize::MAX
+            || i - candidate > MAX_DISTANCE
+            || data[candidate..candidate + MIN_MATCH] != data[i..i + MIN_MATCH]
+        {
+            i += 1;
+            continue;
+        }
+
+        // The source may overlap the match itself (runs)
+        let mut len = MIN_MATCH;
+        while len < MAX_MATCH && i + len < data.len() && data[candidate + len] == data[i + len] {
+            len += 1;
+        }
+
+        if len < MIN_EMITTED_MATCH {
+            i += 1;
+            continue;
+        }
+
+        push_literals(&mut out, &data[literal_start..i]);
+        out.push(0x80 | (len - MIN_MATCH) as u8);
+        out.extend_from_slice(&((i - candidate) as u16).to_le_bytes());
+
+        for j in i + 1..(i + len).min(last_hashable) {
+            table[hash3(&data[j..])] = j;
+        }
+        i += len;
+        literal_start = i;
+    }
+
+    push_literals(&mut out, &data[literal_start..]);
+    out
+}
+
+/// Decompress data produced by [`compress`]
+///
+/// Truncated tokens, references before the start of the output, and a
+/// length that doesn't match the header all fail with `InvalidBytecode`.
+pub fn decompress(data: &[u8]) -> VmResult<Vec<u8>> {
+    let header = data.get(..4).ok_or(VmError::InvalidBytecode)?;
+    let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let body = &data[4..];
+
+    // Each 3-byte match token expands to at most MAX_MATCH bytes; reject
+    // claimed lengths the body can't produce before allocating for them
+    if len > body.len().saturating_mul(MAX_MATCH) {
+        return Err(VmError::InvalidBytecode);
+    }
+
+    let mut out = Vec::with_capacity(len);
+    let mut pos = 0;
+    while pos < body.len() {
+        let token = body[pos];
+        pos += 1;
+
+        if token < 0x80 {
+            let count = token as usize + 1;
+            let literals = body.get(pos..pos + count).ok_or(VmError::InvalidBytecode)?;
+            out.extend_from_slice(literals);
+            pos += count;
+        } else {
+            let count = (token & 0x7F) as usize + MIN_MATCH;
+            let distance = body.get(pos..pos + 2).ok_or(VmError::InvalidBytecode)?;
+            let distance = u16::from_le_bytes([distance[0], distance[1]]) as usize;
+            pos += 2;
+            if distance == 0 || distance > out.len() {
+                return Err(VmError::InvalidBytecode);
+            }
+            let start = out.len() - distance;
+            for k in 0..count {
+                out.push(out[start + k]);
+            }
+        }
+
+        if out.len() > len {
+            return Err(VmError::InvalidBytecode);
+        }
+    }
+
+    if out.len() != len {
+        return Err(VmError::InvalidBytecode);
+    }
+    Ok(out)
+}
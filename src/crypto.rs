@@ -5,12 +5,14 @@
 use crate::error::{VmError, VmResult};
 use aes_gcm::{
     aead::Aead,
+    aes::{cipher::BlockEncrypt, Aes256},
     Aes256Gcm, Nonce,
     KeyInit as AesKeyInit,
 };
 use hmac::{Hmac, Mac};
 use hmac::digest::KeyInit as HmacKeyInit;
 use sha2::Sha256;
+use subtle::ConstantTimeEq;
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
@@ -150,6 +152,96 @@ pub fn decrypt_bytecode(
     Ok(plaintext)
 }
 
+/// Move AES-256-GCM ciphertext from `old_key` to `new_key` in place
+///
+/// GCM encrypts with a CTR keystream, so each block is re-encrypted as
+/// `c ^ ks_old ^ ks_new` and the plaintext never exists as a buffer. The
+/// old tag is checked over the ciphertext first; on mismatch `ciphertext`
+/// is left untouched and `DecryptionFailed` is returned. The nonce is kept,
+/// which is safe because it has never been used with `new_key`.
+///
+/// Returns the tag under `new_key`.
+pub fn rekey_bytecode(
+    old_key: &[u8; KEY_SIZE],
+    new_key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    ciphertext: &mut [u8],
+    tag: &[u8; TAG_SIZE],
+) -> VmResult<[u8; TAG_SIZE]> {
+    let old = GcmKey::new(old_key, nonce)?;
+    let new = GcmKey::new(new_key, nonce)?;
+
+    if !bool::from(old.tag(ciphertext).ct_eq(tag)) {
+        return Err(VmError::DecryptionFailed);
+    }
+
+    for (i, chunk) in ciphertext.chunks_mut(16).enumerate() {
+        let counter = i as u32 + 2;
+        let old_stream = old.keystream(counter);
+        let new_stream = new.keystream(counter);
+        for (j, byte) in chunk.iter_mut().enumerate() {
+            *byte ^= old_stream[j] ^ new_stream[j];
+        }
+    }
+    Ok(new.tag(ciphertext))
+}
+
+/// Raw AES-256-GCM pieces for one key and 96-bit nonce (no AAD)
+struct GcmKey {
+    cipher: Aes256,
+    /// GHASH key, E_K(0^128)
+    h: u128,
+    nonce: [u8; NONCE_SIZE],
+}
+
+impl GcmKey {
+    fn new(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> VmResult<Self> {
+        let cipher = Aes256::new_from_slice(key).map_err(|_| VmError::DecryptionFailed)?;
+        let mut block = [0u8; 16].into();
+        cipher.encrypt_block(&mut block);
+        let h = u128::from_be_bytes(block.into());
+        Ok(Self { cipher, h, nonce: *nonce })
+    }
+
+    /// E_K(nonce || counter)
+    fn keystream(&self, counter: u32) -> [u8; 16] {
+        let mut block = [0u8; 16];
+        block[..NONCE_SIZE].copy_from_slice(&self.nonce);
+        block[NONCE_SIZE..].copy_from_slice(&counter.to_be_bytes());
+        let mut block = block.into();
+        self.cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    /// Authentication tag over `ciphertext`
+    fn tag(&self, ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let mut x = 0u128;
+        for chunk in ciphertext.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            x = gf128_mul(x ^ u128::from_be_bytes(block), self.h);
+        }
+        // Length block: 0 bits of AAD, then ciphertext bits
+        x = gf128_mul(x ^ (ciphertext.len() as u128 * 8), self.h);
+
+        let mask = u128::from_be_bytes(self.keystream(1));
+        (x ^ mask).to_be_bytes()
+    }
+}
+
+/// Multiply in GCM's GF(2^128), bit-reflected as in SP 800-38D
+fn gf128_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    let mut z = 0u128;
+    let mut v = y;
+    for i in (0..128).rev() {
+        // Branch-free: all ones when bit i of x is set
+        z ^= v & ((x >> i) & 1).wrapping_neg();
+        v = (v >> 1) ^ (R & (v & 1).wrapping_neg());
+    }
+    z
+}
+
 /// Compute HMAC-SHA256 for integrity verification
 pub fn compute_hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
     let mut mac = HmacSha256::new_from_slice(key)
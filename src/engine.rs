@@ -3,13 +3,28 @@
 //! Main dispatch loop using indirect threading (function pointer table)
 //! This eliminates the switch-case pattern visible in binary analysis.
 
+use crate::build_config::OPCODE_DECODE;
+use crate::bytecode::{instruction_extent, BytecodePackage, InstructionMap};
+use crate::crypto::CryptoContext;
 use crate::error::{VmError, VmResult};
-use crate::native::NativeRegistry;
-use crate::state::{VmState, MAX_INSTRUCTIONS};
+use crate::native::{NativeRegistry, MAX_NATIVE_ARGS};
+use crate::opcodes::native;
+use crate::state::{VmState, VmOutcome};
+use crate::whitebox::WhiteboxCryptoContext;
 
 // Indirect dispatch via function pointer table
 use crate::handlers::dispatch::dispatch_indirect;
 
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Instructions between wall-clock checks in `run_with_timeout`
+/// (power of two, checked with a mask)
+pub const TIMEOUT_CHECK_INTERVAL: u64 = 256;
+
 /// Execute bytecode with given input, return result
 pub fn execute(code: &[u8], input: &[u8]) -> VmResult<u64> {
     let mut state = VmState::new(code, input);
@@ -17,6 +32,58 @@ pub fn execute(code: &[u8], input: &[u8]) -> VmResult<u64> {
     Ok(state.result)
 }
 
+/// Execute bytecode with anti-analysis checks disabled
+///
+/// TIMING_CHECK and HASH_CHECK are treated as no-ops so correctness tests
+/// are isolated from timing and integrity behavior.
+pub fn execute_deterministic(code: &[u8], input: &[u8]) -> VmResult<u64> {
+    let mut state = VmState::new(code, input);
+    state.disable_anti_analysis();
+    run(&mut state)?;
+    Ok(state.result)
+}
+
+/// Execute bytecode whose branch operands are absolute code offsets
+///
+/// For code built with `BytecodeBuilder::absolute_jumps` (packages flagged
+/// `BytecodeFlags::AbsoluteJumps`); see `VmState::set_absolute_jumps`.
+pub fn execute_absolute(code: &[u8], input: &[u8]) -> VmResult<u64> {
+    let mut state = VmState::new(code, input);
+    state.set_absolute_jumps(true);
+    run(&mut state)?;
+    Ok(state.result)
+}
+
+/// Execute a package after checking its ABI tag
+///
+/// Fails with `AbiMismatch` for code built against another opcode set, then
+/// decrypts (authenticating the tag) and decompresses as the header flags
+/// say. Absolute-jump packages run with absolute branch operands.
+pub fn execute_verified(package: &BytecodePackage, ctx: &CryptoContext, input: &[u8]) -> VmResult<u64> {
+    package.header.check_abi()?;
+    let code = package.unpack(ctx)?;
+    let mut state = VmState::new(&code, input);
+    state.set_absolute_jumps(package.header.has_absolute_jumps());
+    run(&mut state)?;
+    Ok(state.result)
+}
+
+/// Execute bytecode over a whitebox-encrypted input buffer
+///
+/// `encrypted_input` is produced by `WhiteboxCryptoContext::encrypt_input`.
+/// Bytes are decrypted on demand inside the input reads, so the plaintext
+/// buffer never exists in memory as a whole.
+pub fn execute_with_encrypted_input(
+    code: &[u8],
+    encrypted_input: &[u8],
+    cipher: &WhiteboxCryptoContext,
+) -> VmResult<u64> {
+    let mut state = VmState::new(code, encrypted_input);
+    state.input_cipher = Some(cipher);
+    run(&mut state)?;
+    Ok(state.result)
+}
+
 /// Execute bytecode with native function registry
 pub fn execute_with_natives(code: &[u8], input: &[u8], registry: &NativeRegistry) -> VmResult<u64> {
     let mut state = VmState::new(code, input);
@@ -33,24 +100,113 @@ pub fn execute_with_native_table(code: &[u8], input: &[u8], native_table: &[fn(&
     Ok(state.result)
 }
 
+/// Execute bytecode with a native table, reporting every native call
+///
+/// After each NATIVE_CALL returns, `observer` receives the native index, the
+/// arguments (first pushed first) and the result. Calls that fail are not
+/// reported; the run stops with their error.
+pub fn execute_with_native_observer(
+    code: &[u8],
+    input: &[u8],
+    native_table: &[fn(&[u64]) -> u64],
+    observer: &mut dyn FnMut(usize, &[u64], u64),
+) -> VmResult<u64> {
+    let mut state = VmState::new(code, input);
+    state.set_native_table(native_table);
+    run_with_native_observer(&mut state, observer)?;
+    Ok(state.result)
+}
+
+/// Same as `execute_with_natives`, but a failed run also returns the
+/// NATIVE_CALL index that had no function (`VmState::unknown_native`)
+///
+/// The index is `Some` only when the error is `VmError::UnknownNative`.
+/// Native IDs are shuffled per build, so this is the quickest way to tell
+/// which entry of a mismatched registry is missing.
+pub fn execute_with_natives_traced(
+    code: &[u8],
+    input: &[u8],
+    registry: &NativeRegistry,
+) -> Result<u64, (VmError, Option<u8>)> {
+    let mut state = VmState::new(code, input);
+    match run_with_natives(&mut state, registry) {
+        Ok(()) => Ok(state.result),
+        Err(e) => Err((e, state.unknown_native)),
+    }
+}
+
+/// Same as `execute_with_native_table`, but a failed run also returns the
+/// NATIVE_CALL index that had no function (see `execute_with_natives_traced`)
+pub fn execute_with_native_table_traced(
+    code: &[u8],
+    input: &[u8],
+    native_table: &[fn(&[u64]) -> u64],
+) -> Result<u64, (VmError, Option<u8>)> {
+    let mut state = VmState::new(code, input);
+    state.set_native_table(native_table);
+    match run_with_native_table(&mut state) {
+        Ok(()) => Ok(state.result),
+        Err(e) => Err((e, state.unknown_native)),
+    }
+}
+
+/// Execute bytecode, failing with `VmError::Timeout` once `timeout` has elapsed
+///
+/// The instruction budget bounds the work, not the time: a slow native call can
+/// stall a run well below it. Elapsed time is checked every
+/// `TIMEOUT_CHECK_INTERVAL` instructions, so a single blocking call is not
+/// interrupted, but the run stops at the next check after it returns.
+#[cfg(feature = "std")]
+pub fn execute_with_timeout(code: &[u8], input: &[u8], timeout: Duration) -> VmResult<u64> {
+    let mut state = VmState::new(code, input);
+    run_with_timeout(&mut state, &NativeRegistry::new(), timeout)?;
+    Ok(state.result)
+}
+
+/// Main execution loop with a wall-clock time limit (see `execute_with_timeout`)
+#[cfg(feature = "std")]
+pub fn run_with_timeout(state: &mut VmState, registry: &NativeRegistry, timeout: Duration) -> VmResult<()> {
+    let start = Instant::now();
+    dispatch_loop(state, |state, opcode| {
+        // Wall-clock limit, sampled to keep Instant::now() off the hot path
+        if state.instruction_count & (TIMEOUT_CHECK_INTERVAL - 1) == 0 && start.elapsed() > timeout {
+            return Err(VmError::Timeout);
+        }
+        dispatch_indirect(state, opcode, registry)
+    })
+}
+
 /// Main execution loop with native function table support
 pub fn run_with_native_table(state: &mut VmState) -> VmResult<()> {
     let empty_registry = NativeRegistry::new();
-    while !state.halted && state.ip < state.code.len() {
-        // Instruction count limit
-        state.instruction_count += 1;
-        if state.instruction_count > MAX_INSTRUCTIONS {
-            return Err(VmError::MaxInstructionsExceeded);
+    dispatch_loop(state, |state, opcode| dispatch_indirect(state, opcode, &empty_registry))
+}
+
+/// Main execution loop with native table support and a native call observer
+/// (see `execute_with_native_observer`)
+pub fn run_with_native_observer(
+    state: &mut VmState,
+    observer: &mut dyn FnMut(usize, &[u64], u64),
+) -> VmResult<()> {
+    let empty_registry = NativeRegistry::new();
+    dispatch_loop(state, |state, opcode| {
+        if OPCODE_DECODE[opcode as usize] != native::NATIVE_CALL {
+            return dispatch_indirect(state, opcode, &empty_registry);
         }
 
-        // Fetch opcode
-        let opcode = state.read_u8()?;
+        // Capture operands and arguments before the handler consumes them;
+        // malformed calls fail in the handler and are never reported
+        let index = state.code.get(state.ip).copied().unwrap_or(0) as usize;
+        let count = (state.code.get(state.ip + 1).copied().unwrap_or(0) as usize).min(MAX_NATIVE_ARGS);
+        let mut args = [0u64; MAX_NATIVE_ARGS];
+        if let Some(top) = state.stack.len().checked_sub(count) {
+            args[..count].copy_from_slice(&state.stack[top..]);
+        }
 
-        // Indirect dispatch via function pointer table
         dispatch_indirect(state, opcode, &empty_registry)?;
-    }
-
-    Ok(())
+        observer(index, &args[..count], state.peek()?);
+        Ok(())
+    })
 }
 
 /// Execute bytecode, return full state (for debugging)
@@ -60,6 +216,63 @@ pub fn execute_with_state<'a>(code: &'a [u8], input: &'a [u8]) -> VmResult<VmSta
     Ok(state)
 }
 
+/// Execute bytecode, return the result together with the final output,
+/// heap, registers, flags and instruction count
+///
+/// For routines that report through the output buffer or heap. `execute`
+/// stays the cheaper choice when only the `u64` result is needed.
+pub fn execute_capturing(code: &[u8], input: &[u8]) -> VmResult<VmOutcome> {
+    let mut state = VmState::new(code, input);
+    run(&mut state)?;
+    Ok(state.into_outcome())
+}
+
+/// Run bytecode in a caller-provided state, return result
+///
+/// For pooling: configure a `VmState` once (limits, native table, ...) and
+/// run many blobs through it without reallocating. The state is rebound to
+/// `code`/`input` before running, so nothing carries over from the
+/// previous run except configuration (see `VmState::rebind`).
+///
+/// `VmState<'a>` borrows its code and input, so every blob run through the
+/// same state must outlive it. Keep the blobs in a longer-lived owner, or
+/// create one state per batch.
+pub fn run_in_state<'a>(state: &mut VmState<'a>, code: &'a [u8], input: &'a [u8]) -> VmResult<u64> {
+    state.rebind(code, input);
+    run(state)?;
+    Ok(state.result)
+}
+
+/// Execute the same bytecode over many inputs
+///
+/// One `VmState` is rebound per input (see `run_in_state`), so registers,
+/// heap and stacks are allocated once for the whole batch. Each result is
+/// the same as `execute(code, input)` for that input.
+pub fn execute_batch<'a>(code: &'a [u8], inputs: &[&'a [u8]]) -> Vec<VmResult<u64>> {
+    let mut state = VmState::new(code, &[]);
+    inputs
+        .iter()
+        .map(|input| run_in_state(&mut state, code, input))
+        .collect()
+}
+
+/// `execute_batch` split across the rayon thread pool
+///
+/// Inputs are processed in chunks, one reused `VmState` per chunk; results
+/// keep the order of `inputs`.
+#[cfg(feature = "parallel")]
+pub fn execute_batch_parallel<'a>(code: &'a [u8], inputs: &[&'a [u8]]) -> Vec<VmResult<u64>> {
+    use rayon::prelude::*;
+
+    /// Inputs per rayon task, enough to amortize the state allocation
+    const CHUNK: usize = 64;
+
+    inputs
+        .par_chunks(CHUNK)
+        .flat_map_iter(|chunk| execute_batch(code, chunk))
+        .collect()
+}
+
 /// Main execution loop (without native functions)
 pub fn run(state: &mut VmState) -> VmResult<()> {
     let empty_registry = NativeRegistry::new();
@@ -68,22 +281,55 @@ pub fn run(state: &mut VmState) -> VmResult<()> {
 
 /// Main execution loop with native function support
 /// Uses indirect threading (function pointer table) for opcode dispatch
+///
+/// With `VmState::set_jump_guard`, jumps are checked against the
+/// instructions executed so far (see `InstructionMap`): landing inside one
+/// fails with `InvalidJumpTarget`.
 pub fn run_with_natives(state: &mut VmState, registry: &NativeRegistry) -> VmResult<()> {
+    // Indirect dispatch via function pointer table
+    // This replaces the switch-case pattern for better obfuscation
+    dispatch_loop(state, |state, opcode| dispatch_indirect(state, opcode, registry))
+}
+
+/// Dispatch loop shared by the `run*` functions
+///
+/// `exec` handles each fetched opcode, after the instruction budget and
+/// jump guard checks.
+#[inline(always)]
+fn dispatch_loop(
+    state: &mut VmState,
+    mut exec: impl FnMut(&mut VmState, u8) -> VmResult<()>,
+) -> VmResult<()> {
+    let mut guard = jump_guard(state);
     while !state.halted && state.ip < state.code.len() {
-        // Instruction count limit
-        state.instruction_count += 1;
-        if state.instruction_count > MAX_INSTRUCTIONS {
-            return Err(VmError::MaxInstructionsExceeded);
-        }
+        let opcode = fetch(state, &mut guard)?;
+        exec(state, opcode)?;
+    }
 
-        // Fetch opcode
-        let opcode = state.read_u8()?;
+    Ok(())
+}
 
-        // Indirect dispatch via function pointer table
-        // This replaces the switch-case pattern for better obfuscation
-        dispatch_indirect(state, opcode, registry)?;
+/// Instruction map for a run, if the state has the jump guard enabled
+#[inline]
+pub(crate) fn jump_guard(state: &VmState) -> Option<InstructionMap> {
+    state.jump_guard.then(|| InstructionMap::new(state.code.len()))
+}
+
+/// Count the next instruction against the budget, check it with the jump
+/// guard, and fetch its opcode
+#[inline(always)]
+pub(crate) fn fetch(state: &mut VmState, guard: &mut Option<InstructionMap>) -> VmResult<u8> {
+    // Instruction count limit
+    state.instruction_count += 1;
+    if state.instruction_count > state.max_instructions {
+        return Err(VmError::MaxInstructionsExceeded);
     }
 
-    Ok(())
+    // Reject landing inside an instruction that already ran
+    if let Some(visited) = guard {
+        visited.visit(state.ip, || instruction_extent(state.code, state.ip))?;
+    }
+
+    state.read_u8()
 }
 
@@ -7,7 +7,7 @@ use core::fmt;
 
 /// VM execution errors
 ///
-/// Note: Debug impl only shows error code (E00-E20) to prevent string leakage.
+/// Note: Debug impl only shows error code (E00-E27) to prevent string leakage.
 /// Use `as_str()` for human-readable messages (decrypted at runtime).
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -42,7 +42,10 @@ pub enum VmError {
     InvalidBytecode = 13,
     /// Memory access out of bounds
     MemoryOutOfBounds = 14,
-    /// Native function not found
+    /// Native function not found by `NativeRegistry::call`
+    ///
+    /// Only the registry API returns this; a NATIVE_CALL during execution
+    /// reports a missing function as `UnknownNative`.
     NativeFunctionNotFound = 15,
     /// Native function already registered
     NativeFunctionAlreadyRegistered = 16,
@@ -54,6 +57,23 @@ pub enum VmError {
     HeapOutOfBounds = 19,
     /// Double-free detected (freeing already freed block)
     DoubleFree = 20,
+    /// Call stack overflow (exceeded max call depth)
+    CallStackOverflow = 21,
+    /// Input buffer access out of bounds
+    InputOutOfBounds = 22,
+    /// Output buffer write beyond MAX_OUTPUT_SIZE
+    OutputOutOfBounds = 23,
+    /// Wall-clock time limit exceeded
+    Timeout = 24,
+    /// Heap access outside a live allocation (strict_heap mode)
+    UseAfterFree = 25,
+    /// NATIVE_CALL index with no function in the native table or registry
+    ///
+    /// Returned by execution since 0.2.52 (formerly `NativeFunctionNotFound`).
+    /// The `*_traced` entry points return the offending index.
+    UnknownNative = 26,
+    /// Bytecode header carries a different VM ABI version
+    AbiMismatch = 27,
 }
 
 // Manual Debug impl - only shows error code, no string leakage
@@ -97,6 +117,13 @@ impl VmError {
             VmError::HeapOutOfMemory => aegis_str_internal!("VM_ERR_HEAP_OOM"),
             VmError::HeapOutOfBounds => aegis_str_internal!("VM_ERR_HEAP_OOB"),
             VmError::DoubleFree => aegis_str_internal!("VM_ERR_DOUBLE_FREE"),
+            VmError::CallStackOverflow => aegis_str_internal!("VM_ERR_CALL_STACK_OVERFLOW"),
+            VmError::InputOutOfBounds => aegis_str_internal!("VM_ERR_INPUT_OOB"),
+            VmError::OutputOutOfBounds => aegis_str_internal!("VM_ERR_OUTPUT_OOB"),
+            VmError::Timeout => aegis_str_internal!("VM_ERR_TIMEOUT"),
+            VmError::UseAfterFree => aegis_str_internal!("VM_ERR_USE_AFTER_FREE"),
+            VmError::UnknownNative => aegis_str_internal!("VM_ERR_UNKNOWN_NATIVE"),
+            VmError::AbiMismatch => aegis_str_internal!("VM_ERR_ABI_MISMATCH"),
         }
     }
 
@@ -1,8 +1,9 @@
 //! Arithmetic Operation Handlers
 //!
-//! ADD, SUB, MUL, XOR, AND, OR, SHL, SHR, NOT, ROL, ROR, INC, DEC, DIV, MOD, IDIV, IMOD
+//! ADD, SUB, MUL, XOR, AND, OR, SHL, SHR, NOT, ROL, ROR, INC, DEC, DIV, MOD, IDIV, IMOD, POW, ADC, SBB,
+//! BFX, BFI, IMOD_EUCLID
 
-use crate::error::VmResult;
+use crate::error::{VmError, VmResult};
 use crate::state::VmState;
 
 /// ADD: Pop 2, push sum
@@ -11,6 +12,7 @@ pub fn handle_add(state: &mut VmState) -> VmResult<()> {
     let a = state.pop()?;
     let result = a.wrapping_add(b);
     state.set_zero_flag(result);
+    state.set_carry_flag(result < a);
     state.push(result)
 }
 
@@ -20,6 +22,7 @@ pub fn handle_sub(state: &mut VmState) -> VmResult<()> {
     let a = state.pop()?;
     let result = a.wrapping_sub(b);
     state.set_zero_flag(result);
+    state.set_carry_flag(a < b);
     state.push(result)
 }
 
@@ -136,19 +139,123 @@ pub fn handle_mod(state: &mut VmState) -> VmResult<()> {
 }
 
 /// IDIV: Signed division ((a as i64) / (b as i64))
+/// i64::MIN / -1 wraps to i64::MIN instead of trapping.
 pub fn handle_idiv(state: &mut VmState) -> VmResult<()> {
     let b = state.pop()? as i64;
     let a = state.pop()? as i64;
-    let result = if b == 0 { 0 } else { (a / b) as u64 };
+    let result = if b == 0 { 0 } else { a.wrapping_div(b) as u64 };
     state.set_zero_flag(result);
     state.push(result)
 }
 
 /// IMOD: Signed modulo ((a as i64) % (b as i64))
+/// Truncated like Rust's `%`: the result takes the sign of the dividend
+/// (-7 % 3 == -1). i64::MIN % -1 wraps to 0 instead of trapping.
 pub fn handle_imod(state: &mut VmState) -> VmResult<()> {
     let b = state.pop()? as i64;
     let a = state.pop()? as i64;
-    let result = if b == 0 { 0 } else { (a % b) as u64 };
+    let result = if b == 0 { 0 } else { a.wrapping_rem(b) as u64 };
     state.set_zero_flag(result);
     state.push(result)
 }
+
+/// MULH: Pop 2, push the high 64 bits of the full unsigned product
+/// ((a as u128 * b as u128) >> 64), for fixed-point and hashing
+pub fn handle_mulh(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()?;
+    let a = state.pop()?;
+    let result = ((a as u128 * b as u128) >> 64) as u64;
+    state.set_zero_flag(result);
+    state.push(result)
+}
+
+/// IMULH: Pop 2, push the high 64 bits of the full signed product
+/// ((a as i128 * b as i128) >> 64); never overflows, even for i64::MIN
+pub fn handle_imulh(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()? as i64;
+    let a = state.pop()? as i64;
+    let result = ((a as i128 * b as i128) >> 64) as u64;
+    state.set_zero_flag(result);
+    state.push(result)
+}
+
+/// IMOD_EUCLID: Signed Euclidean remainder ((a as i64).rem_euclid(b as i64))
+/// Never negative (-7 rem_euclid 3 == 2). Division by zero returns 0.
+pub fn handle_imod_euclid(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()? as i64;
+    let a = state.pop()? as i64;
+    let result = if b == 0 { 0 } else { a.wrapping_rem_euclid(b) as u64 };
+    state.set_zero_flag(result);
+    state.push(result)
+}
+
+/// POW: Exponentiation (a ** b), wrapping like u64::wrapping_pow
+/// Uses square-and-multiply so the full u64 exponent range is supported
+pub fn handle_pow(state: &mut VmState) -> VmResult<()> {
+    let mut exp = state.pop()?;
+    let mut base = state.pop()?;
+    let mut result: u64 = 1;
+    while exp != 0 {
+        if exp & 1 != 0 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exp >>= 1;
+    }
+    state.set_zero_flag(result);
+    state.push(result)
+}
+
+/// ADC: Pop 2, push a + b + carry, carry flag = carry out
+/// Chains after ADD (or a CMP of equal values to clear the carry)
+pub fn handle_adc(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()?;
+    let a = state.pop()?;
+    let (sum, c1) = a.overflowing_add(b);
+    let (result, c2) = sum.overflowing_add(state.is_carry() as u64);
+    state.set_zero_flag(result);
+    state.set_carry_flag(c1 || c2);
+    state.push(result)
+}
+
+/// SBB: Pop 2, push a - b - carry, carry flag = borrow out
+/// Chains after SUB (or a CMP of equal values to clear the borrow)
+pub fn handle_sbb(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()?;
+    let a = state.pop()?;
+    let (diff, b1) = a.overflowing_sub(b);
+    let (result, b2) = diff.overflowing_sub(state.is_carry() as u64);
+    state.set_zero_flag(result);
+    state.set_carry_flag(b1 || b2);
+    state.push(result)
+}
+
+/// Read BFX/BFI `<offset u8> <width u8>` operands, return (offset, mask)
+///
+/// The field must lie within 64 bits; width 0 selects nothing.
+#[inline(always)]
+fn read_bitfield(state: &mut VmState) -> VmResult<(u32, u64)> {
+    let offset = state.read_u8()? as u32;
+    let width = state.read_u8()? as u32;
+    if offset + width > 64 || offset >= 64 {
+        return Err(VmError::InvalidBytecode);
+    }
+    let mask = if width == 64 { u64::MAX } else { (1u64 << width) - 1 };
+    Ok((offset, mask))
+}
+
+/// BFX: Pop x, push (x >> offset) & mask(width)
+pub fn handle_bfx(state: &mut VmState) -> VmResult<()> {
+    let (offset, mask) = read_bitfield(state)?;
+    let x = state.pop()?;
+    state.push((x >> offset) & mask)
+}
+
+/// BFI: Pop field, pop x, push x with bits [offset, offset + width) replaced
+/// by the low `width` bits of field
+pub fn handle_bfi(state: &mut VmState) -> VmResult<()> {
+    let (offset, mask) = read_bitfield(state)?;
+    let field = state.pop()?;
+    let x = state.pop()?;
+    state.push((x & !(mask << offset)) | ((field & mask) << offset))
+}
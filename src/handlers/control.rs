@@ -1,9 +1,9 @@
 //! Control Flow Handlers
 //!
-//! CMP, JMP, JZ, JNZ, JGT, JLT, JGE, JLE, CALL, RET
+//! CMP, JMP, JZ, JNZ, JGT, JLT, JGE, JLE, CALL, RET, CMOV
 
 use crate::error::{VmError, VmResult};
-use crate::state::VmState;
+use crate::state::{VmState, MAX_CALL_DEPTH};
 
 /// CMP: Compare top two stack values, set flags
 pub fn handle_cmp(state: &mut VmState) -> VmResult<()> {
@@ -17,15 +17,15 @@ pub fn handle_cmp(state: &mut VmState) -> VmResult<()> {
 
 /// JMP: Unconditional jump
 pub fn handle_jmp(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
-    jump_relative(state, offset)
+    let operand = state.read_u16()?;
+    jump_to_operand(state, operand)
 }
 
 /// JZ: Jump if zero flag set
 pub fn handle_jz(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     if state.is_zero() {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
@@ -33,9 +33,9 @@ pub fn handle_jz(state: &mut VmState) -> VmResult<()> {
 
 /// JNZ: Jump if zero flag not set
 pub fn handle_jnz(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     if !state.is_zero() {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
@@ -43,10 +43,10 @@ pub fn handle_jnz(state: &mut VmState) -> VmResult<()> {
 
 /// JGT: Jump if greater (signed)
 pub fn handle_jgt(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     // Greater: not zero AND sign == overflow
     if !state.is_zero() && (state.is_negative() == state.is_overflow()) {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
@@ -54,10 +54,10 @@ pub fn handle_jgt(state: &mut VmState) -> VmResult<()> {
 
 /// JLT: Jump if less (signed)
 pub fn handle_jlt(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     // Less: sign != overflow
     if state.is_negative() != state.is_overflow() {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
@@ -65,10 +65,10 @@ pub fn handle_jlt(state: &mut VmState) -> VmResult<()> {
 
 /// JGE: Jump if greater or equal
 pub fn handle_jge(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     // Greater or equal: sign == overflow
     if state.is_negative() == state.is_overflow() {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
@@ -76,22 +76,40 @@ pub fn handle_jge(state: &mut VmState) -> VmResult<()> {
 
 /// JLE: Jump if less or equal
 pub fn handle_jle(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
     // Less or equal: zero OR (sign != overflow)
     if state.is_zero() || (state.is_negative() != state.is_overflow()) {
-        jump_relative(state, offset)
+        jump_to_operand(state, operand)
     } else {
         Ok(())
     }
 }
 
+/// Helper: Jump to a branch operand
+///
+/// The operand is an i16 offset from the IP after it, or with
+/// `VmState::absolute_jumps` a u16 offset from the start of the code.
+#[inline]
+pub fn jump_to_operand(state: &mut VmState, operand: u16) -> VmResult<()> {
+    if state.absolute_jumps {
+        jump_absolute(state, operand as usize)
+    } else {
+        jump_relative(state, operand as i16)
+    }
+}
+
+/// Helper: Jump to an absolute code offset
+pub fn jump_absolute(state: &mut VmState, target: usize) -> VmResult<()> {
+    if target > state.code.len() {
+        return Err(VmError::InvalidJumpTarget);
+    }
+    state.ip = target;
+    Ok(())
+}
+
 /// Helper: Jump by relative offset
 pub fn jump_relative(state: &mut VmState, offset: i16) -> VmResult<()> {
-    let new_ip = if offset >= 0 {
-        state.ip.checked_add(offset as usize)
-    } else {
-        state.ip.checked_sub((-offset) as usize)
-    };
+    let new_ip = state.ip.checked_add_signed(offset as isize);
 
     match new_ip {
         Some(ip) if ip <= state.code.len() => {
@@ -104,10 +122,13 @@ pub fn jump_relative(state: &mut VmState, offset: i16) -> VmResult<()> {
 
 /// CALL: Call subroutine
 pub fn handle_call(state: &mut VmState) -> VmResult<()> {
-    let offset = state.read_i16()?;
+    let operand = state.read_u16()?;
+    if state.call_stack.len() >= MAX_CALL_DEPTH {
+        return Err(VmError::CallStackOverflow);
+    }
     // Push return address
     state.call_stack.push(state.ip);
-    jump_relative(state, offset)
+    jump_to_operand(state, operand)
 }
 
 /// RET: Return from subroutine
@@ -125,3 +146,14 @@ pub fn handle_ret(state: &mut VmState) -> VmResult<()> {
         }
     }
 }
+
+/// CMOV: Select a or b by cond without branching
+///
+/// Both operands are always popped and the choice is made with a mask,
+/// so the work done doesn't depend on cond.
+pub fn handle_cmov(state: &mut VmState) -> VmResult<()> {
+    let b = state.pop()?;
+    let a = state.pop()?;
+    let cond = state.pop()?;
+    state.push(crate::select(cond, a, b))
+}
@@ -1,6 +1,6 @@
 //! Type Conversion Handlers
 //!
-//! SEXT8, SEXT16, SEXT32, TRUNC8, TRUNC16, TRUNC32
+//! SEXT8, SEXT16, SEXT32, TRUNC8, TRUNC16, TRUNC32, BSWAP16, BSWAP32, BSWAP
 
 use crate::error::VmResult;
 use crate::state::VmState;
@@ -46,3 +46,23 @@ pub fn handle_trunc32(state: &mut VmState) -> VmResult<()> {
     let result = value & 0xFFFFFFFF;
     state.push(result)
 }
+
+/// BSWAP16: Reverse the bytes of the low 16 bits
+pub fn handle_bswap16(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()?;
+    let result = (value as u16).swap_bytes() as u64;
+    state.push(result)
+}
+
+/// BSWAP32: Reverse the bytes of the low 32 bits
+pub fn handle_bswap32(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()?;
+    let result = (value as u32).swap_bytes() as u64;
+    state.push(result)
+}
+
+/// BSWAP: Reverse all 8 bytes
+pub fn handle_bswap(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()?;
+    state.push(value.swap_bytes())
+}
@@ -133,6 +133,14 @@ pub fn w_dec(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_dec(s)
 }
 #[inline(always)]
+pub fn w_mulh(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_mulh(s)
+}
+#[inline(always)]
+pub fn w_imulh(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_imulh(s)
+}
+#[inline(always)]
 pub fn w_div(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_div(s)
 }
@@ -148,6 +156,30 @@ pub fn w_idiv(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
 pub fn w_imod(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_imod(s)
 }
+#[inline(always)]
+pub fn w_pow(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_pow(s)
+}
+#[inline(always)]
+pub fn w_adc(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_adc(s)
+}
+#[inline(always)]
+pub fn w_sbb(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_sbb(s)
+}
+#[inline(always)]
+pub fn w_bfx(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_bfx(s)
+}
+#[inline(always)]
+pub fn w_bfi(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_bfi(s)
+}
+#[inline(always)]
+pub fn w_imod_euclid(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_imod_euclid(s)
+}
 
 // Control handlers
 #[inline(always)]
@@ -190,6 +222,10 @@ pub fn w_call(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
 pub fn w_ret(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_ret(s)
 }
+#[inline(always)]
+pub fn w_cmov(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_cmov(s)
+}
 
 // Special handlers
 #[inline(always)]
@@ -242,6 +278,18 @@ pub fn w_trunc16(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
 pub fn w_trunc32(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_trunc32(s)
 }
+#[inline(always)]
+pub fn w_bswap16(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_bswap16(s)
+}
+#[inline(always)]
+pub fn w_bswap32(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_bswap32(s)
+}
+#[inline(always)]
+pub fn w_bswap(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_bswap(s)
+}
 
 // Memory handlers
 #[inline(always)]
@@ -322,6 +370,50 @@ pub fn w_heap_store64(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
 pub fn w_heap_size(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
     super::handle_heap_size(s)
 }
+#[inline(always)]
+pub fn w_heap_store8_grow(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store8_grow(s)
+}
+#[inline(always)]
+pub fn w_heap_store16_grow(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store16_grow(s)
+}
+#[inline(always)]
+pub fn w_heap_store32_grow(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store32_grow(s)
+}
+#[inline(always)]
+pub fn w_heap_store64_grow(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store64_grow(s)
+}
+#[inline(always)]
+pub fn w_heap_load16_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_load16_be(s)
+}
+#[inline(always)]
+pub fn w_heap_load32_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_load32_be(s)
+}
+#[inline(always)]
+pub fn w_heap_load64_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_load64_be(s)
+}
+#[inline(always)]
+pub fn w_heap_store16_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store16_be(s)
+}
+#[inline(always)]
+pub fn w_heap_store32_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store32_be(s)
+}
+#[inline(always)]
+pub fn w_heap_store64_be(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_store64_be(s)
+}
+#[inline(always)]
+pub fn w_heap_copy(s: &mut VmState, _: &NativeRegistry) -> VmResult<()> {
+    super::handle_heap_copy(s)
+}
 
 // Vector handlers
 #[inline(always)]
@@ -460,7 +552,7 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x12] = w_load_mem;
     table[0x13] = w_store_mem;
 
-    // Arithmetic (0x20-0x2C, 0x46-0x49)
+    // Arithmetic (0x20-0x2E, 0x46-0x4F)
     table[0x20] = w_add;
     table[0x21] = w_sub;
     table[0x22] = w_mul;
@@ -474,12 +566,20 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x2A] = w_ror;
     table[0x2B] = w_inc;
     table[0x2C] = w_dec;
+    table[0x2D] = w_mulh;
+    table[0x2E] = w_imulh;
     table[0x46] = w_div;
     table[0x47] = w_mod;
     table[0x48] = w_idiv;
     table[0x49] = w_imod;
-
-    // Control (0x30-0x39)
+    table[0x4A] = w_pow;
+    table[0x4B] = w_adc;
+    table[0x4C] = w_sbb;
+    table[0x4D] = w_bfx;
+    table[0x4E] = w_bfi;
+    table[0x4F] = w_imod_euclid;
+
+    // Control (0x30-0x3A)
     table[0x30] = w_cmp;
     table[0x31] = w_jmp;
     table[0x32] = w_jz;
@@ -490,6 +590,7 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x37] = w_jle;
     table[0x38] = w_call;
     table[0x39] = w_ret;
+    table[0x3A] = w_cmov;
 
     // Special (0x40-0x45)
     table[0x40] = w_nop;
@@ -499,13 +600,16 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x44] = w_hash_check;
     table[0x45] = w_timing_check;
 
-    // Convert (0x50-0x55)
+    // Convert (0x50-0x58)
     table[0x50] = w_sext8;
     table[0x51] = w_sext16;
     table[0x52] = w_sext32;
     table[0x53] = w_trunc8;
     table[0x54] = w_trunc16;
     table[0x55] = w_trunc32;
+    table[0x56] = w_bswap16;
+    table[0x57] = w_bswap32;
+    table[0x58] = w_bswap;
 
     // Memory (0x60-0x67)
     table[0x60] = w_load8;
@@ -517,7 +621,7 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x66] = w_store32;
     table[0x67] = w_store64;
 
-    // Heap (0x70-0x7A)
+    // Heap (0x70-0x7E)
     table[0x70] = w_heap_alloc;
     table[0x71] = w_heap_free;
     table[0x72] = w_heap_load8;
@@ -529,6 +633,17 @@ pub const HANDLER_TABLE: [Handler; 256] = {
     table[0x78] = w_heap_store32;
     table[0x79] = w_heap_store64;
     table[0x7A] = w_heap_size;
+    table[0x7B] = w_heap_store8_grow;
+    table[0x7C] = w_heap_store16_grow;
+    table[0x7D] = w_heap_store32_grow;
+    table[0x7E] = w_heap_store64_grow;
+    table[0xA0] = w_heap_load16_be;
+    table[0xA1] = w_heap_load32_be;
+    table[0xA2] = w_heap_load64_be;
+    table[0xA3] = w_heap_store16_be;
+    table[0xA4] = w_heap_store32_be;
+    table[0xA5] = w_heap_store64_be;
+    table[0xA6] = w_heap_copy;
 
     // Vector (0x80-0x89)
     table[0x80] = w_vec_new;
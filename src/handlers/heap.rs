@@ -1,8 +1,9 @@
 //! Heap Operation Handlers
 //!
-//! HEAP_ALLOC, HEAP_FREE, HEAP_LOAD*, HEAP_STORE*, HEAP_SIZE
+//! HEAP_ALLOC, HEAP_FREE, HEAP_LOAD*, HEAP_STORE*, HEAP_STORE*_GROW, HEAP_SIZE,
+//! HEAP_LOAD*_BE, HEAP_STORE*_BE, HEAP_COPY
 
-use crate::error::VmResult;
+use crate::error::{VmError, VmResult};
 use crate::state::VmState;
 
 /// HEAP_ALLOC: Allocate memory on heap
@@ -94,3 +95,98 @@ pub fn handle_heap_size(state: &mut VmState) -> VmResult<()> {
     let size = state.heap_size() as u64;
     state.push(size)
 }
+
+/// Pop [address, value] and grow heap so `width` bytes at address are writable
+#[inline(always)]
+fn pop_grow_store(state: &mut VmState, width: usize) -> VmResult<(usize, u64)> {
+    let value = state.pop()?;
+    let addr = state.pop()? as usize;
+    let end = addr.checked_add(width).ok_or(VmError::HeapOutOfBounds)?;
+    state.heap_grow_to(end)?;
+    Ok((addr, value))
+}
+
+/// HEAP_STORE8_GROW: Write u8 to heap, growing it up to heap_limit
+/// Stack: [address, value] -> []
+pub fn handle_heap_store8_grow(state: &mut VmState) -> VmResult<()> {
+    let (addr, value) = pop_grow_store(state, 1)?;
+    state.heap_write_u8(addr, value as u8)
+}
+
+/// HEAP_STORE16_GROW: Write u16 to heap (little-endian), growing it up to heap_limit
+/// Stack: [address, value] -> []
+pub fn handle_heap_store16_grow(state: &mut VmState) -> VmResult<()> {
+    let (addr, value) = pop_grow_store(state, 2)?;
+    state.heap_write_u16(addr, value as u16)
+}
+
+/// HEAP_STORE32_GROW: Write u32 to heap (little-endian), growing it up to heap_limit
+/// Stack: [address, value] -> []
+pub fn handle_heap_store32_grow(state: &mut VmState) -> VmResult<()> {
+    let (addr, value) = pop_grow_store(state, 4)?;
+    state.heap_write_u32(addr, value as u32)
+}
+
+/// HEAP_STORE64_GROW: Write u64 to heap (little-endian), growing it up to heap_limit
+/// Stack: [address, value] -> []
+pub fn handle_heap_store64_grow(state: &mut VmState) -> VmResult<()> {
+    let (addr, value) = pop_grow_store(state, 8)?;
+    state.heap_write_u64(addr, value)
+}
+
+/// HEAP_LOAD16_BE: Read u16 from heap (big-endian)
+/// Stack: [address] -> [value]
+pub fn handle_heap_load16_be(state: &mut VmState) -> VmResult<()> {
+    let addr = state.pop()? as usize;
+    let value = u16::from_be_bytes(state.heap_read_u16(addr)?.to_le_bytes()) as u64;
+    state.push(value)
+}
+
+/// HEAP_LOAD32_BE: Read u32 from heap (big-endian)
+/// Stack: [address] -> [value]
+pub fn handle_heap_load32_be(state: &mut VmState) -> VmResult<()> {
+    let addr = state.pop()? as usize;
+    let value = u32::from_be_bytes(state.heap_read_u32(addr)?.to_le_bytes()) as u64;
+    state.push(value)
+}
+
+/// HEAP_LOAD64_BE: Read u64 from heap (big-endian)
+/// Stack: [address] -> [value]
+pub fn handle_heap_load64_be(state: &mut VmState) -> VmResult<()> {
+    let addr = state.pop()? as usize;
+    let value = u64::from_be_bytes(state.heap_read_u64(addr)?.to_le_bytes());
+    state.push(value)
+}
+
+/// HEAP_STORE16_BE: Write u16 to heap (big-endian)
+/// Stack: [address, value] -> []
+pub fn handle_heap_store16_be(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()? as u16;
+    let addr = state.pop()? as usize;
+    state.heap_write_u16(addr, u16::from_le_bytes(value.to_be_bytes()))
+}
+
+/// HEAP_STORE32_BE: Write u32 to heap (big-endian)
+/// Stack: [address, value] -> []
+pub fn handle_heap_store32_be(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()? as u32;
+    let addr = state.pop()? as usize;
+    state.heap_write_u32(addr, u32::from_le_bytes(value.to_be_bytes()))
+}
+
+/// HEAP_STORE64_BE: Write u64 to heap (big-endian)
+/// Stack: [address, value] -> []
+pub fn handle_heap_store64_be(state: &mut VmState) -> VmResult<()> {
+    let value = state.pop()?;
+    let addr = state.pop()? as usize;
+    state.heap_write_u64(addr, u64::from_le_bytes(value.to_be_bytes()))
+}
+
+/// HEAP_COPY: Copy bytes between heap regions (memmove semantics)
+/// Stack: [src, dst, len] -> []
+pub fn handle_heap_copy(state: &mut VmState) -> VmResult<()> {
+    let len = state.pop()? as usize;
+    let dst = state.pop()? as usize;
+    let src = state.pop()? as usize;
+    state.heap_copy(src, dst, len)
+}
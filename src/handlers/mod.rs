@@ -38,6 +38,9 @@ pub use string::*;
 pub use arithmetic::{
     handle_shl, handle_shr, handle_rol, handle_ror,
     handle_div, handle_mod, handle_idiv, handle_imod,
+    handle_pow, handle_adc, handle_sbb,
+    handle_bfx, handle_bfi, handle_imod_euclid,
+    handle_mulh, handle_imulh,
 };
 
 // Mutated arithmetic handlers - use build-time generated versions
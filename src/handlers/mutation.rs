@@ -10,3 +10,74 @@
 
 // Include the build-time generated mutations
 include!(concat!(env!("OUT_DIR"), "/mutated_handlers.rs"));
+
+/// Operands for `verify_mutated_handlers`: edges around zero, the sign bit
+/// and u64::MAX, plus one mixed pattern
+#[cfg(feature = "handler_mutation")]
+const PROBES: [u64; 8] = [
+    0,
+    1,
+    2,
+    0x7FFF_FFFF_FFFF_FFFF,
+    0x8000_0000_0000_0000,
+    u64::MAX - 1,
+    u64::MAX,
+    0xDEAD_BEEF_CAFE_BABE,
+];
+
+/// Runtime self-check of this build's mutated handlers
+///
+/// Runs every mutated handler against its reference in `arithmetic` over
+/// edge-case operands and compares both the result and the flags. Cheap
+/// enough to call once at startup; `false` means the mutation generator
+/// emitted a variant that diverges from the reference semantics.
+#[cfg(feature = "handler_mutation")]
+pub fn verify_mutated_handlers() -> bool {
+    use super::arithmetic;
+    type Handler = fn(&mut VmState) -> VmResult<()>;
+
+    let binary: [(Handler, Handler); 6] = [
+        (mutated_add, arithmetic::handle_add),
+        (mutated_sub, arithmetic::handle_sub),
+        (mutated_mul, arithmetic::handle_mul),
+        (mutated_xor, arithmetic::handle_xor),
+        (mutated_and, arithmetic::handle_and),
+        (mutated_or, arithmetic::handle_or),
+    ];
+    let unary: [(Handler, Handler); 3] = [
+        (mutated_not, arithmetic::handle_not),
+        (mutated_inc, arithmetic::handle_inc),
+        (mutated_dec, arithmetic::handle_dec),
+    ];
+
+    let mut state = VmState::new(&[], &[]);
+    let mut run = |handler: Handler, operands: &[u64]| {
+        state.stack.clear();
+        state.flags = 0;
+        for &value in operands {
+            state.push(value).ok()?;
+        }
+        handler(&mut state).ok()?;
+        Some((state.pop().ok()?, state.flags))
+    };
+
+    for (mutated, reference) in binary {
+        for a in PROBES {
+            for b in PROBES {
+                let got = run(mutated, &[a, b]);
+                if got.is_none() || got != run(reference, &[a, b]) {
+                    return false;
+                }
+            }
+        }
+    }
+    for (mutated, reference) in unary {
+        for a in PROBES {
+            let got = run(mutated, &[a]);
+            if got.is_none() || got != run(reference, &[a]) {
+                return false;
+            }
+        }
+    }
+    true
+}
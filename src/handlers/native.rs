@@ -3,7 +3,7 @@
 //! NATIVE_CALL, NATIVE_READ, NATIVE_WRITE, INPUT_LEN
 
 use crate::error::{VmError, VmResult};
-use crate::native::{NativeRegistry, MAX_NATIVE_ARGS};
+use crate::native::{call_guarded, NativeRegistry, MAX_NATIVE_ARGS};
 use crate::state::VmState;
 
 /// NATIVE_CALL: Call native function from registry or table
@@ -14,6 +14,10 @@ use crate::state::VmState;
 /// Priority:
 /// 1. If native_table is set on VmState, use that (for vm_protect macro)
 /// 2. Otherwise fall back to NativeRegistry
+///
+/// An index found in neither fails with `UnknownNative` and is left in
+/// `state.unknown_native`; a native that panics fails the call with
+/// `NativeCallFailed` (std only).
 pub fn handle_native_call(state: &mut VmState, registry: &NativeRegistry) -> VmResult<()> {
     let func_id = state.read_u8()?;
     let arg_count = state.read_u8()? as usize;
@@ -31,11 +35,15 @@ pub fn handle_native_call(state: &mut VmState, registry: &NativeRegistry) -> VmR
 
     // Try native table first (for vm_protect macro)
     if let Some(native_fn) = state.get_native_fn(func_id as usize) {
-        let result = native_fn(&args[..arg_count]);
+        let result = call_guarded(&native_fn, &args[..arg_count])?;
         return state.push(result);
     }
 
     // Fall back to registry
+    if !registry.is_registered(func_id) {
+        state.unknown_native = Some(func_id);
+        return Err(VmError::UnknownNative);
+    }
     let result = registry.call(func_id, &args[..arg_count])?;
 
     // Push result
@@ -53,8 +61,7 @@ pub fn handle_native_read(state: &mut VmState) -> VmResult<()> {
 pub fn handle_native_write(state: &mut VmState) -> VmResult<()> {
     let _offset = state.read_u16()?;
     let value = state.pop()?;
-    state.output.push(value as u8);
-    Ok(())
+    state.append_output(&[value as u8])
 }
 
 /// INPUT_LEN: Push input length to stack
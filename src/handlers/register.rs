@@ -34,6 +34,5 @@ pub fn handle_store_mem(state: &mut VmState) -> VmResult<()> {
     let _addr_reg = state.read_u8()?;
     let src_reg = state.read_u8()?;
     let value = state.get_reg(src_reg)?;
-    state.output.extend_from_slice(&value.to_le_bytes());
-    Ok(())
+    state.append_output(&value.to_le_bytes())
 }
@@ -51,6 +51,9 @@ pub fn handle_opaque_false(state: &mut VmState) -> VmResult<()> {
 /// HASH_CHECK: Verify bytecode integrity
 pub fn handle_hash_check(state: &mut VmState) -> VmResult<()> {
     let expected = state.read_u32()?;
+    if !state.anti_analysis {
+        return Ok(());
+    }
 
     // FNV-1a hash of bytecode (randomized constants per build)
     let mut hash = crate::build_config::FNV_BASIS_32;
@@ -83,6 +86,11 @@ pub fn handle_timing_check(state: &mut VmState) -> VmResult<()> {
 
     #[cfg(not(feature = "vm_debug"))]
     {
+        // Deterministic mode
+        if !state.anti_analysis {
+            return Ok(());
+        }
+
         // Get current time
         let current_ns = state.current_time_ns();
 
@@ -29,31 +29,31 @@ const OFFSET_DATA: usize = 24;
 /// Read string length from heap
 #[inline]
 fn str_get_length(state: &VmState, str_addr: usize) -> VmResult<u64> {
-    state.heap_read_u64(str_addr + OFFSET_LENGTH)
+    state.heap_read_u64(str_addr.saturating_add(OFFSET_LENGTH))
 }
 
 /// Read string capacity from heap
 #[inline]
 fn str_get_capacity(state: &VmState, str_addr: usize) -> VmResult<u64> {
-    state.heap_read_u64(str_addr + OFFSET_CAPACITY)
+    state.heap_read_u64(str_addr.saturating_add(OFFSET_CAPACITY))
 }
 
 /// Write string length to heap
 #[inline]
 fn str_set_length(state: &mut VmState, str_addr: usize, length: u64) -> VmResult<()> {
-    state.heap_write_u64(str_addr + OFFSET_LENGTH, length)
+    state.heap_write_u64(str_addr.saturating_add(OFFSET_LENGTH), length)
 }
 
 /// Read byte at index
 #[inline]
 fn str_read_byte(state: &VmState, str_addr: usize, index: u64) -> VmResult<u8> {
-    state.heap_read_u8(str_addr + OFFSET_DATA + index as usize)
+    state.heap_read_u8(str_addr.saturating_add(OFFSET_DATA).saturating_add(index as usize))
 }
 
 /// Write byte at index
 #[inline]
 fn str_write_byte(state: &mut VmState, str_addr: usize, index: u64, value: u8) -> VmResult<()> {
-    state.heap_write_u8(str_addr + OFFSET_DATA + index as usize, value)
+    state.heap_write_u8(str_addr.saturating_add(OFFSET_DATA).saturating_add(index as usize), value)
 }
 
 // ============================================================================
@@ -66,15 +66,16 @@ pub fn handle_str_new(state: &mut VmState) -> VmResult<()> {
     let capacity = state.pop()?;
 
     // Calculate total size: header + capacity bytes
-    let total_size = VEC_HEADER_SIZE as u64 + capacity;
+    let total_size = capacity.checked_add(VEC_HEADER_SIZE as u64)
+        .ok_or(VmError::HeapOutOfMemory)?;
 
     // Allocate on heap
     let str_addr = state.heap_alloc(total_size as usize)? as usize;
 
     // Initialize header (elem_size = 1 for strings)
-    state.heap_write_u64(str_addr + OFFSET_CAPACITY, capacity)?;
-    state.heap_write_u64(str_addr + OFFSET_LENGTH, 0)?;
-    state.heap_write_u64(str_addr + OFFSET_ELEM_SIZE, 1)?;
+    state.heap_write_u64(str_addr.saturating_add(OFFSET_CAPACITY), capacity)?;
+    state.heap_write_u64(str_addr.saturating_add(OFFSET_LENGTH), 0)?;
+    state.heap_write_u64(str_addr.saturating_add(OFFSET_ELEM_SIZE), 1)?;
 
     state.push(str_addr as u64)
 }
@@ -235,13 +236,14 @@ pub fn handle_str_concat(state: &mut VmState) -> VmResult<()> {
         .ok_or(VmError::HeapOutOfMemory)?;
 
     // Allocate new string
-    let total_size = VEC_HEADER_SIZE as u64 + new_len;
+    let total_size = new_len.checked_add(VEC_HEADER_SIZE as u64)
+        .ok_or(VmError::HeapOutOfMemory)?;
     let new_addr = state.heap_alloc(total_size as usize)? as usize;
 
     // Initialize header
-    state.heap_write_u64(new_addr + OFFSET_CAPACITY, new_len)?;
-    state.heap_write_u64(new_addr + OFFSET_LENGTH, new_len)?;
-    state.heap_write_u64(new_addr + OFFSET_ELEM_SIZE, 1)?;
+    state.heap_write_u64(new_addr.saturating_add(OFFSET_CAPACITY), new_len)?;
+    state.heap_write_u64(new_addr.saturating_add(OFFSET_LENGTH), new_len)?;
+    state.heap_write_u64(new_addr.saturating_add(OFFSET_ELEM_SIZE), 1)?;
 
     // Copy str1
     for i in 0..len1 {
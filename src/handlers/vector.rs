@@ -35,31 +35,33 @@ const OFFSET_DATA: usize = 24;
 /// Read vector capacity from heap
 #[inline]
 fn vec_get_capacity(state: &VmState, vec_addr: usize) -> VmResult<u64> {
-    state.heap_read_u64(vec_addr + OFFSET_CAPACITY)
+    state.heap_read_u64(vec_addr.saturating_add(OFFSET_CAPACITY))
 }
 
 /// Read vector length from heap
 #[inline]
 fn vec_get_length(state: &VmState, vec_addr: usize) -> VmResult<u64> {
-    state.heap_read_u64(vec_addr + OFFSET_LENGTH)
+    state.heap_read_u64(vec_addr.saturating_add(OFFSET_LENGTH))
 }
 
 /// Read vector element size from heap
 #[inline]
 fn vec_get_elem_size(state: &VmState, vec_addr: usize) -> VmResult<u64> {
-    state.heap_read_u64(vec_addr + OFFSET_ELEM_SIZE)
+    state.heap_read_u64(vec_addr.saturating_add(OFFSET_ELEM_SIZE))
 }
 
 /// Write vector length to heap
 #[inline]
 fn vec_set_length(state: &mut VmState, vec_addr: usize, length: u64) -> VmResult<()> {
-    state.heap_write_u64(vec_addr + OFFSET_LENGTH, length)
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_LENGTH), length)
 }
 
 /// Calculate data offset for element at index
 #[inline]
 fn vec_data_offset(vec_addr: usize, index: u64, elem_size: u64) -> usize {
-    vec_addr + OFFSET_DATA + (index as usize * elem_size as usize)
+    vec_addr
+        .saturating_add(OFFSET_DATA)
+        .saturating_add((index as usize).saturating_mul(elem_size as usize))
 }
 
 /// Read element from vector based on element size
@@ -125,15 +127,16 @@ pub fn handle_vec_new(state: &mut VmState) -> VmResult<()> {
     // Calculate total size: header + (capacity * elem_size)
     let data_size = capacity.checked_mul(elem_size)
         .ok_or(VmError::HeapOutOfMemory)?;
-    let total_size = VEC_HEADER_SIZE as u64 + data_size;
+    let total_size = data_size.checked_add(VEC_HEADER_SIZE as u64)
+        .ok_or(VmError::HeapOutOfMemory)?;
 
     // Allocate on heap
     let vec_addr = state.heap_alloc(total_size as usize)? as usize;
 
     // Initialize header
-    state.heap_write_u64(vec_addr + OFFSET_CAPACITY, capacity)?;
-    state.heap_write_u64(vec_addr + OFFSET_LENGTH, 0)?;
-    state.heap_write_u64(vec_addr + OFFSET_ELEM_SIZE, elem_size)?;
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_CAPACITY), capacity)?;
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_LENGTH), 0)?;
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_ELEM_SIZE), elem_size)?;
 
     // Return vector address
     state.push(vec_addr as u64)
@@ -257,15 +260,16 @@ pub fn handle_vec_repeat(state: &mut VmState) -> VmResult<()> {
     // Calculate total size
     let data_size = count.checked_mul(elem_size)
         .ok_or(VmError::HeapOutOfMemory)?;
-    let total_size = VEC_HEADER_SIZE as u64 + data_size;
+    let total_size = data_size.checked_add(VEC_HEADER_SIZE as u64)
+        .ok_or(VmError::HeapOutOfMemory)?;
 
     // Allocate on heap
     let vec_addr = state.heap_alloc(total_size as usize)? as usize;
 
     // Initialize header
-    state.heap_write_u64(vec_addr + OFFSET_CAPACITY, count)?;
-    state.heap_write_u64(vec_addr + OFFSET_LENGTH, count)?; // Length = count (fully initialized)
-    state.heap_write_u64(vec_addr + OFFSET_ELEM_SIZE, elem_size)?;
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_CAPACITY), count)?;
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_LENGTH), count)?; // Length = count (fully initialized)
+    state.heap_write_u64(vec_addr.saturating_add(OFFSET_ELEM_SIZE), elem_size)?;
 
     // Fill with repeated value [expr.array.repeat-copy]
     for i in 0..count {
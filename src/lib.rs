@@ -55,10 +55,16 @@ pub mod state;
 pub mod handlers;
 pub mod engine;
 pub mod bytecode;
+pub mod compress;
 pub mod crypto;
 pub mod native;
 pub mod integrity;
 pub mod smc;
+pub mod stream;
+pub mod passes;
+pub mod sandbox;
+pub mod builder;
+pub mod watermark;
 pub mod string_obfuscation;
 
 // White-box cryptography module (required for encrypted bytecode)
@@ -80,13 +86,27 @@ pub use alloc::vec::Vec as StdVec;
 
 // Re-exports
 pub use error::{VmError, VmResult};
-pub use state::VmState;
-pub use engine::{execute, execute_with_state, execute_with_natives, execute_with_native_table, run, run_with_natives, run_with_native_table};
-pub use bytecode::{BytecodeHeader, BytecodePackage, ProtectionLevel, BuildInfo};
+pub use state::{VmState, VmOutcome, AllocStats};
+#[cfg(feature = "zeroize")]
+pub use state::WipeOnDrop;
+pub use engine::{execute, execute_deterministic, execute_absolute, execute_verified, execute_with_encrypted_input, execute_with_state, execute_capturing, execute_with_natives, execute_with_native_table, execute_with_natives_traced, execute_with_native_table_traced, execute_with_native_observer, execute_batch, run, run_in_state, run_with_natives, run_with_native_table, run_with_native_observer};
+pub use bytecode::{BytecodeHeader, BytecodePackage, BytecodeBundle, BytecodeStats, ProtectionLevel, BuildInfo};
 pub use crypto::CryptoContext;
-pub use native::{NativeRegistry, NativeRegistryBuilder, NativeFunction, standard_ids};
+pub use native::{NativeRegistry, NativeRegistryBuilder, NativeOverrides, NativeFunction, NativeOverride, standard_ids};
 pub use integrity::{IntegrityTable, IntegrityError, compute_hash, verify_hash};
-pub use smc::{SmcConfig, execute_smc, execute_smc_with_natives, encrypt_bytecode, decrypt_bytecode};
+pub use smc::{SmcConfig, SmcStats, execute_smc, execute_smc_with_natives, execute_smc_with_stats, encrypt_bytecode, decrypt_bytecode};
+pub use stream::{execute_stream, execute_stream_with_window};
+pub use passes::{BytecodePass, PassPipeline};
+pub use builder::BytecodeBuilder;
+pub use sandbox::Sandbox;
+#[cfg(feature = "std")]
+pub use passes::set_obfuscation_seed;
+#[cfg(feature = "std")]
+pub use engine::{execute_with_timeout, run_with_timeout};
+#[cfg(feature = "std")]
+pub use stream::execute_reader;
+#[cfg(feature = "parallel")]
+pub use engine::execute_batch_parallel;
 
 /// Build-time generated configuration
 pub mod build_config {
@@ -116,4 +136,77 @@ pub fn fnv1a_hash32(data: &[u8]) -> u32 {
         hash = hash.wrapping_mul(build_config::FNV_PRIME_32);
     }
     hash
-}
\ No newline at end of file
+}
+
+/// Constant-time select: `a` if `cond != 0`, otherwise `b`
+///
+/// Intended for secret-dependent choices in license and crypto code. The
+/// result is computed from a mask derived from `cond`, with no branch on
+/// `cond`, and both `a` and `b` are always evaluated by the caller. The
+/// `CMOV` opcode selects with the same mask.
+///
+/// Limits: only the selection itself is branch-free. Computing `a` and `b`,
+/// and any memory access that depends on the result (indexing a table with
+/// it, for example), can still leak through timing or cache behavior.
+#[inline]
+pub fn select(cond: u64, a: u64, b: u64) -> u64 {
+    // All ones if cond != 0, zero otherwise
+    let mask = ((cond | cond.wrapping_neg()) >> 63).wrapping_neg();
+    let mask = core::hint::black_box(mask);
+    (a & mask) | (b & !mask)
+}
+
+/// Integer types [`saturating_cast`] can convert to
+pub trait SaturatingCast: Sized {
+    /// Clamp `value` into this type's range and convert
+    fn saturate_from(value: i128) -> Self;
+}
+
+/// Integer types [`saturating_cast`] accepts as a source
+pub trait SaturatingSource {
+    /// Widen to `i128`; only `u128` above `i128::MAX` is clamped
+    fn widen(self) -> i128;
+}
+
+macro_rules! impl_saturating_cast {
+    ($($t:ty),*) => {$(
+        impl SaturatingCast for $t {
+            #[inline]
+            fn saturate_from(value: i128) -> Self {
+                value.clamp(<$t>::MIN as i128, <$t>::MAX as i128) as $t
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_saturating_source {
+    ($($t:ty),*) => {$(
+        impl SaturatingSource for $t {
+            #[inline]
+            fn widen(self) -> i128 {
+                self as i128
+            }
+        }
+    )*};
+}
+
+impl_saturating_cast!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl_saturating_source!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl SaturatingSource for u128 {
+    #[inline]
+    fn widen(self) -> i128 {
+        i128::try_from(self).unwrap_or(i128::MAX)
+    }
+}
+
+/// Saturating integer cast: `value` clamped to `T`'s range
+///
+/// Unlike `as`, which truncates (`300 as u8 == 44`), out-of-range values
+/// stick to the nearest bound (`saturating_cast::<u8>(300) == 255`, and
+/// negative values become 0 for unsigned targets). Any primitive integer,
+/// including `usize` and `isize`, is accepted as the source.
+#[inline]
+pub fn saturating_cast<T: SaturatingCast>(value: impl SaturatingSource) -> T {
+    T::saturate_from(value.widen())
+}
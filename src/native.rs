@@ -22,6 +22,14 @@
 //! assert_eq!(registry.call(0, &[]).unwrap(), 42);
 //! assert_eq!(registry.call(1, &[21]).unwrap(), 42);
 //! ```
+//!
+//! # Panicking natives
+//!
+//! With `std`, a native that panics is caught at the call boundary and the
+//! run fails with `VmError::NativeCallFailed` instead of unwinding through
+//! the VM. This only works when panics unwind: under `panic = "abort"`
+//! (the release profile) or without `std`, a panicking native still
+//! aborts, so natives should report failure through their return value.
 
 #[cfg(not(feature = "std"))]
 use alloc::{boxed::Box, vec::Vec};
@@ -38,6 +46,9 @@ pub const MAX_NATIVE_ARGS: usize = 8;
 /// Takes a slice of u64 arguments, returns a u64 result
 pub type NativeFunction = Box<dyn Fn(&[u64]) -> u64 + Send + Sync>;
 
+/// Plain function pointer used by [`NativeOverrides`]
+pub type NativeOverride = fn(&[u64]) -> u64;
+
 /// Native function registry
 ///
 /// Stores registered native functions that can be called from VM bytecode.
@@ -108,10 +119,11 @@ impl NativeRegistry {
     /// # Returns
     /// * `Ok(result)` - The function's return value
     /// * `Err(NativeFunctionNotFound)` - If no function is registered with this ID
+    /// * `Err(NativeCallFailed)` - If the function panicked (std only)
     pub fn call(&self, id: u8, args: &[u64]) -> VmResult<u64> {
         let idx = id as usize;
         match &self.functions[idx] {
-            Some(func) => Ok(func(args)),
+            Some(func) => call_guarded(func, args),
             None => Err(VmError::NativeFunctionNotFound),
         }
     }
@@ -134,6 +146,21 @@ impl NativeRegistry {
     }
 }
 
+/// Call a native, turning a panic into `NativeCallFailed` where unwinding
+/// is available
+#[inline]
+pub(crate) fn call_guarded<F: Fn(&[u64]) -> u64 + ?Sized>(func: &F, args: &[u64]) -> VmResult<u64> {
+    #[cfg(feature = "std")]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| func(args)))
+            .map_err(|_| VmError::NativeCallFailed)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        Ok(func(args))
+    }
+}
+
 /// Standard native function IDs
 ///
 /// These are predefined IDs for common anticheat operations.
@@ -143,6 +170,78 @@ pub mod standard_ids {
     pub use crate::build_config::native_ids::*;
 }
 
+/// Platform overrides for the standard native IDs
+///
+/// Maps standard IDs to plain function pointers so each platform can wire
+/// real detection logic. IDs without an override fall back to the default
+/// implementation (detection checks report "not detected").
+///
+/// ```rust
+/// use aegis_vm::native::{NativeOverrides, standard_ids};
+///
+/// let registry = NativeOverrides::new()
+///     .with_override(standard_ids::CHECK_ROOT, |_| 1)
+///     .registry();
+/// assert_eq!(registry.call(standard_ids::CHECK_ROOT, &[]).unwrap(), 1);
+/// assert_eq!(registry.call(standard_ids::CHECK_DEBUGGER, &[]).unwrap(), 0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct NativeOverrides {
+    functions: [Option<NativeOverride>; MAX_NATIVE_FUNCTIONS],
+}
+
+impl Default for NativeOverrides {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NativeOverrides {
+    /// Create an empty override set (all defaults)
+    pub fn new() -> Self {
+        Self {
+            functions: [None; MAX_NATIVE_FUNCTIONS],
+        }
+    }
+
+    /// Override the function for the given ID
+    pub fn with_override(mut self, id: u8, func: NativeOverride) -> Self {
+        self.functions[id as usize] = Some(func);
+        self
+    }
+
+    /// Get the override for the given ID, if any
+    pub fn get(&self, id: u8) -> Option<NativeOverride> {
+        self.functions[id as usize]
+    }
+
+    /// Build a registry with the overrides applied on top of the defaults
+    pub fn registry(&self) -> NativeRegistry {
+        let mut builder = NativeRegistryBuilder::new();
+        for (id, func) in self.functions.iter().enumerate() {
+            if let Some(func) = *func {
+                builder = builder.with_function(id as u8, func);
+            }
+        }
+
+        // Defaults only fill IDs that were not overridden
+        [
+            standard_ids::CHECK_ROOT,
+            standard_ids::CHECK_EMULATOR,
+            standard_ids::CHECK_HOOKS,
+            standard_ids::CHECK_DEBUGGER,
+            standard_ids::CHECK_TAMPER,
+            standard_ids::READ_MEMORY,
+            standard_ids::GET_DEVICE_HASH,
+        ]
+        .into_iter()
+        .fold(builder, |builder, id| builder.with_function(id, |_| 0))
+        .with_timestamp()
+        .with_hash()
+        .build()
+    }
+}
+
 /// Builder pattern for creating a NativeRegistry with common functions
 pub struct NativeRegistryBuilder {
     registry: NativeRegistry,
@@ -206,8 +305,118 @@ impl NativeRegistryBuilder {
         })
     }
 
+    /// Add the hex formatting natives ([`hex_char`], [`hex_word`])
+    pub fn with_hex(self, char_id: u8, word_id: u8) -> Self {
+        self.with_function(char_id, hex_char).with_function(word_id, hex_word)
+    }
+
     /// Build the registry
     pub fn build(self) -> NativeRegistry {
         self.registry
     }
 }
+
+// ========== Hex formatting ==========
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Format a u64 as 16 lowercase hex digits, most significant first
+///
+/// Same text as `format!("{:016x}", value)`, without allocating.
+pub fn to_hex(value: u64) -> [u8; 16] {
+    let mut out = [0u8; 16];
+    for (i, digit) in out.iter_mut().enumerate() {
+        *digit = HEX_DIGITS[(value >> (60 - 4 * i)) as usize & 0xF];
+    }
+    out
+}
+
+/// Native: ASCII hex digit `index` (0 = most significant) of a u64
+///
+/// Args: `[value, index]`. Returns 0 for an index past 15, so a STR can be
+/// built one `STR_PUSH` per digit.
+pub fn hex_char(args: &[u64]) -> u64 {
+    let value = args.first().copied().unwrap_or(0);
+    let index = args.get(1).copied().unwrap_or(0);
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| to_hex(value).get(i).copied())
+        .map_or(0, u64::from)
+}
+
+/// Native: 8 ASCII hex digits of a u64, packed big-endian
+///
+/// Args: `[value, word]`, where word 0 is the high half and 1 the low half.
+/// `HEAP_STORE64_BE` of the result writes the digits in reading order, so
+/// two calls fill a 16-byte heap destination.
+pub fn hex_word(args: &[u64]) -> u64 {
+    let value = args.first().copied().unwrap_or(0);
+    let hex = to_hex(value);
+    let digits = match args.get(1).copied().unwrap_or(0) {
+        0 => &hex[..8],
+        1 => &hex[8..],
+        _ => return 0,
+    };
+    digits.iter().fold(0, |acc, &d| acc << 8 | d as u64)
+}
+
+/// Build a native table from functions and closures, in NATIVE_CALL id order
+///
+/// `natives![f0, f1, ...]` expands to a `[NativeOverride; N]` array for
+/// `execute_with_native_table`. Closures take their argument type from the
+/// table, so `|args| args[0] + args[1]` needs no annotation. Only
+/// non-capturing closures coerce to `fn`; for capturing ones use
+/// `natives![registry: ...]`, which boxes each entry into a `NativeRegistry`
+/// for `execute_with_natives`. Ids are u8, so more than 256 entries is a
+/// compile error.
+///
+/// ```rust
+/// use aegis_vm::{natives, execute_with_native_table, execute_with_natives, BytecodeBuilder};
+///
+/// fn square(args: &[u64]) -> u64 {
+///     args[0] * args[0]
+/// }
+///
+/// // square(2 + 3)
+/// let code = BytecodeBuilder::new()
+///     .push_imm8(2).push_imm8(3).native_call(0, 2)
+///     .native_call(1, 1)
+///     .halt()
+///     .build()
+///     .unwrap();
+///
+/// let table = natives![|args| args[0] + args[1], square];
+/// assert_eq!(execute_with_native_table(&code, &[], &table), Ok(25));
+///
+/// let offset = 10;
+/// let registry = natives![registry: move |args| args[0] + args[1] + offset, square];
+/// assert_eq!(execute_with_natives(&code, &[], &registry), Ok(225));
+/// ```
+#[macro_export]
+macro_rules! natives {
+    // Compile error past 256 entries, where ids would wrap around u8
+    (@check $($f:expr),*) => {
+        const _: () = assert!(
+            <[()]>::len(&[$($crate::natives!(@unit $f)),*]) <= $crate::native::MAX_NATIVE_FUNCTIONS,
+            "natives!: more than 256 entries"
+        );
+    };
+    (@unit $f:expr) => { () };
+    (registry: $($f:expr),* $(,)?) => {{
+        $crate::natives!(@check $($f),*);
+        let mut registry = $crate::native::NativeRegistry::new();
+        let mut id: usize = 0;
+        $(
+            registry.register_replace(id as u8, $f);
+            id += 1;
+        )*
+        registry
+    }};
+    ($($f:expr),* $(,)?) => {{
+        $crate::natives!(@check $($f),*);
+        [$({
+            let f: $crate::native::NativeOverride = $f;
+            f
+        }),*]
+    }};
+}
@@ -117,6 +117,14 @@ pub mod arithmetic {
     /// Format: DEC
     pub const DEC: u8 = 0x2C;
 
+    /// Unsigned multiply-high: high 64 bits of (a as u128) * (b as u128)
+    /// Format: MULH
+    pub const MULH: u8 = 0x2D;
+
+    /// Signed multiply-high: high 64 bits of (a as i128) * (b as i128)
+    /// Format: IMULH
+    pub const IMULH: u8 = 0x2E;
+
     /// Unsigned division: a / b (division by zero returns 0)
     /// Format: DIV
     pub const DIV: u8 = 0x46;
@@ -129,9 +137,35 @@ pub mod arithmetic {
     /// Format: IDIV
     pub const IDIV: u8 = 0x48;
 
-    /// Signed modulo: (a as i64) % (b as i64)
+    /// Signed modulo: (a as i64) % (b as i64), truncated (sign of a)
     /// Format: IMOD
     pub const IMOD: u8 = 0x49;
+
+    /// Pop 2, push a raised to the power b (wrapping on overflow)
+    /// Format: POW
+    pub const POW: u8 = 0x4A;
+
+    /// Add with carry: a + b + CF, CF = carry out
+    /// Format: ADC
+    pub const ADC: u8 = 0x4B;
+
+    /// Subtract with borrow: a - b - CF, CF = borrow out
+    /// Format: SBB
+    pub const SBB: u8 = 0x4C;
+
+    /// Bitfield extract: (x >> offset) & ((1 << width) - 1)
+    /// Stack: [x] -> [field]
+    /// Format: BFX <offset u8> <width u8>
+    pub const BFX: u8 = 0x4D;
+
+    /// Bitfield insert: replace width bits of x at offset with the low bits of field
+    /// Stack: [x, field] -> [x']
+    /// Format: BFI <offset u8> <width u8>
+    pub const BFI: u8 = 0x4E;
+
+    /// Signed Euclidean remainder: (a as i64).rem_euclid(b as i64), never negative
+    /// Format: IMOD_EUCLID
+    pub const IMOD_EUCLID: u8 = 0x4F;
 }
 
 /// Comparison & Control Flow
@@ -175,6 +209,11 @@ pub mod control {
     /// Return from subroutine
     /// Format: RET
     pub const RET: u8 = 0x39;
+
+    /// Branchless select: pop (cond, a, b), push a if cond != 0 else b
+    /// Stack: [cond, a, b] -> [result]
+    /// Format: CMOV
+    pub const CMOV: u8 = 0x3A;
 }
 
 /// Special Operations (Anti-analysis)
@@ -229,6 +268,18 @@ pub mod convert {
     /// Truncate to 32-bit (mask with 0xFFFFFFFF)
     /// Format: TRUNC32
     pub const TRUNC32: u8 = 0x55;
+
+    /// Reverse the bytes of the low 16 bits (upper bits cleared)
+    /// Format: BSWAP16
+    pub const BSWAP16: u8 = 0x56;
+
+    /// Reverse the bytes of the low 32 bits (upper bits cleared)
+    /// Format: BSWAP32
+    pub const BSWAP32: u8 = 0x57;
+
+    /// Reverse all 8 bytes
+    /// Format: BSWAP
+    pub const BSWAP: u8 = 0x58;
 }
 
 /// Memory Operations (sized loads/stores)
@@ -425,6 +476,61 @@ pub mod heap {
     /// Stack: [] -> [heap_ptr]
     /// Format: HEAP_SIZE
     pub const HEAP_SIZE: u8 = 0x7A;
+
+    /// Write u8 to heap, growing it up to heap_limit if needed
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE8_GROW
+    pub const HEAP_STORE8_GROW: u8 = 0x7B;
+
+    /// Write u16 to heap (little-endian), growing it up to heap_limit if needed
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE16_GROW
+    pub const HEAP_STORE16_GROW: u8 = 0x7C;
+
+    /// Write u32 to heap (little-endian), growing it up to heap_limit if needed
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE32_GROW
+    pub const HEAP_STORE32_GROW: u8 = 0x7D;
+
+    /// Write u64 to heap (little-endian), growing it up to heap_limit if needed
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE64_GROW
+    pub const HEAP_STORE64_GROW: u8 = 0x7E;
+
+    /// Read u16 from heap (big-endian)
+    /// Stack: [address] -> [value]
+    /// Format: HEAP_LOAD16_BE
+    pub const HEAP_LOAD16_BE: u8 = 0xA0;
+
+    /// Read u32 from heap (big-endian)
+    /// Stack: [address] -> [value]
+    /// Format: HEAP_LOAD32_BE
+    pub const HEAP_LOAD32_BE: u8 = 0xA1;
+
+    /// Read u64 from heap (big-endian)
+    /// Stack: [address] -> [value]
+    /// Format: HEAP_LOAD64_BE
+    pub const HEAP_LOAD64_BE: u8 = 0xA2;
+
+    /// Write u16 to heap (big-endian)
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE16_BE
+    pub const HEAP_STORE16_BE: u8 = 0xA3;
+
+    /// Write u32 to heap (big-endian)
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE32_BE
+    pub const HEAP_STORE32_BE: u8 = 0xA4;
+
+    /// Write u64 to heap (big-endian)
+    /// Stack: [address, value] -> []
+    /// Format: HEAP_STORE64_BE
+    pub const HEAP_STORE64_BE: u8 = 0xA5;
+
+    /// Copy len bytes between heap regions (overlap-safe, like memmove)
+    /// Stack: [src, dst, len] -> []
+    /// Format: HEAP_COPY
+    pub const HEAP_COPY: u8 = 0xA6;
 }
 
 /// Native Calls (Escape to Rust)
@@ -494,10 +600,18 @@ pub fn opcode_name(op: u8) -> &'static str {
         arithmetic::ROR => "ROR",
         arithmetic::INC => "INC",
         arithmetic::DEC => "DEC",
+        arithmetic::MULH => "MULH",
+        arithmetic::IMULH => "IMULH",
         arithmetic::DIV => "DIV",
         arithmetic::MOD => "MOD",
         arithmetic::IDIV => "IDIV",
         arithmetic::IMOD => "IMOD",
+        arithmetic::POW => "POW",
+        arithmetic::ADC => "ADC",
+        arithmetic::SBB => "SBB",
+        arithmetic::BFX => "BFX",
+        arithmetic::BFI => "BFI",
+        arithmetic::IMOD_EUCLID => "IMOD_EUCLID",
 
         control::CMP => "CMP",
         control::JMP => "JMP",
@@ -509,6 +623,7 @@ pub fn opcode_name(op: u8) -> &'static str {
         control::JLE => "JLE",
         control::CALL => "CALL",
         control::RET => "RET",
+        control::CMOV => "CMOV",
 
         special::NOP => "NOP",
         special::NOP_N => "NOP_N",
@@ -523,6 +638,9 @@ pub fn opcode_name(op: u8) -> &'static str {
         convert::TRUNC8 => "TRUNC8",
         convert::TRUNC16 => "TRUNC16",
         convert::TRUNC32 => "TRUNC32",
+        convert::BSWAP16 => "BSWAP16",
+        convert::BSWAP32 => "BSWAP32",
+        convert::BSWAP => "BSWAP",
 
         memory::LOAD8 => "LOAD8",
         memory::LOAD16 => "LOAD16",
@@ -565,6 +683,17 @@ pub fn opcode_name(op: u8) -> &'static str {
         heap::HEAP_STORE32 => "HEAP_STORE32",
         heap::HEAP_STORE64 => "HEAP_STORE64",
         heap::HEAP_SIZE => "HEAP_SIZE",
+        heap::HEAP_STORE8_GROW => "HEAP_STORE8_GROW",
+        heap::HEAP_STORE16_GROW => "HEAP_STORE16_GROW",
+        heap::HEAP_STORE32_GROW => "HEAP_STORE32_GROW",
+        heap::HEAP_STORE64_GROW => "HEAP_STORE64_GROW",
+        heap::HEAP_LOAD16_BE => "HEAP_LOAD16_BE",
+        heap::HEAP_LOAD32_BE => "HEAP_LOAD32_BE",
+        heap::HEAP_LOAD64_BE => "HEAP_LOAD64_BE",
+        heap::HEAP_STORE16_BE => "HEAP_STORE16_BE",
+        heap::HEAP_STORE32_BE => "HEAP_STORE32_BE",
+        heap::HEAP_STORE64_BE => "HEAP_STORE64_BE",
+        heap::HEAP_COPY => "HEAP_COPY",
 
         native::NATIVE_CALL => "NATIVE_CALL",
         native::NATIVE_READ => "NATIVE_READ",
@@ -0,0 +1,452 @@
+//! Bytecode Obfuscation Passes
+//!
+//! Post-processing passes that rewrite finished (opcode-encoded) bytecode
+//! without changing what it computes. Passes are chained in a
+//! [`PassPipeline`]; the `paranoid` preset bundles every built-in pass that
+//! leaves register usage alone, and `paranoid_function` adds the
+//! per-function [`RegisterPermutationPass`] on top.
+//!
+//! Nothing runs these passes implicitly: `BytecodeBuilder` output and
+//! `vm_protect` bytecode (encoded by the proc-macro crate) are left as is,
+//! so a pipeline has to be applied by hand.
+//!
+//! ## Built-in Passes
+//!
+//! - [`JunkNopPass`]: inserts NOP / NOP_N padding between instructions
+//! - [`AliasSubstitutionPass`]: swaps duplicated opcodes for random aliases
+//! - [`OpaquePredicatePass`]: guards dead junk with always-taken branches
+//! - [`RegisterPermutationPass`]: renames R0-R7 by a seeded permutation
+//!
+//! [`paranoid_warning`] flags paranoid output that ended up without any
+//! anti-analysis opcode.
+//!
+//! Custom passes are plain closures `Fn(Vec<u8>) -> Vec<u8>` or any type
+//! implementing [`BytecodePass`].
+//!
+//! ## Seeding
+//!
+//! Passes built with `new(seed)` are fully deterministic. Passes built with
+//! `Default` draw their seed from a per-thread `fastrand::Rng` owned by this
+//! module, separate from `fastrand`'s global generator, so other users of
+//! `fastrand` on the thread are unaffected. With `std`,
+//! [`set_obfuscation_seed`] pins that RNG so those runs are reproducible
+//! too; without `std`, `Default` seeds come from `fastrand::u64`.
+//!
+//! ## Limitations
+//!
+//! Built-in passes return the input unchanged when it cannot be rewritten
+//! safely: undecodable bytes, HASH_CHECK (its hash covers the original
+//! bytes), or a relocated branch offset that no longer fits in an i16.
+//! Branches are assumed to be relative; don't run passes over code built
+//! with absolute jumps (`BytecodeFlags::AbsoluteJumps`).
+
+use crate::build_config::opcodes as enc;
+use crate::build_config::{opcode_aliases, OPCODE_DECODE, OPCODE_ENCODE};
+use crate::bytecode::{instruction_length, is_relative_branch, BytecodeStats};
+use crate::opcodes::{control, register, special, stack};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Seed source for `Default`-constructed passes; seeded from std's
+    /// hasher keys so creating it doesn't advance `fastrand`'s generator
+    static PASS_RNG: core::cell::RefCell<fastrand::Rng> = {
+        use std::hash::BuildHasher;
+        let seed = std::collections::hash_map::RandomState::new()
+            .hash_one(std::thread::current().id());
+        core::cell::RefCell::new(fastrand::Rng::with_seed(seed))
+    };
+}
+
+/// Seed the RNG behind `Default`-constructed passes on this thread
+///
+/// Only the pass RNG is reseeded; `fastrand`'s global generator is left
+/// alone.
+#[cfg(feature = "std")]
+pub fn set_obfuscation_seed(seed: u64) {
+    PASS_RNG.with(|rng| rng.borrow_mut().seed(seed));
+}
+
+/// Seed for a pass built without an explicit one
+#[cfg(feature = "std")]
+fn next_seed() -> u64 {
+    PASS_RNG.with(|rng| rng.borrow_mut().u64(..))
+}
+
+/// Seed for a pass built without an explicit one
+#[cfg(not(feature = "std"))]
+fn next_seed() -> u64 {
+    fastrand::u64(..)
+}
+
+/// A bytecode-to-bytecode rewrite that must preserve semantics
+pub trait BytecodePass {
+    /// Transform encoded bytecode
+    fn transform(&self, code: Vec<u8>) -> Vec<u8>;
+}
+
+impl<F: Fn(Vec<u8>) -> Vec<u8>> BytecodePass for F {
+    fn transform(&self, code: Vec<u8>) -> Vec<u8> {
+        self(code)
+    }
+}
+
+/// Ordered chain of passes
+#[derive(Default)]
+pub struct PassPipeline {
+    passes: Vec<Box<dyn BytecodePass>>,
+}
+
+impl PassPipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All built-in passes, seeded from `seed`
+    pub fn paranoid(seed: u64) -> Self {
+        Self::new()
+            .with_pass(OpaquePredicatePass::new(seed))
+            .with_pass(JunkNopPass::new(seed ^ 0x9E3779B97F4A7C15))
+            .with_pass(AliasSubstitutionPass::new(seed ^ 0xC6A4A7935BD1E995))
+    }
+
+    /// `paranoid`, preceded by the register permutation for the protected
+    /// function `name` (see [`RegisterPermutationPass::for_function`])
+    ///
+    /// Not for code whose caller presets or reads registers through
+    /// `VmState`.
+    pub fn paranoid_function(name: &str, seed: u64) -> Self {
+        let paranoid = Self::paranoid(seed);
+        let mut pipeline = Self::new().with_pass(RegisterPermutationPass::for_function(name));
+        pipeline.passes.extend(paranoid.passes);
+        pipeline
+    }
+
+    /// Append a pass
+    pub fn with_pass<P: BytecodePass + 'static>(mut self, pass: P) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Number of passes
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Check if the pipeline has no passes
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Run every pass in order
+    pub fn run(&self, code: Vec<u8>) -> Vec<u8> {
+        self.passes.iter().fold(code, |code, pass| pass.transform(code))
+    }
+}
+
+impl BytecodePass for PassPipeline {
+    fn transform(&self, code: Vec<u8>) -> Vec<u8> {
+        self.run(code)
+    }
+}
+
+/// Insert NOP / NOP_N padding between instructions
+pub struct JunkNopPass {
+    seed: u64,
+    /// Pad before roughly one in `density` instructions
+    density: u32,
+}
+
+impl Default for JunkNopPass {
+    fn default() -> Self {
+        Self::new(next_seed())
+    }
+}
+
+impl JunkNopPass {
+    /// Create with default density (one in four instructions)
+    pub fn new(seed: u64) -> Self {
+        Self { seed, density: 4 }
+    }
+
+    /// Set padding density (1 = before every instruction)
+    pub fn with_density(mut self, density: u32) -> Self {
+        self.density = density.max(1);
+        self
+    }
+}
+
+impl BytecodePass for JunkNopPass {
+    fn transform(&self, code: Vec<u8>) -> Vec<u8> {
+        let mut rng = fastrand::Rng::with_seed(self.seed);
+        rewrite(code, |_, out| {
+            if rng.u32(..self.density) != 0 {
+                return;
+            }
+            if rng.bool() {
+                out.push(enc::special::NOP);
+            } else {
+                // NOP_N skips its payload, so the payload can be anything
+                let count = rng.u8(1..=4);
+                out.extend_from_slice(&[enc::special::NOP_N, count]);
+                out.extend((0..count).map(|_| rng.u8(..)));
+            }
+        })
+    }
+}
+
+/// Replace duplicated opcodes (ADD, SUB, XOR, AND, OR, CMP) with a random
+/// alias from the build's handler duplication table
+pub struct AliasSubstitutionPass {
+    seed: u64,
+}
+
+impl Default for AliasSubstitutionPass {
+    fn default() -> Self {
+        Self::new(next_seed())
+    }
+}
+
+impl AliasSubstitutionPass {
+    /// Create with a seed
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl BytecodePass for AliasSubstitutionPass {
+    fn transform(&self, mut code: Vec<u8>) -> Vec<u8> {
+        let instructions = match decode(&code) {
+            Some(instructions) => instructions,
+            None => return code,
+        };
+        let mut rng = fastrand::Rng::with_seed(self.seed);
+
+        for insn in instructions {
+            if let Some(&(base, aliases)) = opcode_aliases::ALL.iter().find(|(b, _)| *b == insn.base) {
+                let pick = rng.usize(..=aliases.len());
+                code[insn.pos] = aliases.get(pick).copied().unwrap_or(OPCODE_ENCODE[base as usize]);
+            }
+        }
+        code
+    }
+}
+
+/// Insert always-taken branches over junk bytes
+///
+/// Predicates are only placed directly before a CMP, where the flags they
+/// clobber are about to be overwritten anyway.
+pub struct OpaquePredicatePass {
+    seed: u64,
+}
+
+impl Default for OpaquePredicatePass {
+    fn default() -> Self {
+        Self::new(next_seed())
+    }
+}
+
+impl OpaquePredicatePass {
+    /// Create with a seed
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl BytecodePass for OpaquePredicatePass {
+    fn transform(&self, code: Vec<u8>) -> Vec<u8> {
+        let mut rng = fastrand::Rng::with_seed(self.seed);
+        rewrite(code, |insn, out| {
+            if insn.base != control::CMP {
+                return;
+            }
+            // OPAQUE_TRUE  -> 1 != 0 -> JNZ taken
+            // OPAQUE_FALSE -> 0 == 0 -> JZ taken
+            let (predicate, branch) = if rng.bool() {
+                (enc::special::OPAQUE_TRUE, enc::control::JNZ)
+            } else {
+                (enc::special::OPAQUE_FALSE, enc::control::JZ)
+            };
+            let junk = rng.u8(1..=8);
+            out.extend_from_slice(&[
+                predicate,
+                enc::stack::PUSH_IMM8, 0,
+                enc::control::CMP,
+                enc::stack::DROP,
+                enc::stack::DROP,
+                branch, junk, 0,
+            ]);
+            out.extend((0..junk).map(|_| junk_byte(&mut rng)));
+        })
+    }
+}
+
+/// Rename registers R0-R7 by a seeded permutation
+///
+/// Every register operand (PUSH_REG, POP_REG, MOV_IMM, MOV_REG, LOAD_MEM,
+/// STORE_MEM) is rewritten consistently, so two copies of the same
+/// function differ in which registers they touch, not just in opcode
+/// bytes. Registers above R7 are left alone. Not for code whose caller
+/// presets or reads registers through `VmState`.
+pub struct RegisterPermutationPass {
+    map: [u8; 8],
+}
+
+impl Default for RegisterPermutationPass {
+    fn default() -> Self {
+        Self::new(next_seed())
+    }
+}
+
+impl RegisterPermutationPass {
+    /// Create with a seed
+    pub fn new(seed: u64) -> Self {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut map = [0, 1, 2, 3, 4, 5, 6, 7];
+        rng.shuffle(&mut map);
+        Self { map }
+    }
+
+    /// Permutation for one protected function, from its name and the
+    /// build seed
+    pub fn for_function(name: &str) -> Self {
+        let build_seed = crate::build_config::get_build_seed();
+        let mut seed_bytes = [0u8; 8];
+        seed_bytes.copy_from_slice(&build_seed[..8]);
+        Self::new(crate::fnv1a_hash(name.as_bytes()) ^ u64::from_le_bytes(seed_bytes))
+    }
+
+    /// Physical register for each logical R0-R7
+    pub fn map(&self) -> [u8; 8] {
+        self.map
+    }
+}
+
+impl BytecodePass for RegisterPermutationPass {
+    fn transform(&self, mut code: Vec<u8>) -> Vec<u8> {
+        let instructions = match decode(&code) {
+            Some(instructions) => instructions,
+            None => return code,
+        };
+        for insn in instructions {
+            let operands = match insn.base {
+                stack::PUSH_REG | stack::POP_REG | register::MOV_IMM => 1,
+                register::MOV_REG | register::LOAD_MEM | register::STORE_MEM => 2,
+                _ => continue,
+            };
+            for reg in &mut code[insn.pos + 1..insn.pos + 1 + operands] {
+                if let Some(&physical) = self.map.get(*reg as usize) {
+                    *reg = physical;
+                }
+            }
+        }
+        code
+    }
+}
+
+/// Random encoded single-byte opcode, so a linear sweep over the junk (by
+/// a later pass or `BytecodeStats`) stays aligned with the real code
+fn junk_byte(rng: &mut fastrand::Rng) -> u8 {
+    loop {
+        let byte = rng.u8(..);
+        if instruction_length(OPCODE_DECODE[byte as usize]) == 1 {
+            return byte;
+        }
+    }
+}
+
+/// Warning for paranoid output that contains no anti-analysis opcode
+///
+/// Opaque predicates are only placed before a CMP, so a function that
+/// compiles to a few straight-line instructions leaves the paranoid
+/// pipeline with padding and aliases but no OPAQUE_*, TIMING_CHECK or
+/// HASH_CHECK. Returns `None` when at least one of those is present.
+pub fn paranoid_warning(code: &[u8]) -> Option<String> {
+    let stats = BytecodeStats::of(code);
+    if stats.anti_analysis_count() > 0 {
+        return None;
+    }
+    Some(format!(
+        "paranoid protection produced no opaque predicate, timing check or hash check \
+         ({} instructions); the function is too small for paranoid to add anything",
+        stats.instruction_count()
+    ))
+}
+
+/// Decoded instruction
+struct Instruction {
+    /// Offset of the opcode byte
+    pos: usize,
+    /// Total length including operands (and NOP_N payload)
+    len: usize,
+    /// Base (decoded) opcode
+    base: u8,
+}
+
+/// Split code into instructions, `None` if it can't be safely rewritten
+fn decode(code: &[u8]) -> Option<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut pos = 0;
+
+    while pos < code.len() {
+        let base = OPCODE_DECODE[code[pos] as usize];
+        let len = match base {
+            special::HASH_CHECK => return None,
+            special::NOP_N => 2 + *code.get(pos + 1)? as usize,
+            _ => match instruction_length(base) {
+                0 => return None,
+                len => len,
+            },
+        };
+        if pos + len > code.len() {
+            return None;
+        }
+        instructions.push(Instruction { pos, len, base });
+        pos += len;
+    }
+    Some(instructions)
+}
+
+/// Rebuild code, letting `insert` emit bytes before each instruction, and
+/// fix up relative branches. Falls back to the original on any failure.
+fn rewrite<F>(code: Vec<u8>, insert: F) -> Vec<u8>
+where
+    F: FnMut(&Instruction, &mut Vec<u8>),
+{
+    relocate(&code, insert).unwrap_or(code)
+}
+
+fn relocate<F>(code: &[u8], mut insert: F) -> Option<Vec<u8>>
+where
+    F: FnMut(&Instruction, &mut Vec<u8>),
+{
+    let instructions = decode(code)?;
+    let mut out = Vec::with_capacity(code.len() * 2);
+    // old offset -> new offset of whatever now precedes that instruction
+    let mut targets = vec![None; code.len() + 1];
+    // new offset of each instruction's opcode
+    let mut moved = Vec::with_capacity(instructions.len());
+
+    for insn in &instructions {
+        targets[insn.pos] = Some(out.len());
+        insert(insn, &mut out);
+        moved.push(out.len());
+        out.extend_from_slice(&code[insn.pos..insn.pos + insn.len]);
+    }
+    targets[code.len()] = Some(out.len());
+
+    for (insn, &new_pos) in instructions.iter().zip(&moved) {
+        if !is_relative_branch(insn.base) {
+            continue;
+        }
+        let offset = i16::from_le_bytes([code[insn.pos + 1], code[insn.pos + 2]]);
+        let old_target = (insn.pos + 3).checked_add_signed(offset as isize)?;
+        let new_target = (*targets.get(old_target)?)?;
+        let new_offset = i16::try_from(new_target as isize - (new_pos + 3) as isize).ok()?;
+        out[new_pos + 1..new_pos + 3].copy_from_slice(&new_offset.to_le_bytes());
+    }
+    Some(out)
+}
@@ -0,0 +1,120 @@
+//! Sandboxed Execution
+//!
+//! [`Sandbox`] runs bytecode from a source you don't control with every
+//! guard on, and reports every failure as a `VmError`:
+//!
+//! - Validation: the code is checked by linear sweep before it runs
+//!   (undecodable bytes, truncated instructions, stray branches)
+//! - Limits: heap bytes, value stack depth and executed instructions are
+//!   capped, each at or below the VM-wide maximum
+//! - Heap: strict mode, so accesses outside live allocations fail instead
+//!   of reading stale data
+//! - Jumps: landing inside an instruction that already ran fails with
+//!   `InvalidJumpTarget` (see `VmState::set_jump_guard`)
+//! - Natives and the VM itself: panics become `NativeCallFailed` and
+//!   `StateCorrupt` (std only; with `panic = "abort"` there is nothing to
+//!   catch)
+//!
+//! ```rust
+//! use aegis_vm::{Sandbox, BytecodeBuilder};
+//!
+//! let code = BytecodeBuilder::new().push_imm8(40).push_imm8(2).add().halt().build().unwrap();
+//! let sandbox = Sandbox::new().heap_limit(64 * 1024).stack_limit(128).instruction_budget(10_000);
+//! assert_eq!(sandbox.run(&code, &[]), Ok(42));
+//! ```
+
+use crate::bytecode;
+use crate::engine::run_with_natives;
+use crate::error::VmResult;
+use crate::native::NativeRegistry;
+use crate::state::{VmState, DEFAULT_HEAP_SIZE, MAX_HEAP_SIZE, MAX_INSTRUCTIONS, MAX_STACK_SIZE};
+
+/// Execution limits and checks for untrusted bytecode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sandbox {
+    heap_limit: usize,
+    stack_limit: usize,
+    instruction_budget: u64,
+    validate: bool,
+    absolute_jumps: bool,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sandbox {
+    /// Default limits, validation on
+    pub fn new() -> Self {
+        Self {
+            heap_limit: DEFAULT_HEAP_SIZE,
+            stack_limit: MAX_STACK_SIZE,
+            instruction_budget: MAX_INSTRUCTIONS,
+            validate: true,
+            absolute_jumps: false,
+        }
+    }
+
+    /// Heap size cap in bytes (at most `MAX_HEAP_SIZE`)
+    pub fn heap_limit(mut self, bytes: usize) -> Self {
+        self.heap_limit = bytes.min(MAX_HEAP_SIZE);
+        self
+    }
+
+    /// Value stack depth cap (at most `MAX_STACK_SIZE`)
+    pub fn stack_limit(mut self, entries: usize) -> Self {
+        self.stack_limit = entries.min(MAX_STACK_SIZE);
+        self
+    }
+
+    /// Executed instruction cap (at most `MAX_INSTRUCTIONS`)
+    pub fn instruction_budget(mut self, instructions: u64) -> Self {
+        self.instruction_budget = instructions.min(MAX_INSTRUCTIONS);
+        self
+    }
+
+    /// Validate code before running it (on by default)
+    pub fn validate(mut self, enabled: bool) -> Self {
+        self.validate = enabled;
+        self
+    }
+
+    /// Run code whose branch operands are absolute code offsets
+    /// (`BytecodeFlags::AbsoluteJumps`); off by default
+    pub fn absolute_jumps(mut self, enabled: bool) -> Self {
+        self.absolute_jumps = enabled;
+        self
+    }
+
+    /// Run `code` without natives
+    pub fn run(&self, code: &[u8], input: &[u8]) -> VmResult<u64> {
+        self.run_with_natives(code, input, &NativeRegistry::new())
+    }
+
+    /// Run `code` with natives from `registry`
+    pub fn run_with_natives(&self, code: &[u8], input: &[u8], registry: &NativeRegistry) -> VmResult<u64> {
+        if self.validate && self.absolute_jumps {
+            bytecode::validate_absolute(code)?;
+        } else if self.validate {
+            bytecode::validate(code)?;
+        }
+        let mut state = VmState::with_heap_limit(code, input, self.heap_limit);
+        state.max_stack = self.stack_limit;
+        state.max_instructions = self.instruction_budget;
+        state.set_strict_heap(true);
+        state.set_jump_guard(true);
+        state.set_absolute_jumps(self.absolute_jumps);
+
+        #[cfg(feature = "std")]
+        {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run_with_natives(&mut state, registry)))
+                .unwrap_or(Err(crate::error::VmError::StateCorrupt))?;
+        }
+        #[cfg(not(feature = "std"))]
+        run_with_natives(&mut state, registry)?;
+
+        Ok(state.result)
+    }
+}
@@ -20,22 +20,29 @@
 
 use crate::error::{VmError, VmResult};
 use crate::native::NativeRegistry;
-use crate::state::{VmState, FreeBlock, MAX_INSTRUCTIONS, DEFAULT_REGISTER_CAPACITY};
+use crate::state::{VmState, AllocStats, FreeBlock, MAX_INSTRUCTIONS, DEFAULT_REGISTER_CAPACITY};
 use crate::build_config::OPCODE_DECODE;
+use crate::bytecode::{instruction_length, InstructionMap};
 use crate::handlers::dispatch::dispatch_indirect;
-use crate::opcodes::{arithmetic, control, convert, exec, heap, native, register, special, stack, string, vector};
 
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
 /// SMC Configuration
+///
+/// Non-exhaustive so options can be added without breaking callers: build
+/// one with `new`, `from_build_seed` or `Default`, then the `with_*`
+/// methods.
 #[derive(Clone)]
+#[non_exhaustive]
 pub struct SmcConfig {
     /// Base key for encryption (derived from build seed)
     pub key: [u8; 32],
     /// Number of instructions to keep decrypted (sliding window)
     /// 1 = most secure, higher = better performance for loops
     pub window_size: usize,
+    /// Zero freed heap blocks before they are recycled
+    pub zero_on_free: bool,
 }
 
 impl Default for SmcConfig {
@@ -43,11 +50,17 @@ impl Default for SmcConfig {
         Self {
             key: [0; 32],
             window_size: 1,
+            zero_on_free: false,
         }
     }
 }
 
 impl SmcConfig {
+    /// Create config with an explicit key and default options
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key, ..Self::default() }
+    }
+
     /// Create config with build-time derived key
     pub fn from_build_seed(seed: u64) -> Self {
         let mut key = [0u8; 32];
@@ -56,10 +69,7 @@ impl SmcConfig {
             state = state.wrapping_mul(0x5DEECE66D).wrapping_add(0xB);
             *byte = (state >> 24) as u8;
         }
-        Self {
-            key,
-            window_size: 1,
-        }
+        Self::new(key)
     }
 
     /// Set window size
@@ -67,6 +77,12 @@ impl SmcConfig {
         self.window_size = size.max(1);
         self
     }
+
+    /// Zero freed heap blocks (for secret-handling routines)
+    pub fn with_zero_on_free(mut self, enabled: bool) -> Self {
+        self.zero_on_free = enabled;
+        self
+    }
 }
 
 /// Generate position-dependent key byte
@@ -93,74 +109,43 @@ fn encrypt_byte(code: &mut [u8], pos: usize, config: &SmcConfig) {
 }
 
 /// Decrypt a range of bytes
-fn decrypt_range(code: &mut [u8], start: usize, len: usize, config: &SmcConfig) {
+/// Returns the number of bytes actually decrypted
+fn decrypt_range(code: &mut [u8], start: usize, len: usize, config: &SmcConfig) -> usize {
+    let mut count = 0;
     for i in 0..len {
         if start + i < code.len() {
             decrypt_byte(code, start + i, config);
+            count += 1;
         }
     }
+    count
 }
 
 /// Encrypt a range of bytes
-fn encrypt_range(code: &mut [u8], start: usize, len: usize, config: &SmcConfig) {
+/// Returns the number of bytes actually encrypted
+fn encrypt_range(code: &mut [u8], start: usize, len: usize, config: &SmcConfig) -> usize {
+    let mut count = 0;
     for i in 0..len {
         if start + i < code.len() {
             encrypt_byte(code, start + i, config);
+            count += 1;
         }
     }
+    count
 }
 
-/// Get instruction length based on opcode
-/// Returns the total bytes including opcode
-fn instruction_length(base_opcode: u8) -> usize {
-    match base_opcode {
-        // 1-byte instructions (opcode only)
-        stack::DUP | stack::SWAP | stack::DROP |
-        arithmetic::ADD | arithmetic::SUB | arithmetic::MUL |
-        arithmetic::XOR | arithmetic::AND | arithmetic::OR |
-        arithmetic::SHL | arithmetic::SHR | arithmetic::NOT |
-        arithmetic::ROL | arithmetic::ROR | arithmetic::INC | arithmetic::DEC |
-        arithmetic::DIV | arithmetic::MOD | arithmetic::IDIV | arithmetic::IMOD |
-        control::CMP | control::RET |
-        convert::SEXT8 | convert::SEXT16 | convert::SEXT32 |
-        convert::TRUNC8 | convert::TRUNC16 | convert::TRUNC32 |
-        special::NOP | exec::HALT |
-        vector::VEC_NEW | vector::VEC_LEN | vector::VEC_CAP |
-        vector::VEC_PUSH | vector::VEC_POP | vector::VEC_GET | vector::VEC_SET |
-        vector::VEC_REPEAT | vector::VEC_CLEAR | vector::VEC_RESERVE |
-        string::STR_NEW | string::STR_LEN | string::STR_PUSH |
-        string::STR_GET | string::STR_SET | string::STR_CMP |
-        string::STR_EQ | string::STR_HASH | string::STR_CONCAT |
-        heap::HEAP_ALLOC | heap::HEAP_FREE |
-        heap::HEAP_LOAD8 | heap::HEAP_LOAD16 | heap::HEAP_LOAD32 | heap::HEAP_LOAD64 |
-        heap::HEAP_STORE8 | heap::HEAP_STORE16 | heap::HEAP_STORE32 | heap::HEAP_STORE64 |
-        heap::HEAP_SIZE |
-        special::OPAQUE_TRUE | special::OPAQUE_FALSE => 1,
-
-        // 2-byte instructions (opcode + u8)
-        stack::PUSH_IMM8 | stack::PUSH_REG | stack::POP_REG |
-        special::NOP_N => 2,
-
-        // 3-byte instructions (opcode + u16 or 2xu8)
-        stack::PUSH_IMM16 |
-        control::JMP | control::JZ | control::JNZ |
-        control::JGT | control::JLT | control::JGE | control::JLE |
-        control::CALL |
-        register::MOV_REG |
-        native::NATIVE_READ | native::NATIVE_WRITE => 3,
-
-        // 5-byte instructions (opcode + u32)
-        stack::PUSH_IMM32 => 5,
-
-        // 9-byte instructions (opcode + u64)
-        stack::PUSH_IMM => 9,
-
-        // 10-byte instructions (opcode + u8 + u64)
-        register::MOV_IMM => 10,
-
-        // Variable length - use max safe value
-        _ => 1,
-    }
+/// SMC profiling counters
+///
+/// Collected only by [`execute_smc_with_stats`]; the regular entry points
+/// skip the bookkeeping entirely. Useful for tuning `window_size`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SmcStats {
+    /// Bytes decrypted (opcode + operands)
+    pub decrypt_ops: u64,
+    /// Bytes re-encrypted
+    pub encrypt_ops: u64,
+    /// Instructions executed
+    pub instructions: u64,
 }
 
 /// Persistent execution state for SMC (without code reference)
@@ -171,6 +156,8 @@ struct SmcExecState {
     heap_ptr: usize,
     heap_limit: usize,
     free_list: Vec<FreeBlock>,
+    zero_on_free: bool,
+    heap_stats: AllocStats,
     stack: Vec<u64>,
     call_stack: Vec<usize>,
     ip: usize,
@@ -192,6 +179,8 @@ impl SmcExecState {
             heap_ptr: 0,
             heap_limit: 1024 * 1024,
             free_list: Vec::with_capacity(16),
+            zero_on_free: false,
+            heap_stats: AllocStats::default(),
             stack: Vec::with_capacity(64),
             call_stack: Vec::with_capacity(16),
             ip: 0,
@@ -213,6 +202,8 @@ impl SmcExecState {
         self.heap_ptr = state.heap_ptr;
         self.heap_limit = state.heap_limit;
         self.free_list.clone_from(&state.free_list);
+        self.zero_on_free = state.zero_on_free;
+        self.heap_stats = state.heap_stats;
         self.stack.clone_from(&state.stack);
         self.call_stack.clone_from(&state.call_stack);
         self.ip = state.ip;
@@ -233,6 +224,8 @@ impl SmcExecState {
         state.heap_ptr = self.heap_ptr;
         state.heap_limit = self.heap_limit;
         state.free_list.clone_from(&self.free_list);
+        state.zero_on_free = self.zero_on_free;
+        state.heap_stats = self.heap_stats;
         state.stack.clone_from(&self.stack);
         state.call_stack.clone_from(&self.call_stack);
         state.ip = self.ip;
@@ -254,18 +247,46 @@ pub fn execute_smc(mut code: Vec<u8>, input: &[u8], config: &SmcConfig) -> VmRes
     execute_smc_with_natives(&mut code, input, config, &registry)
 }
 
+/// SMC-enabled execution with profiling counters
+/// Returns the result together with the crypto operation counts
+pub fn execute_smc_with_stats(
+    mut code: Vec<u8>,
+    input: &[u8],
+    config: &SmcConfig,
+) -> VmResult<(u64, SmcStats)> {
+    let registry = NativeRegistry::new();
+    let mut stats = SmcStats::default();
+    let result = run_smc(&mut code, input, config, &registry, Some(&mut stats))?;
+    Ok((result, stats))
+}
+
 /// SMC-enabled execution with native functions
 pub fn execute_smc_with_natives(
-    code: &mut Vec<u8>,
+    code: &mut [u8],
+    input: &[u8],
+    config: &SmcConfig,
+    registry: &NativeRegistry,
+) -> VmResult<u64> {
+    run_smc(code, input, config, registry, None)
+}
+
+/// SMC execution loop
+/// `stats` is only touched when profiling was requested
+fn run_smc(
+    code: &mut [u8],
     input: &[u8],
     config: &SmcConfig,
     registry: &NativeRegistry,
+    mut stats: Option<&mut SmcStats>,
 ) -> VmResult<u64> {
     // Track decrypted regions for sliding window
     let mut decrypted: Vec<(usize, usize)> = Vec::with_capacity(config.window_size + 1);
+    // Instruction boundaries seen so far
+    let mut visited = InstructionMap::new(code.len());
 
     // Persistent state (separate from VmState)
     let mut exec_state = SmcExecState::new();
+    exec_state.zero_on_free = config.zero_on_free;
 
     while !exec_state.halted && exec_state.ip < code.len() {
         let ip = exec_state.ip;
@@ -276,26 +297,49 @@ pub fn execute_smc_with_natives(
             return Err(VmError::MaxInstructionsExceeded);
         }
 
-        // Decrypt current instruction opcode
-        decrypt_byte(code, ip, config);
-        let opcode = code[ip];
-
-        // Decode to get instruction length
-        let base_opcode = OPCODE_DECODE[opcode as usize];
-        let inst_len = instruction_length(base_opcode);
-
-        // Decrypt operands if any
-        if inst_len > 1 {
-            decrypt_range(code, ip + 1, inst_len - 1, config);
-        }
-
-        // Track this decrypted region
-        decrypted.push((ip, inst_len));
+        // Instruction still inside the window (loop back-edge): it is already
+        // plaintext, so just refresh its position instead of decrypting twice
+        let opcode = if let Some(pos) = decrypted.iter().position(|&(addr, _)| addr == ip) {
+            let region = decrypted.remove(pos);
+            decrypted.push(region);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.instructions += 1;
+            }
+            code[ip]
+        } else {
+            // Landing inside an instruction that already ran would decrypt
+            // its operand bytes as an opcode
+            visited.check_start(ip)?;
+
+            // Decrypt current instruction opcode
+            decrypt_byte(code, ip, config);
+            let opcode = code[ip];
+
+            // Decode to get instruction length
+            let base_opcode = OPCODE_DECODE[opcode as usize];
+            // Unknown bytes decrypt as 1 byte; dispatch rejects them
+            let inst_len = instruction_length(base_opcode).max(1);
+
+            // Decrypt operands if any
+            let mut decrypted_bytes = 1;
+            if inst_len > 1 {
+                decrypted_bytes += decrypt_range(code, ip + 1, inst_len - 1, config);
+            }
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.decrypt_ops += decrypted_bytes as u64;
+                stats.instructions += 1;
+            }
+
+            // Track this decrypted region
+            visited.record(ip, inst_len)?;
+            decrypted.push((ip, inst_len));
+            opcode
+        };
 
         // Execute instruction in a temporary scope
         {
             // Create temporary VmState with current code view
-            let mut state = VmState::new(code.as_slice(), input);
+            let mut state = VmState::new(code, input);
             exec_state.apply_to(&mut state);
 
             // IMPORTANT: Advance IP past opcode before calling handler
@@ -313,13 +357,19 @@ pub fn execute_smc_with_natives(
         // Re-encrypt old instructions outside window
         while decrypted.len() > config.window_size {
             let (old_ip, old_len) = decrypted.remove(0);
-            encrypt_range(code, old_ip, old_len, config);
+            let encrypted_bytes = encrypt_range(code, old_ip, old_len, config);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.encrypt_ops += encrypted_bytes as u64;
+            }
         }
     }
 
     // Re-encrypt any remaining decrypted instructions
     for (old_ip, old_len) in decrypted {
-        encrypt_range(code, old_ip, old_len, config);
+        let encrypted_bytes = encrypt_range(code, old_ip, old_len, config);
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.encrypt_ops += encrypted_bytes as u64;
+        }
     }
 
     Ok(exec_state.result)
@@ -348,6 +398,7 @@ fn dispatch_smc(state: &mut VmState, opcode: u8, registry: &NativeRegistry) -> V
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::opcodes::{arithmetic, control, stack};
 
     #[test]
     fn test_encrypt_decrypt_symmetric() {
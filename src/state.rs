@@ -11,6 +11,7 @@ use alloc::{vec, vec::Vec};
 
 use crate::error::{VmError, VmResult};
 use crate::opcodes::flags;
+use crate::whitebox::WhiteboxCryptoContext;
 
 // =============================================================================
 // Free List Allocator Support
@@ -37,6 +38,62 @@ pub struct FreeBlock {
     pub size: usize,
 }
 
+/// Heap allocation statistics (for leak detection)
+///
+/// Byte counts are block sizes including the allocation header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Bytes held by live blocks
+    pub live_bytes: usize,
+    /// Number of live blocks
+    pub live_blocks: usize,
+    /// Successful HEAP_ALLOC calls
+    pub total_allocs: u64,
+    /// Successful HEAP_FREE calls
+    pub total_frees: u64,
+    /// Highest `live_bytes` seen
+    pub peak_bytes: usize,
+    /// Times the heap buffer had to reallocate to grow
+    pub heap_growths: u64,
+}
+
+impl AllocStats {
+    #[inline]
+    fn record_alloc(&mut self, size: usize) {
+        self.live_bytes += size;
+        self.live_blocks += 1;
+        self.total_allocs += 1;
+        self.peak_bytes = self.peak_bytes.max(self.live_bytes);
+    }
+
+    #[inline]
+    fn record_free(&mut self, size: usize) {
+        self.live_bytes = self.live_bytes.saturating_sub(size);
+        self.live_blocks = self.live_blocks.saturating_sub(1);
+        self.total_frees += 1;
+    }
+}
+
+/// Owned snapshot of a finished run (see `execute_capturing`)
+///
+/// Unlike `VmState`, it doesn't borrow the bytecode or input, so it can
+/// outlive them. `flags` uses this build's shuffled bits (`opcodes::flags`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VmOutcome {
+    /// Value passed to HALT
+    pub result: u64,
+    /// Output buffer
+    pub output: Vec<u8>,
+    /// Heap contents (up to the highest address ever reserved)
+    pub heap: Vec<u8>,
+    /// Register file
+    pub regs: Vec<u64>,
+    /// Final CPU flags
+    pub flags: u8,
+    /// Instructions executed
+    pub instruction_count: u64,
+}
+
 // =============================================================================
 // Constants
 // =============================================================================
@@ -47,6 +104,9 @@ pub const MAX_STACK_SIZE: usize = 1024;
 /// Maximum instructions per execution (prevent infinite loops)
 pub const MAX_INSTRUCTIONS: u64 = 1_000_000;
 
+/// Maximum nested CALL depth (prevents unbounded recursion)
+pub const MAX_CALL_DEPTH: usize = 256;
+
 /// Maximum number of registers (R0-R255)
 /// Limited by u8 index in opcodes
 pub const MAX_REGISTERS: usize = 256;
@@ -61,6 +121,9 @@ pub const DEFAULT_HEAP_SIZE: usize = 1024 * 1024;
 /// Maximum heap size (10 MB) - DoS protection
 pub const MAX_HEAP_SIZE: usize = 10 * 1024 * 1024;
 
+/// Maximum output buffer size (10 MB) - DoS protection
+pub const MAX_OUTPUT_SIZE: usize = 10 * 1024 * 1024;
+
 /// Default heap capacity (start with 4 KB, grow as needed)
 pub const DEFAULT_HEAP_CAPACITY: usize = 4 * 1024;
 
@@ -91,8 +154,10 @@ pub const OUTPUT_BASE_ADDR: u64 = 0xC000_0000;
 pub struct VmState<'a> {
     // ========== Registers ==========
     /// General-purpose registers (R0-R255)
-    /// Dynamically sized, grows on demand up to MAX_REGISTERS
+    /// Dynamically sized, grows on demand up to max_registers
     pub regs: Vec<u64>,
+    /// Register count cap (<= MAX_REGISTERS)
+    pub max_registers: usize,
 
     // ========== Heap (Free-List Allocator) ==========
     /// Managed heap memory
@@ -103,12 +168,22 @@ pub struct VmState<'a> {
     pub heap_limit: usize,
     /// Free list for recycled memory blocks
     pub free_list: Vec<FreeBlock>,
+    /// Zero user data on HEAP_FREE (for secret-handling routines)
+    pub zero_on_free: bool,
+    /// Allocation counters (see `alloc_stats`)
+    pub heap_stats: AllocStats,
+    /// Fail accesses outside live allocations with UseAfterFree
+    pub strict_heap: bool,
+    /// Live user regions as [start, end), sorted by start (strict_heap only)
+    pub live_ranges: Vec<(usize, usize)>,
 
     // ========== Stacks ==========
     /// Value stack
     pub stack: Vec<u64>,
     /// Call stack (return addresses)
     pub call_stack: Vec<usize>,
+    /// Value stack depth cap (<= MAX_STACK_SIZE)
+    pub max_stack: usize,
 
     // ========== Execution Control ==========
     /// Instruction pointer
@@ -117,18 +192,31 @@ pub struct VmState<'a> {
     pub flags: u8,
     /// Instruction counter (for max instruction limit)
     pub instruction_count: u64,
+    /// Instruction budget per run (<= MAX_INSTRUCTIONS)
+    pub max_instructions: u64,
     /// Halted flag
     pub halted: bool,
     /// Result value (set by HALT)
     pub result: u64,
     /// Last error (if any)
     pub last_error: VmError,
+    /// NATIVE_CALL index that failed with `VmError::UnknownNative`
+    pub unknown_native: Option<u8>,
+    /// Branch operands are u16 offsets from the start of the code instead
+    /// of i16 offsets from the next instruction (`BytecodeFlags::AbsoluteJumps`)
+    pub absolute_jumps: bool,
+    /// Reject jumps that land inside an instruction that already ran
+    /// (see `InstructionMap`); off by default
+    pub jump_guard: bool,
 
     // ========== I/O Buffers ==========
     /// Bytecode being executed
     pub code: &'a [u8],
     /// Input data buffer (read-only)
     pub input: &'a [u8],
+    /// Keystream for an encrypted input buffer
+    /// When set, `input` holds ciphertext and bytes are decrypted per read
+    pub input_cipher: Option<&'a WhiteboxCryptoContext>,
     /// Output data buffer
     pub output: Vec<u8>,
 
@@ -137,6 +225,9 @@ pub struct VmState<'a> {
     pub last_timing_ns: u64,
     /// Execution start time (for timing checks)
     pub start_time_ns: u64,
+    /// Anti-analysis checks enabled (TIMING_CHECK, HASH_CHECK)
+    /// Disable for deterministic correctness tests
+    pub anti_analysis: bool,
 
     // ========== Native Function Table ==========
     /// Optional native function table for NATIVE_CALL opcode
@@ -158,28 +249,40 @@ impl<'a> VmState<'a> {
         Self {
             // Pre-allocate registers for common case
             regs: vec![0u64; DEFAULT_REGISTER_CAPACITY],
+            max_registers: MAX_REGISTERS,
             // Heap with default capacity (grows on demand)
             heap: Vec::with_capacity(DEFAULT_HEAP_CAPACITY),
             heap_ptr: 0,
             heap_limit: DEFAULT_HEAP_SIZE,
             free_list: Vec::with_capacity(16), // Pre-allocate for common case
+            zero_on_free: false,
+            heap_stats: AllocStats::default(),
+            strict_heap: false,
+            live_ranges: Vec::new(),
             // Stacks
             stack: Vec::with_capacity(64),
             call_stack: Vec::with_capacity(16),
+            max_stack: MAX_STACK_SIZE,
             // Execution
             ip: 0,
             flags: 0,
             instruction_count: 0,
+            max_instructions: MAX_INSTRUCTIONS,
             halted: false,
             result: 0,
             last_error: VmError::Ok,
+            unknown_native: None,
+            absolute_jumps: false,
+            jump_guard: false,
             // I/O
             code,
             input,
+            input_cipher: None,
             output: Vec::new(),
             // Timing
             last_timing_ns: 0,
             start_time_ns: 0,
+            anti_analysis: true,
             // Native function table
             native_table: None,
             // Async VM yield mask
@@ -195,35 +298,79 @@ impl<'a> VmState<'a> {
         state
     }
 
+    /// Create VM state with a register count cap
+    ///
+    /// Accessing a register at or above `max_registers` fails with
+    /// `VmError::InvalidRegister` instead of growing the register file.
+    pub fn with_max_registers(code: &'a [u8], input: &'a [u8], max_registers: usize) -> Self {
+        let mut state = Self::new(code, input);
+        state.max_registers = max_registers.min(MAX_REGISTERS);
+        state.regs.truncate(state.max_registers);
+        state
+    }
+
+    /// Create VM state with `capacity` heap bytes reserved up front
+    ///
+    /// Allocations within the reserved bytes never reallocate the heap;
+    /// `alloc_stats().heap_growths` counts the times it had to. Capped at
+    /// the heap limit.
+    pub fn with_prealloc_heap(code: &'a [u8], input: &'a [u8], capacity: usize) -> Self {
+        let mut state = Self::new(code, input);
+        state.heap = Vec::with_capacity(capacity.min(state.heap_limit));
+        state
+    }
+
+    /// Create VM state with a preallocated output buffer
+    ///
+    /// For routines with a known output size: writes within `capacity`
+    /// bytes never reallocate. Capped at MAX_OUTPUT_SIZE.
+    pub fn with_output_capacity(code: &'a [u8], input: &'a [u8], capacity: usize) -> Self {
+        let mut state = Self::new(code, input);
+        state.output = Vec::with_capacity(capacity.min(MAX_OUTPUT_SIZE));
+        state
+    }
+
     /// Create VM state with new code reference but preserving execution state
     /// Used by SMC engine to update code view after decryption
     pub fn with_code_and_state(code: &'a [u8], input: &'a [u8], old: &VmState<'a>) -> Self {
         Self {
             // Copy registers
             regs: old.regs.clone(),
+            max_registers: old.max_registers,
             // Copy heap state
             heap: old.heap.clone(),
             heap_ptr: old.heap_ptr,
             heap_limit: old.heap_limit,
             free_list: old.free_list.clone(),
+            zero_on_free: old.zero_on_free,
+            heap_stats: old.heap_stats,
+            strict_heap: old.strict_heap,
+            live_ranges: old.live_ranges.clone(),
             // Copy stacks
             stack: old.stack.clone(),
             call_stack: old.call_stack.clone(),
+            max_stack: old.max_stack,
             // Copy execution state
             ip: old.ip,
             flags: old.flags,
             instruction_count: old.instruction_count,
+            max_instructions: old.max_instructions,
             halted: old.halted,
             result: old.result,
             last_error: old.last_error,
+            unknown_native: old.unknown_native,
+            absolute_jumps: old.absolute_jumps,
+            jump_guard: old.jump_guard,
             // New code reference
             code,
             input,
+            input_cipher: old.input_cipher,
             // Copy output
             output: old.output.clone(),
             // Copy timing
             last_timing_ns: old.last_timing_ns,
             start_time_ns: old.start_time_ns,
+            anti_analysis: old.anti_analysis,
             // Copy native table
             native_table: old.native_table,
             // Copy yield mask
@@ -251,6 +398,15 @@ impl<'a> VmState<'a> {
         }
     }
 
+    /// Disable anti-analysis checks
+    ///
+    /// TIMING_CHECK and HASH_CHECK become no-ops, so results don't depend
+    /// on scheduling, debuggers or bytecode patching. Use for tests only.
+    #[inline]
+    pub fn disable_anti_analysis(&mut self) {
+        self.anti_analysis = false;
+    }
+
     /// Get current time in nanoseconds
     #[inline]
     pub fn current_time_ns(&self) -> u64 {
@@ -272,11 +428,13 @@ impl<'a> VmState<'a> {
     pub fn reset(&mut self) {
         // Reset registers (keep capacity)
         self.regs.clear();
-        self.regs.resize(DEFAULT_REGISTER_CAPACITY, 0);
+        self.regs.resize(DEFAULT_REGISTER_CAPACITY.min(self.max_registers), 0);
         // Reset heap
         self.heap.clear();
         self.heap_ptr = 0;
         self.free_list.clear();
+        self.heap_stats = AllocStats::default();
+        self.live_ranges.clear();
         // Reset stacks
         self.stack.clear();
         self.call_stack.clear();
@@ -287,6 +445,7 @@ impl<'a> VmState<'a> {
         self.halted = false;
         self.result = 0;
         self.last_error = VmError::Ok;
+        self.unknown_native = None;
         // Reset output
         self.output.clear();
         // Reset timing
@@ -301,6 +460,47 @@ impl<'a> VmState<'a> {
         }
     }
 
+    /// Rebind state to new bytecode and input for another run
+    ///
+    /// Resets execution state like `reset`, but keeps the configuration a
+    /// caller set up front (heap, stack, register and instruction limits, zero_on_free,
+    /// strict_heap, absolute_jumps, jump_guard, anti_analysis, native table, input cipher, yield mask) and reuses
+    /// the register, heap and stack allocations.
+    pub fn rebind(&mut self, code: &'a [u8], input: &'a [u8]) {
+        let native_table = self.native_table;
+        #[cfg(feature = "async_vm")]
+        let yield_mask = self.yield_mask;
+
+        self.reset();
+        self.native_table = native_table;
+        #[cfg(feature = "async_vm")]
+        {
+            self.yield_mask = yield_mask;
+        }
+        self.code = code;
+        self.input = input;
+    }
+
+    /// Zero registers, heap, stacks and output, including spare capacity
+    ///
+    /// Uses volatile writes so the wipe isn't optimized away as a dead
+    /// store. Wrap the state in `WipeOnDrop` to run it on every exit path;
+    /// call it directly to scrub a state that stays alive. Leaves the
+    /// buffers empty, so `rebind` before the next run.
+    #[cfg(feature = "zeroize")]
+    pub fn wipe(&mut self) {
+        use zeroize::Zeroize;
+        self.regs.zeroize();
+        self.heap.zeroize();
+        self.stack.zeroize();
+        self.call_stack.zeroize();
+        self.output.zeroize();
+        self.result.zeroize();
+        self.heap_ptr = 0;
+        self.free_list.clear();
+        self.live_ranges.clear();
+    }
+
     /// Get yield mask for async VM
     /// Returns the mask used to determine yield frequency
     #[cfg(feature = "async_vm")]
@@ -343,7 +543,7 @@ impl<'a> VmState<'a> {
     /// Push value to stack
     #[inline]
     pub fn push(&mut self, value: u64) -> VmResult<()> {
-        if self.stack.len() >= MAX_STACK_SIZE {
+        if self.stack.len() >= self.max_stack {
             return Err(VmError::StackOverflow);
         }
         self.stack.push(value);
@@ -377,6 +577,9 @@ impl<'a> VmState<'a> {
     #[inline]
     pub fn get_reg(&self, idx: u8) -> VmResult<u64> {
         let index = idx as usize;
+        if index >= self.max_registers {
+            return Err(VmError::InvalidRegister);
+        }
         if index < self.regs.len() {
             Ok(self.regs[index])
         } else {
@@ -386,16 +589,16 @@ impl<'a> VmState<'a> {
     }
 
     /// Set register value
-    /// Automatically grows register file if needed (up to MAX_REGISTERS)
+    /// Automatically grows register file if needed (up to max_registers)
     #[inline]
     pub fn set_reg(&mut self, idx: u8, value: u64) -> VmResult<()> {
         let index = idx as usize;
+        if index >= self.max_registers {
+            return Err(VmError::InvalidRegister);
+        }
 
         // Grow register file if needed
         if index >= self.regs.len() {
-            if index >= MAX_REGISTERS {
-                return Err(VmError::InvalidRegister);
-            }
             // Grow to accommodate new register (fill with 0)
             self.regs.resize(index + 1, 0);
         }
@@ -414,6 +617,89 @@ impl<'a> VmState<'a> {
     // Heap Operations (Free-List Allocator)
     // =========================================================================
 
+    /// Enable/disable zeroing of freed blocks
+    /// When enabled, HEAP_FREE wipes the user region before it is recycled
+    #[inline]
+    pub fn set_zero_on_free(&mut self, enabled: bool) {
+        self.zero_on_free = enabled;
+    }
+
+    /// Select the branch operand encoding
+    ///
+    /// Must match how the bytecode was built: check
+    /// `BytecodeHeader::has_absolute_jumps` for packaged code. Absolute
+    /// operands address at most 64 KB of code. The SMC and streaming engines
+    /// only run relative code.
+    #[inline]
+    pub fn set_absolute_jumps(&mut self, enabled: bool) {
+        self.absolute_jumps = enabled;
+    }
+
+    /// Enable/disable the jump guard
+    ///
+    /// When enabled, the run loops record every instruction they execute
+    /// and fail with `InvalidJumpTarget` when control flow lands inside
+    /// one. This costs a `u16` per code byte per run and a check per
+    /// instruction, so it is meant for untrusted bytecode (`Sandbox`
+    /// enables it). The SMC engine always checks.
+    #[inline]
+    pub fn set_jump_guard(&mut self, enabled: bool) {
+        self.jump_guard = enabled;
+    }
+
+    /// Enable/disable strict heap checking
+    ///
+    /// Without it, heap accesses are only checked against the reserved heap
+    /// (`heap.len()`), so reading a freed block silently returns stale data.
+    /// With it, every access must fall inside a live HEAP_ALLOC block (or a
+    /// region grown by HEAP_STORE*_GROW); anything else in the reserved
+    /// space fails with `VmError::UseAfterFree`. Enable before the first
+    /// allocation: blocks allocated earlier aren't tracked.
+    #[inline]
+    pub fn set_strict_heap(&mut self, enabled: bool) {
+        self.strict_heap = enabled;
+    }
+
+    /// Bounds-check `len` bytes at `addr`, and liveness in strict mode
+    #[inline]
+    fn check_heap_access(&self, addr: usize, len: usize) -> VmResult<()> {
+        let end = addr.checked_add(len).ok_or(VmError::HeapOutOfBounds)?;
+        if end > self.heap.len() {
+            return Err(VmError::HeapOutOfBounds);
+        }
+        if self.strict_heap && len != 0 && !self.is_live(addr, end) {
+            return Err(VmError::UseAfterFree);
+        }
+        Ok(())
+    }
+
+    /// Check if [start, end) is covered by live ranges
+    ///
+    /// Touching ranges count as one, so an access can span adjacent regions
+    /// grown by HEAP_STORE*_GROW.
+    fn is_live(&self, start: usize, end: usize) -> bool {
+        let idx = self.live_ranges.partition_point(|&(s, _)| s <= start);
+        if idx == 0 {
+            return false;
+        }
+        let mut covered = self.live_ranges[idx - 1].1;
+        for &(s, e) in &self.live_ranges[idx..] {
+            if covered >= end || s != covered {
+                break;
+            }
+            covered = e;
+        }
+        covered >= end
+    }
+
+    /// Record a live range (strict_heap only)
+    fn mark_live(&mut self, start: usize, end: usize) {
+        if self.strict_heap && start < end {
+            let pos = self.live_ranges.partition_point(|&(s, _)| s < start);
+            self.live_ranges.insert(pos, (start, end));
+        }
+    }
+
     /// Allocate memory on the heap
     /// Returns the start address of the allocated block (user data, after header)
     ///
@@ -421,6 +707,11 @@ impl<'a> VmState<'a> {
     /// Alignment is guaranteed to be 8-byte aligned
     #[inline]
     pub fn heap_alloc(&mut self, size: usize) -> VmResult<u64> {
+        // Can never fit; also keeps the size arithmetic below from overflowing
+        if size > self.heap_limit {
+            return Err(VmError::HeapOutOfMemory);
+        }
+
         // Align user size to 8 bytes
         let aligned_user_size = (size + 7) & !7;
         // Total size includes header
@@ -445,6 +736,8 @@ impl<'a> VmState<'a> {
                 });
             }
 
+            self.heap_stats.record_alloc(total_size);
+            self.mark_live(user_addr, user_addr + aligned_user_size);
             return Ok(user_addr as u64);
         }
 
@@ -456,7 +749,7 @@ impl<'a> VmState<'a> {
 
         // Grow heap vector if needed
         if new_ptr > self.heap.len() {
-            self.heap.resize(new_ptr, 0);
+            self.resize_heap(new_ptr);
         }
 
         // Write header with size + ALLOCATED_FLAG
@@ -467,10 +760,21 @@ impl<'a> VmState<'a> {
         // User address is after header
         let user_addr = block_addr + ALLOC_HEADER_SIZE;
         self.heap_ptr = new_ptr;
+        self.heap_stats.record_alloc(total_size);
+        self.mark_live(user_addr, new_ptr);
 
         Ok(user_addr as u64)
     }
 
+    /// Extend the heap to `len` bytes, counting reallocations in `heap_stats`
+    #[inline]
+    fn resize_heap(&mut self, len: usize) {
+        if len > self.heap.capacity() {
+            self.heap_stats.heap_growths += 1;
+        }
+        self.heap.resize(len, 0);
+    }
+
     /// Find a free block that can fit the requested size (first-fit)
     #[inline]
     fn find_free_block(&self, total_size: usize) -> Option<usize> {
@@ -498,8 +802,14 @@ impl<'a> VmState<'a> {
         // Header is right before user data
         let header_addr = user_addr - ALLOC_HEADER_SIZE;
 
-        // Read header (contains size | ALLOCATED_FLAG)
-        let header = self.heap_read_u64(header_addr)?;
+        // Read header (contains size | ALLOCATED_FLAG); headers are never
+        // live, so this bypasses the strict_heap check
+        if header_addr + ALLOC_HEADER_SIZE > self.heap.len() {
+            return Err(VmError::HeapOutOfBounds);
+        }
+        let mut header_bytes = [0u8; 8];
+        header_bytes.copy_from_slice(&self.heap[header_addr..user_addr]);
+        let header = u64::from_le_bytes(header_bytes);
 
         // Double-free protection: check if block is still allocated
         if header & ALLOCATED_FLAG == 0 {
@@ -513,15 +823,34 @@ impl<'a> VmState<'a> {
             return Err(VmError::HeapOutOfBounds);
         }
 
+        // Strict mode: only the start of a live block can be freed
+        if self.strict_heap {
+            match self.live_ranges.binary_search_by_key(&user_addr, |&(s, _)| s) {
+                Ok(idx) => {
+                    self.live_ranges.remove(idx);
+                }
+                Err(_) => return Err(VmError::UseAfterFree),
+            }
+        }
+
         // Clear ALLOCATED_FLAG in header (mark as free)
         self.heap_write_u64_internal(header_addr, total_size as u64);
 
+        // Wipe user data so a reused block can't leak secrets
+        if self.zero_on_free {
+            let end = (header_addr + total_size).min(self.heap.len());
+            if let Some(data) = self.heap.get_mut(user_addr..end) {
+                data.fill(0);
+            }
+        }
+
         // Create free block and add to list with merge
         let new_block = FreeBlock {
             addr: header_addr,
             size: total_size,
         };
         self.add_free_block_with_merge(new_block);
+        self.heap_stats.record_free(total_size);
 
         Ok(())
     }
@@ -579,6 +908,12 @@ impl<'a> VmState<'a> {
         free_list_space + bump_space
     }
 
+    /// Get heap allocation statistics
+    #[inline]
+    pub fn alloc_stats(&self) -> AllocStats {
+        self.heap_stats
+    }
+
     /// Get number of blocks in free list
     #[inline]
     pub fn free_block_count(&self) -> usize {
@@ -588,27 +923,21 @@ impl<'a> VmState<'a> {
     /// Read byte from heap
     #[inline]
     pub fn heap_read_u8(&self, addr: usize) -> VmResult<u8> {
-        if addr >= self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 1)?;
         Ok(self.heap[addr])
     }
 
     /// Read u16 from heap (little-endian)
     #[inline]
     pub fn heap_read_u16(&self, addr: usize) -> VmResult<u16> {
-        if addr + 2 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 2)?;
         Ok(u16::from_le_bytes([self.heap[addr], self.heap[addr + 1]]))
     }
 
     /// Read u32 from heap (little-endian)
     #[inline]
     pub fn heap_read_u32(&self, addr: usize) -> VmResult<u32> {
-        if addr + 4 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 4)?;
         Ok(u32::from_le_bytes([
             self.heap[addr],
             self.heap[addr + 1],
@@ -620,9 +949,7 @@ impl<'a> VmState<'a> {
     /// Read u64 from heap (little-endian)
     #[inline]
     pub fn heap_read_u64(&self, addr: usize) -> VmResult<u64> {
-        if addr + 8 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 8)?;
         Ok(u64::from_le_bytes([
             self.heap[addr],
             self.heap[addr + 1],
@@ -635,13 +962,33 @@ impl<'a> VmState<'a> {
         ]))
     }
 
-    /// Write byte to heap
-    /// Note: Uses heap.len() for bounds check (not heap_ptr) to support free-list reuse
+    /// Grow heap so that `end` bytes are addressable (up to heap_limit)
+    ///
+    /// Used by the HEAP_STORE*_GROW opcodes for scratch buffers that are
+    /// written without a prior HEAP_ALLOC. The bump pointer is moved past
+    /// the grown region so later allocations never overlap it.
     #[inline]
-    pub fn heap_write_u8(&mut self, addr: usize, value: u8) -> VmResult<()> {
-        if addr >= self.heap.len() {
+    pub fn heap_grow_to(&mut self, end: usize) -> VmResult<()> {
+        if end > self.heap_limit {
             return Err(VmError::HeapOutOfBounds);
         }
+        if end > self.heap.len() {
+            self.resize_heap(end);
+        }
+        if end > self.heap_ptr {
+            let start = self.heap_ptr;
+            self.heap_ptr = end;
+            self.mark_live(start, end);
+        }
+        Ok(())
+    }
+
+    /// Write byte to heap
+    /// Note: Uses heap.len() for bounds check (not heap_ptr) to support free-list
+    /// reuse; see `set_strict_heap` for allocation-aware checks
+    #[inline]
+    pub fn heap_write_u8(&mut self, addr: usize, value: u8) -> VmResult<()> {
+        self.check_heap_access(addr, 1)?;
         self.heap[addr] = value;
         Ok(())
     }
@@ -649,9 +996,7 @@ impl<'a> VmState<'a> {
     /// Write u16 to heap (little-endian)
     #[inline]
     pub fn heap_write_u16(&mut self, addr: usize, value: u16) -> VmResult<()> {
-        if addr + 2 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 2)?;
         let bytes = value.to_le_bytes();
         self.heap[addr..addr + 2].copy_from_slice(&bytes);
         Ok(())
@@ -660,9 +1005,7 @@ impl<'a> VmState<'a> {
     /// Write u32 to heap (little-endian)
     #[inline]
     pub fn heap_write_u32(&mut self, addr: usize, value: u32) -> VmResult<()> {
-        if addr + 4 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 4)?;
         let bytes = value.to_le_bytes();
         self.heap[addr..addr + 4].copy_from_slice(&bytes);
         Ok(())
@@ -671,9 +1014,7 @@ impl<'a> VmState<'a> {
     /// Write u64 to heap (little-endian)
     #[inline]
     pub fn heap_write_u64(&mut self, addr: usize, value: u64) -> VmResult<()> {
-        if addr + 8 > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, 8)?;
         let bytes = value.to_le_bytes();
         self.heap[addr..addr + 8].copy_from_slice(&bytes);
         Ok(())
@@ -682,9 +1023,7 @@ impl<'a> VmState<'a> {
     /// Write bytes to heap
     #[inline]
     pub fn heap_write_bytes(&mut self, addr: usize, data: &[u8]) -> VmResult<()> {
-        if addr + data.len() > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, data.len())?;
         self.heap[addr..addr + data.len()].copy_from_slice(data);
         Ok(())
     }
@@ -692,12 +1031,19 @@ impl<'a> VmState<'a> {
     /// Read bytes from heap
     #[inline]
     pub fn heap_read_bytes(&self, addr: usize, len: usize) -> VmResult<&[u8]> {
-        if addr + len > self.heap.len() {
-            return Err(VmError::HeapOutOfBounds);
-        }
+        self.check_heap_access(addr, len)?;
         Ok(&self.heap[addr..addr + len])
     }
 
+    /// Copy bytes within the heap (overlapping ranges allowed)
+    #[inline]
+    pub fn heap_copy(&mut self, src: usize, dst: usize, len: usize) -> VmResult<()> {
+        self.check_heap_access(src, len)?;
+        self.check_heap_access(dst, len)?;
+        self.heap.copy_within(src..src + len, dst);
+        Ok(())
+    }
+
     /// Get current heap size (bytes allocated)
     #[inline]
     pub fn heap_size(&self) -> usize {
@@ -740,6 +1086,16 @@ impl<'a> VmState<'a> {
         }
     }
 
+    /// Set carry flag
+    #[inline]
+    pub fn set_carry_flag(&mut self, carry: bool) {
+        if carry {
+            self.flags |= flags::CARRY;
+        } else {
+            self.flags &= !flags::CARRY;
+        }
+    }
+
     /// Check if zero flag is set
     #[inline]
     pub fn is_zero(&self) -> bool {
@@ -771,11 +1127,7 @@ impl<'a> VmState<'a> {
         self.set_sign_flag(result);
 
         // Carry flag: set if a < b (unsigned)
-        if a < b {
-            self.flags |= flags::CARRY;
-        } else {
-            self.flags &= !flags::CARRY;
-        }
+        self.set_carry_flag(a < b);
 
         // Overflow flag: set if signed overflow occurred
         let sa = (a as i64) < 0;
@@ -806,7 +1158,7 @@ impl<'a> VmState<'a> {
     /// Read i16 from bytecode (little-endian), advance IP
     #[inline]
     pub fn read_i16(&mut self) -> VmResult<i16> {
-        if self.ip + 2 > self.code.len() {
+        if self.ip.saturating_add(2) > self.code.len() {
             return Err(VmError::InvalidBytecode);
         }
         let val = i16::from_le_bytes([self.code[self.ip], self.code[self.ip + 1]]);
@@ -817,7 +1169,7 @@ impl<'a> VmState<'a> {
     /// Read u16 from bytecode (little-endian), advance IP
     #[inline]
     pub fn read_u16(&mut self) -> VmResult<u16> {
-        if self.ip + 2 > self.code.len() {
+        if self.ip.saturating_add(2) > self.code.len() {
             return Err(VmError::InvalidBytecode);
         }
         let val = u16::from_le_bytes([self.code[self.ip], self.code[self.ip + 1]]);
@@ -828,7 +1180,7 @@ impl<'a> VmState<'a> {
     /// Read u32 from bytecode (little-endian), advance IP
     #[inline]
     pub fn read_u32(&mut self) -> VmResult<u32> {
-        if self.ip + 4 > self.code.len() {
+        if self.ip.saturating_add(4) > self.code.len() {
             return Err(VmError::InvalidBytecode);
         }
         let val = u32::from_le_bytes([
@@ -844,7 +1196,7 @@ impl<'a> VmState<'a> {
     /// Read u64 from bytecode (little-endian), advance IP
     #[inline]
     pub fn read_u64(&mut self) -> VmResult<u64> {
-        if self.ip + 8 > self.code.len() {
+        if self.ip.saturating_add(8) > self.code.len() {
             return Err(VmError::InvalidBytecode);
         }
         let val = u64::from_le_bytes([
@@ -865,13 +1217,24 @@ impl<'a> VmState<'a> {
     // Input/Output Operations
     // =========================================================================
 
+    /// Copy `N` input bytes at `offset`, decrypting them if the input is encrypted
+    #[inline]
+    fn input_bytes<const N: usize>(&self, offset: usize) -> VmResult<[u8; N]> {
+        if offset.saturating_add(N) > self.input.len() {
+            return Err(VmError::InputOutOfBounds);
+        }
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&self.input[offset..offset + N]);
+        if let Some(cipher) = self.input_cipher {
+            cipher.apply_input_keystream(offset, &mut bytes);
+        }
+        Ok(bytes)
+    }
+
     /// Read byte from input buffer
     #[inline]
     pub fn read_input(&self, offset: usize) -> VmResult<u8> {
-        if offset >= self.input.len() {
-            return Err(VmError::MemoryOutOfBounds);
-        }
-        Ok(self.input[offset])
+        self.input_bytes::<1>(offset).map(|[b]| b)
     }
 
     /// Read u8 from input buffer (alias for read_input)
@@ -883,99 +1246,99 @@ impl<'a> VmState<'a> {
     /// Read u16 from input buffer (little-endian)
     #[inline]
     pub fn read_input_u16(&self, offset: usize) -> VmResult<u16> {
-        if offset + 2 > self.input.len() {
-            return Err(VmError::MemoryOutOfBounds);
-        }
-        Ok(u16::from_le_bytes([
-            self.input[offset],
-            self.input[offset + 1],
-        ]))
+        self.input_bytes(offset).map(u16::from_le_bytes)
     }
 
     /// Read u32 from input buffer (little-endian)
     #[inline]
     pub fn read_input_u32(&self, offset: usize) -> VmResult<u32> {
-        if offset + 4 > self.input.len() {
-            return Err(VmError::MemoryOutOfBounds);
-        }
-        Ok(u32::from_le_bytes([
-            self.input[offset],
-            self.input[offset + 1],
-            self.input[offset + 2],
-            self.input[offset + 3],
-        ]))
+        self.input_bytes(offset).map(u32::from_le_bytes)
     }
 
     /// Read u64 from input buffer (little-endian)
     #[inline]
     pub fn read_input_u64(&self, offset: usize) -> VmResult<u64> {
-        if offset + 8 > self.input.len() {
-            return Err(VmError::MemoryOutOfBounds);
+        self.input_bytes(offset).map(u64::from_le_bytes)
+    }
+
+    /// Grow output so bytes up to `end` are writable
+    #[inline]
+    fn output_reserve(&mut self, end: usize) -> VmResult<()> {
+        if end > MAX_OUTPUT_SIZE {
+            return Err(VmError::OutputOutOfBounds);
         }
-        Ok(u64::from_le_bytes([
-            self.input[offset],
-            self.input[offset + 1],
-            self.input[offset + 2],
-            self.input[offset + 3],
-            self.input[offset + 4],
-            self.input[offset + 5],
-            self.input[offset + 6],
-            self.input[offset + 7],
-        ]))
+        if end > self.output.len() {
+            self.output.resize(end, 0);
+        }
+        Ok(())
+    }
+
+    /// Write bytes to output buffer at offset
+    #[inline]
+    fn write_output_bytes(&mut self, offset: usize, bytes: &[u8]) -> VmResult<()> {
+        let end = offset.checked_add(bytes.len()).ok_or(VmError::OutputOutOfBounds)?;
+        self.output_reserve(end)?;
+        self.output[offset..end].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Move the output buffer out of the state without copying it
+    ///
+    /// With the `zeroize` feature everything left in the state is wiped;
+    /// the returned buffer belongs to the caller.
+    pub fn take_output(mut self) -> Vec<u8> {
+        let output = core::mem::take(&mut self.output);
+        #[cfg(feature = "zeroize")]
+        self.wipe();
+        output
+    }
+
+    /// Move the results of a finished run into an owned `VmOutcome`
+    ///
+    /// Buffers are moved, not copied. As with `take_output`, the stacks
+    /// left behind are wiped under the `zeroize` feature.
+    pub fn into_outcome(mut self) -> VmOutcome {
+        let outcome = VmOutcome {
+            result: self.result,
+            output: core::mem::take(&mut self.output),
+            heap: core::mem::take(&mut self.heap),
+            regs: core::mem::take(&mut self.regs),
+            flags: self.flags,
+            instruction_count: self.instruction_count,
+        };
+        #[cfg(feature = "zeroize")]
+        self.wipe();
+        outcome
+    }
+
+    /// Append bytes to the end of the output buffer
+    #[inline]
+    pub fn append_output(&mut self, bytes: &[u8]) -> VmResult<()> {
+        self.write_output_bytes(self.output.len(), bytes)
     }
 
     /// Write u8 to output buffer
     #[inline]
     pub fn write_output_u8(&mut self, offset: usize, value: u8) -> VmResult<()> {
-        if offset >= self.output.len() {
-            self.output.resize(offset + 1, 0);
-        }
-        self.output[offset] = value;
-        Ok(())
+        self.write_output_bytes(offset, &[value])
     }
 
     /// Write u16 to output buffer (little-endian)
     #[inline]
     pub fn write_output_u16(&mut self, offset: usize, value: u16) -> VmResult<()> {
-        if offset + 2 > self.output.len() {
-            self.output.resize(offset + 2, 0);
-        }
-        let bytes = value.to_le_bytes();
-        self.output[offset] = bytes[0];
-        self.output[offset + 1] = bytes[1];
-        Ok(())
+        self.write_output_bytes(offset, &value.to_le_bytes())
     }
 
     /// Write u32 to output buffer (little-endian)
     #[inline]
     pub fn write_output_u32(&mut self, offset: usize, value: u32) -> VmResult<()> {
-        if offset + 4 > self.output.len() {
-            self.output.resize(offset + 4, 0);
-        }
-        let bytes = value.to_le_bytes();
-        self.output[offset] = bytes[0];
-        self.output[offset + 1] = bytes[1];
-        self.output[offset + 2] = bytes[2];
-        self.output[offset + 3] = bytes[3];
-        Ok(())
+        self.write_output_bytes(offset, &value.to_le_bytes())
     }
 
     /// Write u64 to output buffer (little-endian)
     #[inline]
     pub fn write_output_u64(&mut self, offset: usize, value: u64) -> VmResult<()> {
-        if offset + 8 > self.output.len() {
-            self.output.resize(offset + 8, 0);
-        }
-        let bytes = value.to_le_bytes();
-        self.output[offset] = bytes[0];
-        self.output[offset + 1] = bytes[1];
-        self.output[offset + 2] = bytes[2];
-        self.output[offset + 3] = bytes[3];
-        self.output[offset + 4] = bytes[4];
-        self.output[offset + 5] = bytes[5];
-        self.output[offset + 6] = bytes[6];
-        self.output[offset + 7] = bytes[7];
-        Ok(())
+        self.write_output_bytes(offset, &value.to_le_bytes())
     }
 
     /// Get input length
@@ -985,6 +1348,56 @@ impl<'a> VmState<'a> {
     }
 }
 
+/// Guard that wipes a `VmState` when dropped
+///
+/// Secrets in registers, heap, stacks and output don't outlive the guard,
+/// including when execution bails out with an error. Derefs to the state,
+/// so it runs like one: `run(&mut guard)`. `VmState` itself has no drop
+/// glue, so its buffers can still be moved out when the wipe isn't wanted.
+#[cfg(feature = "zeroize")]
+pub struct WipeOnDrop<'a>(VmState<'a>);
+
+#[cfg(feature = "zeroize")]
+impl<'a> WipeOnDrop<'a> {
+    /// Take ownership of `state`, wiping it on drop
+    pub fn new(state: VmState<'a>) -> Self {
+        Self(state)
+    }
+
+    /// Move the output buffer out, wiping everything else
+    pub fn take_output(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.0.output)
+    }
+
+    /// Move the results into a `VmOutcome`, wiping everything else
+    pub fn into_outcome(mut self) -> VmOutcome {
+        core::mem::replace(&mut self.0, VmState::new(&[], &[])).into_outcome()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a> core::ops::Deref for WipeOnDrop<'a> {
+    type Target = VmState<'a>;
+
+    fn deref(&self) -> &VmState<'a> {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a> core::ops::DerefMut for WipeOnDrop<'a> {
+    fn deref_mut(&mut self) -> &mut VmState<'a> {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for WipeOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -1088,6 +1501,28 @@ mod tests {
         assert_eq!(state.free_block_count(), 0);
     }
 
+    #[test]
+    fn test_max_registers_cap() {
+        let code = &[];
+        let input = &[];
+        let mut state = VmState::with_max_registers(code, input, 16);
+        assert_eq!(state.reg_count(), 16);
+
+        assert!(state.set_reg(15, 7).is_ok());
+        assert_eq!(state.get_reg(15).unwrap(), 7);
+        assert_eq!(state.set_reg(16, 1), Err(VmError::InvalidRegister));
+        assert_eq!(state.get_reg(200), Err(VmError::InvalidRegister));
+        assert_eq!(state.reg_count(), 16);
+
+        state.reset();
+        assert_eq!(state.reg_count(), 16);
+
+        // Default stays at MAX_REGISTERS
+        let mut state = VmState::new(code, input);
+        assert!(state.set_reg(255, 1).is_ok());
+        assert_eq!(state.reg_count(), MAX_REGISTERS);
+    }
+
     #[test]
     fn test_heap_free_basic() {
         let code = &[];
@@ -0,0 +1,275 @@
+//! Streaming Execution Engine
+//!
+//! Executes bytecode pulled incrementally from an iterator (or reader)
+//! instead of a fully materialized `&[u8]`. Only a sliding window of the
+//! code is buffered, which complements SMC's decrypted window for very
+//! large routines streamed from disk or network.
+//!
+//! ## Window Semantics
+//!
+//! ```text
+//!            base                 ip              buffered end
+//!   dropped   |<---- window ----->|<- lookahead ->|   not yet read
+//! ```
+//!
+//! - Forward jumps read ahead until the target is buffered
+//! - Backward jumps must land inside the window, otherwise they fail with
+//!   `VmError::InvalidJumpTarget`
+//! - Return addresses on the call stack are never dropped
+//! - HASH_CHECK only sees the buffered window, so whole-code integrity
+//!   checks are not meaningful in this mode
+//! - Jumps into the middle of an executed instruction are not detected,
+//!   since its boundaries leave the window with the code
+
+use crate::error::{VmError, VmResult};
+use crate::native::NativeRegistry;
+use crate::state::{VmState, AllocStats, FreeBlock, MAX_INSTRUCTIONS, DEFAULT_REGISTER_CAPACITY, DEFAULT_HEAP_SIZE};
+use crate::build_config::OPCODE_DECODE;
+use crate::handlers::dispatch::dispatch_indirect;
+use crate::opcodes::control;
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// Default number of already-executed bytes kept for backward jumps
+pub const DEFAULT_STREAM_WINDOW: usize = 4096;
+
+/// Bytes buffered past the current opcode (covers the longest instruction)
+const LOOKAHEAD: usize = 16;
+
+/// Execution state that survives across instructions
+/// Heavy buffers are moved in/out of the per-instruction VmState, not cloned
+struct StreamExecState {
+    regs: Vec<u64>,
+    heap: Vec<u8>,
+    heap_ptr: usize,
+    heap_limit: usize,
+    free_list: Vec<FreeBlock>,
+    zero_on_free: bool,
+    heap_stats: AllocStats,
+    stack: Vec<u64>,
+    /// Absolute return addresses
+    call_stack: Vec<usize>,
+    flags: u8,
+    instruction_count: u64,
+    halted: bool,
+    result: u64,
+    output: Vec<u8>,
+}
+
+impl StreamExecState {
+    fn new() -> Self {
+        Self {
+            regs: vec![0u64; DEFAULT_REGISTER_CAPACITY],
+            heap: Vec::new(),
+            heap_ptr: 0,
+            heap_limit: DEFAULT_HEAP_SIZE,
+            free_list: Vec::new(),
+            zero_on_free: false,
+            heap_stats: AllocStats::default(),
+            stack: Vec::with_capacity(64),
+            call_stack: Vec::with_capacity(16),
+            flags: 0,
+            instruction_count: 0,
+            halted: false,
+            result: 0,
+            output: Vec::new(),
+        }
+    }
+
+    /// Move state into a VmState whose code view starts at `base`
+    fn load_into(&mut self, state: &mut VmState, base: usize) {
+        core::mem::swap(&mut state.regs, &mut self.regs);
+        core::mem::swap(&mut state.heap, &mut self.heap);
+        core::mem::swap(&mut state.free_list, &mut self.free_list);
+        core::mem::swap(&mut state.stack, &mut self.stack);
+        core::mem::swap(&mut state.call_stack, &mut self.call_stack);
+        core::mem::swap(&mut state.output, &mut self.output);
+        for addr in state.call_stack.iter_mut() {
+            *addr -= base;
+        }
+        state.heap_ptr = self.heap_ptr;
+        state.heap_limit = self.heap_limit;
+        state.zero_on_free = self.zero_on_free;
+        state.heap_stats = self.heap_stats;
+        state.flags = self.flags;
+        state.instruction_count = self.instruction_count;
+        state.halted = self.halted;
+        state.result = self.result;
+    }
+
+    /// Move state back out of a VmState whose code view starts at `base`
+    fn store_from(&mut self, state: &mut VmState, base: usize) {
+        core::mem::swap(&mut state.regs, &mut self.regs);
+        core::mem::swap(&mut state.heap, &mut self.heap);
+        core::mem::swap(&mut state.free_list, &mut self.free_list);
+        core::mem::swap(&mut state.stack, &mut self.stack);
+        core::mem::swap(&mut state.call_stack, &mut self.call_stack);
+        core::mem::swap(&mut state.output, &mut self.output);
+        for addr in self.call_stack.iter_mut() {
+            *addr += base;
+        }
+        self.heap_ptr = state.heap_ptr;
+        self.heap_limit = state.heap_limit;
+        self.zero_on_free = state.zero_on_free;
+        self.heap_stats = state.heap_stats;
+        self.flags = state.flags;
+        self.instruction_count = state.instruction_count;
+        self.halted = state.halted;
+        self.result = state.result;
+    }
+}
+
+/// Sliding code buffer over a byte source
+struct CodeWindow<F> {
+    source: F,
+    buffer: Vec<u8>,
+    /// Absolute offset of buffer[0]
+    base: usize,
+    eof: bool,
+}
+
+impl<F: FnMut() -> VmResult<Option<u8>>> CodeWindow<F> {
+    /// Absolute end of buffered code
+    fn end(&self) -> usize {
+        self.base + self.buffer.len()
+    }
+
+    /// Pull bytes until `abs_end` is buffered or the source is exhausted
+    fn fill_to(&mut self, abs_end: usize) -> VmResult<()> {
+        while !self.eof && self.end() < abs_end {
+            match (self.source)()? {
+                Some(byte) => self.buffer.push(byte),
+                None => self.eof = true,
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop bytes before `abs_pos`
+    fn drop_before(&mut self, abs_pos: usize) {
+        if abs_pos > self.base {
+            let n = (abs_pos - self.base).min(self.buffer.len());
+            self.buffer.drain(..n);
+            self.base += n;
+        }
+    }
+}
+
+/// Execute bytecode streamed from an iterator
+pub fn execute_stream<I>(code: I, input: &[u8]) -> VmResult<u64>
+where
+    I: IntoIterator<Item = u8>,
+{
+    execute_stream_with_window(code, input, DEFAULT_STREAM_WINDOW)
+}
+
+/// Execute bytecode streamed from an iterator with a custom backward window
+pub fn execute_stream_with_window<I>(code: I, input: &[u8], window: usize) -> VmResult<u64>
+where
+    I: IntoIterator<Item = u8>,
+{
+    let mut iter = code.into_iter();
+    let registry = NativeRegistry::new();
+    run_stream(|| Ok(iter.next()), input, window, &registry)
+}
+
+/// Execute bytecode streamed from a reader
+///
+/// Read errors abort execution with `VmError::InvalidBytecode`.
+#[cfg(feature = "std")]
+pub fn execute_reader<R: std::io::Read>(reader: R, input: &[u8]) -> VmResult<u64> {
+    use std::io::Read;
+
+    let mut bytes = std::io::BufReader::new(reader).bytes();
+    let registry = NativeRegistry::new();
+    run_stream(
+        || bytes.next().transpose().map_err(|_| VmError::InvalidBytecode),
+        input,
+        DEFAULT_STREAM_WINDOW,
+        &registry,
+    )
+}
+
+/// Streaming execution loop
+fn run_stream<F>(
+    source: F,
+    input: &[u8],
+    window: usize,
+    registry: &NativeRegistry,
+) -> VmResult<u64>
+where
+    F: FnMut() -> VmResult<Option<u8>>,
+{
+    let mut code = CodeWindow {
+        source,
+        buffer: Vec::with_capacity(window + LOOKAHEAD),
+        base: 0,
+        eof: false,
+    };
+    let mut exec_state = StreamExecState::new();
+    let mut ip = 0usize;
+
+    loop {
+        if ip < code.base {
+            return Err(VmError::InvalidJumpTarget);
+        }
+        code.fill_to(ip + LOOKAHEAD)?;
+        if exec_state.halted || ip >= code.end() {
+            break;
+        }
+
+        exec_state.instruction_count += 1;
+        if exec_state.instruction_count > MAX_INSTRUCTIONS {
+            return Err(VmError::MaxInstructionsExceeded);
+        }
+
+        let opcode = code.buffer[ip - code.base];
+
+        // Make sure relative jump/call targets ahead of us are buffered
+        if let Some(target) = branch_target(&code.buffer, ip - code.base, opcode) {
+            code.fill_to((target + code.base).saturating_add(1))?;
+        }
+
+        {
+            let base = code.base;
+            let mut state = VmState::new(&code.buffer, input);
+            exec_state.load_into(&mut state, base);
+            state.ip = ip - base + 1;
+
+            let result = dispatch_indirect(&mut state, opcode, registry);
+
+            ip = state.ip + base;
+            exec_state.store_from(&mut state, base);
+            result?;
+        }
+
+        // Slide the window, never past a pending return address
+        let keep_from = exec_state
+            .call_stack
+            .iter()
+            .copied()
+            .fold(ip, usize::min)
+            .saturating_sub(window);
+        if keep_from >= code.base + window {
+            code.drop_before(keep_from);
+        }
+    }
+
+    Ok(exec_state.result)
+}
+
+/// Buffer-relative target of a relative branch at `pos`, if any
+fn branch_target(buffer: &[u8], pos: usize, opcode: u8) -> Option<usize> {
+    match OPCODE_DECODE[opcode as usize] {
+        control::JMP | control::JZ | control::JNZ |
+        control::JGT | control::JLT | control::JGE | control::JLE |
+        control::CALL => {
+            let lo = *buffer.get(pos + 1)?;
+            let hi = *buffer.get(pos + 2)?;
+            let offset = i16::from_le_bytes([lo, hi]);
+            (pos + 3).checked_add_signed(offset as isize)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,46 @@
+//! Build Watermark
+//!
+//! Every build embeds a 128-bit watermark derived from the customer ID,
+//! build seed and build timestamp (see `generate_watermark` in build.rs).
+//! These helpers read it back and recompute it so a licensee, or the
+//! vendor holding the build records, can confirm which build a binary is.
+//!
+//! ```text
+//! WATERMARK = SHA256(customer_id || build_seed || timestamp_le || "watermark-v1")[..16]
+//! ```
+
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::build_config::WATERMARK;
+
+/// Domain separator, must match build.rs
+const WATERMARK_DOMAIN: &[u8] = b"watermark-v1";
+
+/// Watermark embedded in this binary
+#[inline]
+pub fn current() -> [u8; 16] {
+    WATERMARK
+}
+
+/// Compute the watermark for the given build inputs
+/// Mirrors `generate_watermark` in build.rs
+pub fn compute(customer_id: &str, build_seed: &[u8; 32], timestamp: u64) -> [u8; 16] {
+    let mut hasher = Sha256::new();
+    hasher.update(customer_id.as_bytes());
+    hasher.update(build_seed);
+    hasher.update(timestamp.to_le_bytes());
+    hasher.update(WATERMARK_DOMAIN);
+    let hash = hasher.finalize();
+
+    let mut watermark = [0u8; 16];
+    watermark.copy_from_slice(&hash[..16]);
+    watermark
+}
+
+/// Check whether this binary was built from the given inputs (constant time)
+pub fn verify(customer_id: &str, build_seed: &[u8; 32], timestamp: u64) -> bool {
+    compute(customer_id, build_seed, timestamp)
+        .ct_eq(&current())
+        .into()
+}
@@ -6,6 +6,9 @@ mod tables;
 mod generator;
 mod cipher;
 
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 pub use tables::{WhiteboxTables, WhiteboxTablesLite, WHITEBOX_TABLE_SIZE};
 pub use cipher::{whitebox_encrypt, whitebox_decrypt, whitebox_encrypt_lite};
 pub use generator::{generate_tables, generate_tables_lite};
@@ -55,6 +58,31 @@ pub fn init_tables() -> WhiteboxTables {
     }
 }
 
+/// Process-global tables, reconstructed on first use
+#[cfg(feature = "std")]
+static CACHED_TABLES: std::sync::OnceLock<WhiteboxTables> = std::sync::OnceLock::new();
+
+/// Process-global tables, reconstructed on first use
+#[cfg(not(feature = "std"))]
+static CACHED_TABLES: spin::Once<WhiteboxTables> = spin::Once::new();
+
+/// Get the process-global whitebox tables
+///
+/// Tables are reconstructed once on first call and shared afterwards.
+/// The convenience functions (`encrypt_block`, `derive_bytecode_key`,
+/// `derive_smc_key`, `derive_nonce`, ...) all use this instance.
+/// Call `init_tables()` instead for a fresh, independently owned copy.
+pub fn cached_tables() -> &'static WhiteboxTables {
+    #[cfg(feature = "std")]
+    {
+        CACHED_TABLES.get_or_init(init_tables)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        CACHED_TABLES.call_once(init_tables)
+    }
+}
+
 /// Initialize lightweight whitebox tables (~40KB instead of ~500KB)
 /// Less secure but smaller footprint
 ///
@@ -79,18 +107,16 @@ pub fn init_tables_lite() -> WhiteboxTablesLite {
 }
 
 /// Encrypt a block using build-time derived key (convenience function)
-/// Creates tables on-demand - for repeated use, prefer init_tables() once
+/// Uses the cached tables from `cached_tables()`
 pub fn encrypt_block(block: &mut [u8; AES_BLOCK_SIZE]) {
-    let tables = init_tables();
-    whitebox_encrypt(block, &tables);
+    whitebox_encrypt(block, cached_tables());
 }
 
 /// Encrypt multiple blocks using the same tables
-/// More efficient than calling encrypt_block repeatedly
 pub fn encrypt_blocks(blocks: &mut [[u8; AES_BLOCK_SIZE]]) {
-    let tables = init_tables();
+    let tables = cached_tables();
     for block in blocks.iter_mut() {
-        whitebox_encrypt(block, &tables);
+        whitebox_encrypt(block, tables);
     }
 }
 
@@ -112,8 +138,7 @@ pub fn encrypt_blocks(blocks: &mut [[u8; AES_BLOCK_SIZE]]) {
 /// # Returns
 /// A 32-byte derived key
 pub fn derive_key_from_hash(domain_hash: &[u8; 32]) -> [u8; 32] {
-    let tables = init_tables();
-    derive_key_from_hash_with_tables(domain_hash, &tables)
+    derive_key_from_hash_with_tables(domain_hash, cached_tables())
 }
 
 /// Derive key from pre-computed hash using pre-initialized tables
@@ -207,7 +232,6 @@ pub fn derive_smc_key() -> [u8; 32] {
 pub fn derive_nonce(counter: u64) -> [u8; 12] {
     use crate::build_config::whitebox_config::get_nonce_domain_hash;
 
-    let tables = init_tables();
     let nonce_hash = get_nonce_domain_hash();
 
     // Create block from counter + pre-computed nonce domain hash
@@ -216,7 +240,7 @@ pub fn derive_nonce(counter: u64) -> [u8; 12] {
     // Use first 8 bytes of nonce domain hash instead of "wbc-nonce" string
     block[8..16].copy_from_slice(&nonce_hash[0..8]);
 
-    whitebox_encrypt(&mut block, &tables);
+    whitebox_encrypt(&mut block, cached_tables());
 
     // Take first 12 bytes as nonce
     let mut nonce = [0u8; 12];
@@ -239,6 +263,15 @@ pub struct WhiteboxCryptoContext {
     nonce_counter: u64,
 }
 
+// Keys and tables are deliberately left out of the output
+impl core::fmt::Debug for WhiteboxCryptoContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WhiteboxCryptoContext")
+            .field("nonce_counter", &self.nonce_counter)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Default for WhiteboxCryptoContext {
     fn default() -> Self {
         Self::new()
@@ -316,4 +349,44 @@ impl WhiteboxCryptoContext {
     pub fn wbc_encrypt(&self, block: &mut [u8; AES_BLOCK_SIZE]) {
         whitebox_encrypt(block, &self.tables);
     }
+
+    /// XOR `data` with the input keystream starting at byte `offset`
+    ///
+    /// CTR mode: keystream block `i` is the WBC encryption of
+    /// `[i as u64 LE | nonce domain hash[8..16]]`. Encryption and
+    /// decryption are the same operation, and any byte range can be
+    /// processed independently, which is what lazy per-access decryption
+    /// of `VmState::input` relies on.
+    ///
+    /// The keystream is fixed per build, so this keeps plaintext input out
+    /// of memory but is not a substitute for authenticated encryption.
+    pub fn apply_input_keystream(&self, offset: usize, data: &mut [u8]) {
+        use crate::build_config::whitebox_config::get_nonce_domain_hash;
+
+        let nonce_hash = get_nonce_domain_hash();
+        let mut pos = offset;
+        let mut done = 0;
+
+        while done < data.len() {
+            let mut block = [0u8; AES_BLOCK_SIZE];
+            block[0..8].copy_from_slice(&((pos / AES_BLOCK_SIZE) as u64).to_le_bytes());
+            block[8..16].copy_from_slice(&nonce_hash[8..16]);
+            whitebox_encrypt(&mut block, &self.tables);
+
+            let start = pos % AES_BLOCK_SIZE;
+            let n = (AES_BLOCK_SIZE - start).min(data.len() - done);
+            for (byte, key) in data[done..done + n].iter_mut().zip(&block[start..start + n]) {
+                *byte ^= key;
+            }
+            pos += n;
+            done += n;
+        }
+    }
+
+    /// Encrypt an input buffer for `execute_with_encrypted_input`
+    pub fn encrypt_input(&self, input: &[u8]) -> Vec<u8> {
+        let mut encrypted = input.to_vec();
+        self.apply_input_keystream(0, &mut encrypted);
+        encrypted
+    }
 }
@@ -0,0 +1,102 @@
+//! ABI Version Tests
+//!
+//! Every package header records the VM ABI (opcode set) it was built for.
+//! Parsing or running a package built for another ABI fails with
+//! `AbiMismatch` instead of executing misread opcodes.
+
+use aegis_vm::{
+    execute_verified, BytecodeBuilder, CryptoContext, VmError,
+    bytecode::{BytecodeFlags, BytecodeHeader, BytecodePackage, FORMAT_VERSION, VM_ABI_VERSION},
+};
+
+/// input[0] * 3
+fn code() -> Vec<u8> {
+    BytecodeBuilder::new().native_read(0).push_imm8(3).mul().halt().build().unwrap()
+}
+
+fn context() -> CryptoContext {
+    CryptoContext::new([0x42; 32])
+}
+
+fn sealed(ctx: &mut CryptoContext) -> BytecodePackage {
+    let (ciphertext, nonce, tag) = ctx.encrypt(&code()).unwrap();
+    let mut header = BytecodeHeader::new(ctx.build_id, 0, BytecodeFlags::Encrypted as u16);
+    header.nonce = nonce;
+    header.tag = tag;
+    header.code_len = ciphertext.len() as u32;
+    BytecodePackage { header, code: ciphertext }
+}
+
+const INPUT: [u8; 8] = 14u64.to_le_bytes();
+
+// ============================================================================
+// Matching ABI
+// ============================================================================
+
+#[test]
+fn test_new_header_carries_current_abi() {
+    let header = BytecodeHeader::new(1, 2, 0);
+    assert_eq!(header.abi, VM_ABI_VERSION);
+    assert_eq!(header.version, FORMAT_VERSION);
+    assert_eq!(header.check_abi(), Ok(()));
+
+    let parsed = BytecodeHeader::from_bytes(&header.to_bytes()).unwrap();
+    assert_eq!(parsed.abi, VM_ABI_VERSION);
+}
+
+#[test]
+fn test_execute_verified_runs_matching_package() {
+    let ctx = &mut context();
+    assert_eq!(execute_verified(&sealed(ctx), ctx, &INPUT), Ok(42));
+
+    let plain = BytecodePackage::new_plaintext(code(), 0);
+    assert_eq!(execute_verified(&plain, ctx, &INPUT), Ok(42));
+
+    let restored = BytecodePackage::from_bytes(&sealed(ctx).to_bytes()).unwrap();
+    assert_eq!(execute_verified(&restored, ctx, &INPUT), Ok(42));
+}
+
+// ============================================================================
+// Mismatched ABI
+// ============================================================================
+
+#[test]
+fn test_from_bytes_rejects_other_abi() {
+    for abi in [0, VM_ABI_VERSION + 1, u16::MAX] {
+        let mut package = BytecodePackage::new_plaintext(code(), 0);
+        package.header.abi = abi;
+        let bytes = package.to_bytes();
+        assert_eq!(BytecodeHeader::from_bytes(&bytes).err(), Some(VmError::AbiMismatch), "abi {abi}");
+        assert_eq!(BytecodePackage::from_bytes(&bytes).err(), Some(VmError::AbiMismatch), "abi {abi}");
+    }
+}
+
+#[test]
+fn test_execute_verified_rejects_other_abi() {
+    let ctx = &mut context();
+    let mut package = sealed(ctx);
+    package.header.abi = VM_ABI_VERSION + 1;
+    assert_eq!(package.header.check_abi(), Err(VmError::AbiMismatch));
+    assert_eq!(execute_verified(&package, ctx, &INPUT), Err(VmError::AbiMismatch));
+
+    let mut plain = BytecodePackage::new_plaintext(code(), 0);
+    plain.header.abi = 0;
+    assert_eq!(execute_verified(&plain, ctx, &INPUT), Err(VmError::AbiMismatch));
+}
+
+#[test]
+fn test_untagged_format_is_rejected() {
+    // Version 1 headers predate the ABI field
+    let mut header = BytecodeHeader::new(0, 0, 0);
+    header.version = 1;
+    assert_eq!(BytecodeHeader::from_bytes(&header.to_bytes()).err(), Some(VmError::AbiMismatch));
+
+    header.version = FORMAT_VERSION + 1;
+    assert_eq!(BytecodeHeader::from_bytes(&header.to_bytes()).err(), Some(VmError::InvalidBytecode));
+}
+
+#[test]
+fn test_abi_mismatch_error_code() {
+    assert_eq!(VmError::AbiMismatch.code(), 27);
+    assert_eq!(format!("{:?}", VmError::AbiMismatch), "E27");
+}
@@ -0,0 +1,150 @@
+//! Absolute Jump Tests
+//!
+//! Branch operands are i16 offsets from the next instruction by default, or
+//! u16 offsets from the start of the code in absolute mode. The same
+//! program must compute the same results in both encodings.
+
+use aegis_vm::{
+    execute, execute_absolute, run, BytecodeBuilder, BytecodePackage, Sandbox, VmError, VmState,
+    bytecode::{BytecodeFlags, validate, validate_absolute},
+    build_config::{BUILD_ID, opcodes::{stack, control, exec}},
+};
+
+/// sum(1..=input[0]) * 2, with a loop, a forward skip and a subroutine
+fn sum_code(absolute: bool) -> Vec<u8> {
+    BytecodeBuilder::new()
+        .absolute_jumps(absolute)
+        .native_read(0).pop_reg(0)
+        .push_imm8(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jz("done")
+        .label("loop")
+        .push_reg(0).add()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .label("done")
+        .call("double")
+        .halt()
+        .label("double")
+        .dup().add().ret()
+        .build()
+        .unwrap()
+}
+
+fn word(v: u64) -> [u8; 8] {
+    v.to_le_bytes()
+}
+
+// ============================================================================
+// Both encodings compute the same thing
+// ============================================================================
+
+#[test]
+fn test_same_program_same_results() {
+    let relative = sum_code(false);
+    let absolute = sum_code(true);
+    assert_ne!(relative, absolute, "branch operands should be encoded differently");
+    assert_eq!(relative.len(), absolute.len());
+
+    for n in [0u64, 1, 5, 100] {
+        let expected = n * (n + 1);
+        assert_eq!(execute(&relative, &word(n)), Ok(expected));
+        assert_eq!(execute_absolute(&absolute, &word(n)), Ok(expected));
+    }
+}
+
+#[test]
+fn test_absolute_operand_is_code_offset() {
+    // JMP to offset 5 skips the error exit
+    let code = [
+        control::JMP, 5, 0,
+        exec::HALT_ERR, 1,
+        stack::PUSH_IMM8, 42,
+        exec::HALT,
+    ];
+    assert_eq!(execute_absolute(&code, &[]), Ok(42));
+    // Read as relative, the same operand jumps to the end of the code
+    assert_eq!(execute(&code, &[]), Ok(0));
+}
+
+#[test]
+fn test_absolute_target_out_of_range() {
+    let code = [control::JMP, 0xFF, 0x00, exec::HALT];
+    assert_eq!(execute_absolute(&code, &[]), Err(VmError::InvalidJumpTarget));
+}
+
+#[test]
+fn test_untaken_branch_target_is_not_checked() {
+    let code = [
+        stack::PUSH_IMM8, 1,
+        stack::PUSH_IMM8, 0,
+        control::CMP,
+        control::JZ, 0xFF, 0xFF,
+        stack::DROP,
+        exec::HALT,
+    ];
+    assert_eq!(execute_absolute(&code, &[]), Ok(1));
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+#[test]
+fn test_validate_absolute_program() {
+    let code = sum_code(true);
+    assert_eq!(validate_absolute(&code), Ok(()));
+    assert_eq!(validate(&sum_code(false)), Ok(()));
+    assert_eq!(Sandbox::new().absolute_jumps(true).run(&code, &word(10)), Ok(110));
+}
+
+#[test]
+fn test_validate_absolute_rejects_bad_targets() {
+    // Offset 4 is the HALT_ERR operand; 0xFF is past the end
+    let into_operand = [control::JMP, 4, 0, exec::HALT_ERR, 1, exec::HALT];
+    let out_of_range = [control::JMP, 0xFF, 0x00, exec::HALT];
+    assert_eq!(validate_absolute(&into_operand), Err(VmError::InvalidJumpTarget));
+    assert_eq!(validate_absolute(&out_of_range), Err(VmError::InvalidJumpTarget));
+    // Jumping to the end of the code is valid, as for relative branches
+    assert_eq!(validate_absolute(&[control::JMP, 4, 0, exec::HALT]), Ok(()));
+}
+
+// ============================================================================
+// Header flag
+// ============================================================================
+
+#[test]
+fn test_package_flag_selects_mode() {
+    let mut package = BytecodePackage::new_plaintext(sum_code(true), BUILD_ID);
+    package.header.flags |= BytecodeFlags::AbsoluteJumps as u16;
+
+    let parsed = BytecodePackage::from_bytes(&package.to_bytes()).unwrap();
+    assert!(parsed.header.has_absolute_jumps());
+
+    let input = word(10);
+    let mut state = VmState::new(&parsed.code, &input);
+    state.set_absolute_jumps(parsed.header.has_absolute_jumps());
+    run(&mut state).unwrap();
+    assert_eq!(state.result, 110);
+}
+
+#[test]
+fn test_relative_package_has_no_flag() {
+    let package = BytecodePackage::new_plaintext(sum_code(false), BUILD_ID);
+    assert!(!package.header.has_absolute_jumps());
+}
+
+#[test]
+fn test_rebind_keeps_absolute_mode() {
+    let code = sum_code(true);
+    let (first, second) = (word(3), word(4));
+    let mut state = VmState::new(&code, &first);
+    state.set_absolute_jumps(true);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, 12);
+
+    state.rebind(&code, &second);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, 20);
+}
@@ -0,0 +1,116 @@
+//! Batched Execution Tests
+//!
+//! `execute_batch` reuses one VmState across inputs; every result must match
+//! an independent `execute` call, including errors.
+
+use std::time::Instant;
+
+use aegis_vm::{
+    execute, execute_batch, VmError,
+    build_config::opcodes::{stack, arithmetic, control, heap, native, exec},
+};
+
+/// First input word plus the word count, staged through a heap cell;
+/// empty input halts with an error
+fn probe_code() -> Vec<u8> {
+    vec![
+        // R0 = scratch cell
+        stack::PUSH_IMM8, 8,
+        heap::HEAP_ALLOC,
+        stack::POP_REG, 0,
+        // R1 = word count, fail on empty input
+        native::INPUT_LEN,
+        stack::PUSH_IMM8, 3,
+        arithmetic::SHR,
+        stack::POP_REG, 1,
+        stack::PUSH_REG, 1,
+        stack::PUSH_IMM8, 0,
+        control::CMP,
+        stack::DROP,
+        stack::DROP,
+        control::JNZ, 2, 0,
+        exec::HALT_ERR, 1,
+        // heap[R0] = input[0] + R1
+        stack::PUSH_REG, 0,
+        native::NATIVE_READ, 0, 0,
+        stack::PUSH_REG, 1,
+        arithmetic::ADD,
+        heap::HEAP_STORE64,
+        stack::PUSH_REG, 0,
+        heap::HEAP_LOAD64,
+        exec::HALT,
+    ]
+}
+
+fn inputs() -> Vec<Vec<u8>> {
+    (0..200u64)
+        .map(|i| match i % 5 {
+            0 => Vec::new(),
+            n => (0..n).flat_map(|w| (i * 1000 + w).to_le_bytes()).collect(),
+        })
+        .collect()
+}
+
+// ============================================================================
+// Equivalence
+// ============================================================================
+
+#[test]
+fn test_batch_matches_independent_runs() {
+    let code = probe_code();
+    let owned = inputs();
+    let refs: Vec<&[u8]> = owned.iter().map(Vec::as_slice).collect();
+
+    let expected: Vec<_> = refs.iter().map(|input| execute(&code, input)).collect();
+    assert_eq!(execute_batch(&code, &refs), expected);
+
+    // Both success and failure paths are exercised
+    assert!(expected.iter().any(Result::is_ok));
+    assert!(expected.contains(&Err(VmError::InvalidOpcode)));
+}
+
+#[test]
+fn test_batch_error_does_not_leak_into_next_input() {
+    let code = probe_code();
+    let word = 5u64.to_le_bytes();
+    let refs: [&[u8]; 3] = [&word, &[], &word];
+    let results = execute_batch(&code, &refs);
+    assert_eq!(results[0], results[2]);
+    assert_eq!(results[1], Err(VmError::InvalidOpcode));
+}
+
+#[test]
+fn test_empty_batch() {
+    assert!(execute_batch(&probe_code(), &[]).is_empty());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_batch_matches_sequential() {
+    let code = probe_code();
+    let owned = inputs();
+    let refs: Vec<&[u8]> = owned.iter().map(Vec::as_slice).collect();
+    assert_eq!(aegis_vm::execute_batch_parallel(&code, &refs), execute_batch(&code, &refs));
+}
+
+// ============================================================================
+// Performance smoke test
+// ============================================================================
+
+#[test]
+fn test_batch_perf_smoke() {
+    let code = probe_code();
+    let owned: Vec<Vec<u8>> = (0..20_000u64).map(|i| i.to_le_bytes().to_vec()).collect();
+    let refs: Vec<&[u8]> = owned.iter().map(Vec::as_slice).collect();
+
+    let start = Instant::now();
+    let looped: Vec<_> = refs.iter().map(|input| execute(&code, input)).collect();
+    let loop_time = start.elapsed();
+
+    let start = Instant::now();
+    let batched = execute_batch(&code, &refs);
+    let batch_time = start.elapsed();
+
+    assert_eq!(batched, looped);
+    println!("{} inputs: loop {:?}, batch {:?}", refs.len(), loop_time, batch_time);
+}
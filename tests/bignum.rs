@@ -0,0 +1,104 @@
+//! Multi-word Arithmetic Tests
+//!
+//! 128-bit addition and subtraction from u64 limbs using ADC/SBB,
+//! checked against native u128 arithmetic.
+
+use aegis_vm::{
+    execute, execute_with_state,
+    build_config::opcodes::{stack, arithmetic, control, exec},
+};
+
+fn push(code: &mut Vec<u8>, value: u64) {
+    code.push(stack::PUSH_IMM);
+    code.extend_from_slice(&value.to_le_bytes());
+}
+
+/// R0 = low limb, R1 = high limb of `a <op> b`
+fn wide_op(a: u128, b: u128, low_op: u8, high_op: u8) -> u128 {
+    let mut code = Vec::new();
+    push(&mut code, a as u64);
+    push(&mut code, b as u64);
+    code.extend_from_slice(&[low_op, stack::POP_REG, 0]);
+    push(&mut code, (a >> 64) as u64);
+    push(&mut code, (b >> 64) as u64);
+    code.extend_from_slice(&[high_op, stack::POP_REG, 1]);
+    code.extend_from_slice(&[stack::PUSH_IMM8, 0, exec::HALT]);
+
+    let state = execute_with_state(&code, &[]).unwrap();
+    (state.regs[1] as u128) << 64 | state.regs[0] as u128
+}
+
+const SAMPLES: &[(u128, u128)] = &[
+    (0, 0),
+    (1, 1),
+    (u64::MAX as u128, 1),
+    (u64::MAX as u128, u64::MAX as u128),
+    (1 << 64, 1),
+    (u128::MAX, 1),
+    (u128::MAX, u128::MAX),
+    (0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210, 0xFFFF_FFFF_FFFF_FFFF_0000_0000_0000_0001),
+    (0x8000_0000_0000_0000_8000_0000_0000_0000, 0x8000_0000_0000_0000_8000_0000_0000_0000),
+];
+
+// ============================================================================
+// 128-bit addition
+// ============================================================================
+
+#[test]
+fn test_add_128() {
+    for &(a, b) in SAMPLES {
+        assert_eq!(wide_op(a, b, arithmetic::ADD, arithmetic::ADC), a.wrapping_add(b), "{a:#x} + {b:#x}");
+        assert_eq!(wide_op(b, a, arithmetic::ADD, arithmetic::ADC), b.wrapping_add(a), "{b:#x} + {a:#x}");
+    }
+}
+
+#[test]
+fn test_adc_chain_from_cleared_carry() {
+    // CMP of equal values clears the carry, so ADC can start the chain
+    let mut code = vec![
+        stack::PUSH_IMM8, 0, stack::PUSH_IMM8, 0, control::CMP, stack::DROP, stack::DROP,
+    ];
+    push(&mut code, u64::MAX);
+    push(&mut code, 1);
+    code.extend_from_slice(&[arithmetic::ADC, stack::DROP]);
+    code.extend_from_slice(&[
+        stack::PUSH_IMM8, 0, stack::PUSH_IMM8, 0, arithmetic::ADC, exec::HALT,
+    ]);
+    // Carry out of the first limb propagates into the second
+    assert_eq!(execute(&code, &[]).unwrap(), 1);
+}
+
+#[test]
+fn test_add_clears_carry() {
+    // A non-overflowing ADD leaves no carry for the following ADC
+    let code = [
+        stack::PUSH_IMM8, 1, stack::PUSH_IMM8, 2, arithmetic::ADD, stack::DROP,
+        stack::PUSH_IMM8, 5, stack::PUSH_IMM8, 5, arithmetic::ADC,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]).unwrap(), 10);
+}
+
+// ============================================================================
+// 128-bit subtraction
+// ============================================================================
+
+#[test]
+fn test_sub_128() {
+    for &(a, b) in SAMPLES {
+        assert_eq!(wide_op(a, b, arithmetic::SUB, arithmetic::SBB), a.wrapping_sub(b), "{a:#x} - {b:#x}");
+        assert_eq!(wide_op(b, a, arithmetic::SUB, arithmetic::SBB), b.wrapping_sub(a), "{b:#x} - {a:#x}");
+    }
+}
+
+#[test]
+fn test_sbb_borrow_through_zero() {
+    // 0 - 0 - borrow wraps and borrows again
+    let code = [
+        stack::PUSH_IMM8, 0, stack::PUSH_IMM8, 1, arithmetic::SUB, stack::DROP,
+        stack::PUSH_IMM8, 0, stack::PUSH_IMM8, 0, arithmetic::SBB, stack::DROP,
+        stack::PUSH_IMM8, 7, stack::PUSH_IMM8, 0, arithmetic::SBB,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]).unwrap(), 6);
+}
@@ -0,0 +1,98 @@
+//! Bitfield Operation Tests
+//!
+//! BFX extracts and BFI inserts `width` bits at `offset`, checked against
+//! hand-computed shift/mask results.
+
+use aegis_vm::{
+    execute, VmError,
+    build_config::opcodes::{stack, arithmetic, exec},
+};
+
+fn push(code: &mut Vec<u8>, value: u64) {
+    code.push(stack::PUSH_IMM);
+    code.extend_from_slice(&value.to_le_bytes());
+}
+
+fn bfx(x: u64, offset: u8, width: u8) -> Result<u64, VmError> {
+    let mut code = Vec::new();
+    push(&mut code, x);
+    code.extend_from_slice(&[arithmetic::BFX, offset, width, exec::HALT]);
+    execute(&code, &[])
+}
+
+fn bfi(x: u64, field: u64, offset: u8, width: u8) -> Result<u64, VmError> {
+    let mut code = Vec::new();
+    push(&mut code, x);
+    push(&mut code, field);
+    code.extend_from_slice(&[arithmetic::BFI, offset, width, exec::HALT]);
+    execute(&code, &[])
+}
+
+const X: u64 = 0xDEAD_BEEF_CAFE_BABE;
+
+// ============================================================================
+// Extract
+// ============================================================================
+
+#[test]
+fn test_bfx_byte_fields() {
+    assert_eq!(bfx(X, 0, 8), Ok(0xBE));
+    assert_eq!(bfx(X, 8, 8), Ok(0xBA));
+    assert_eq!(bfx(X, 56, 8), Ok(0xDE));
+    assert_eq!(bfx(X, 16, 16), Ok(0xCAFE));
+    assert_eq!(bfx(X, 32, 32), Ok(0xDEAD_BEEF));
+}
+
+#[test]
+fn test_bfx_odd_offsets_and_widths() {
+    for &(offset, width) in &[(1u8, 1u8), (3, 5), (7, 13), (13, 29), (31, 33), (60, 4), (63, 1)] {
+        let mask = (1u64 << width) - 1;
+        assert_eq!(bfx(X, offset, width), Ok((X >> offset) & mask), "offset {offset} width {width}");
+    }
+}
+
+#[test]
+fn test_bfx_full_and_empty_width() {
+    assert_eq!(bfx(X, 0, 64), Ok(X));
+    assert_eq!(bfx(X, 12, 0), Ok(0));
+}
+
+// ============================================================================
+// Insert
+// ============================================================================
+
+#[test]
+fn test_bfi_byte_fields() {
+    assert_eq!(bfi(X, 0x11, 0, 8), Ok(0xDEAD_BEEF_CAFE_BA11));
+    assert_eq!(bfi(X, 0x1234, 16, 16), Ok(0xDEAD_BEEF_1234_BABE));
+    assert_eq!(bfi(X, 0, 32, 32), Ok(0x0000_0000_CAFE_BABE));
+}
+
+#[test]
+fn test_bfi_truncates_field() {
+    // Only the low `width` bits of the field are used
+    assert_eq!(bfi(0, 0xFF, 4, 4), Ok(0xF0));
+    assert_eq!(bfi(u64::MAX, 0, 60, 4), Ok(0x0FFF_FFFF_FFFF_FFFF));
+}
+
+#[test]
+fn test_bfi_then_bfx_round_trip() {
+    for &(offset, width) in &[(0u8, 3u8), (5, 11), (17, 7), (40, 24), (0, 64)] {
+        let mask = if width == 64 { u64::MAX } else { (1u64 << width) - 1 };
+        let field = 0x5A5A_5A5A_5A5A_5A5A & mask;
+        let inserted = bfi(X, field, offset, width).unwrap();
+        assert_eq!(inserted & !(mask << offset), X & !(mask << offset));
+        assert_eq!(bfx(inserted, offset, width), Ok(field));
+    }
+}
+
+// ============================================================================
+// Invalid fields
+// ============================================================================
+
+#[test]
+fn test_field_outside_64_bits_rejected() {
+    assert_eq!(bfx(X, 60, 8), Err(VmError::InvalidBytecode));
+    assert_eq!(bfx(X, 64, 0), Err(VmError::InvalidBytecode));
+    assert_eq!(bfi(X, 1, 0, 65), Err(VmError::InvalidBytecode));
+}
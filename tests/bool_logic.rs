@@ -0,0 +1,189 @@
+//! Boolean Logic Tests
+//!
+//! Comparisons produce exactly 0 or 1, and `!` on a bool flips that bit
+//! (`x ^ 1`), not all 64 bits. Functions returning `bool` built from `!`,
+//! `&&`, `||` and comparisons must match native Rust.
+
+use aegis_vm_macro::vm_protect;
+
+// ============================================================================
+// Protected functions
+// ============================================================================
+
+#[vm_protect(level = "debug")]
+fn not_ready(x: u64) -> bool {
+    !(x > 0)
+}
+
+#[vm_protect(level = "debug")]
+fn double_not(x: u64) -> bool {
+    !!(x == 7)
+}
+
+#[vm_protect(level = "debug")]
+fn outside(lo: u64, x: u64, hi: u64) -> bool {
+    !(lo <= x && x <= hi)
+}
+
+#[vm_protect(level = "debug")]
+fn either_not(a: u64, b: u64) -> bool {
+    !(a == 0) || !(b < 10)
+}
+
+#[vm_protect(level = "debug")]
+fn de_morgan(a: u64, b: u64) -> bool {
+    !(a > 5 || b > 5) == (!(a > 5) && !(b > 5))
+}
+
+#[vm_protect(level = "debug")]
+fn xor_bool(a: u64, b: u64) -> bool {
+    (a > 10) != (b > 10)
+}
+
+/// A negated comparison cast to a number: 0 or 1, never !1 or !0
+#[vm_protect(level = "debug")]
+fn not_as_number(x: u64) -> u64 {
+    let small = !(x >= 10);
+    small as u64 + 100
+}
+
+/// A negated comparison used as a condition
+#[vm_protect(level = "debug")]
+fn count_small(a: u64, b: u64, c: u64) -> u64 {
+    let mut count = 0;
+    if !(a >= 10) {
+        count += 1;
+    }
+    if !(b >= 10) {
+        count += 1;
+    }
+    if !(c >= 10) {
+        count += 1;
+    }
+    count
+}
+
+/// Bool locals combined after the fact
+#[vm_protect(level = "debug")]
+fn flags(x: u64, y: u64) -> bool {
+    let big = x > 1000;
+    let even = x % 2 == 0;
+    let same = x == y;
+    (big && !even) || (!big && same) || !(even || same)
+}
+
+#[vm_protect]
+fn outside_standard(lo: u64, x: u64, hi: u64) -> bool {
+    !(lo <= x && x <= hi)
+}
+
+#[vm_protect(level = "paranoid")]
+fn flags_paranoid(x: u64, y: u64) -> bool {
+    let big = x > 1000;
+    let even = x % 2 == 0;
+    let same = x == y;
+    (big && !even) || (!big && same) || !(even || same)
+}
+
+// ============================================================================
+// Native references
+// ============================================================================
+
+fn native_outside(lo: u64, x: u64, hi: u64) -> bool {
+    !(lo <= x && x <= hi)
+}
+
+fn native_flags(x: u64, y: u64) -> bool {
+    let (big, even, same) = (x > 1000, x & 1 == 0, x == y);
+    matches!((big, even, same), (true, false, _) | (false, _, true) | (_, false, false))
+}
+
+const SAMPLES: [u64; 9] = [0, 1, 5, 6, 7, 9, 10, 11, 1001];
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_not_comparison() {
+    assert!(not_ready(0));
+    assert!(!not_ready(1));
+    assert!(!not_ready(u64::MAX >> 1));
+}
+
+#[test]
+fn test_double_not() {
+    for x in SAMPLES {
+        assert_eq!(double_not(x), x == 7, "x = {x}");
+    }
+}
+
+#[test]
+fn test_negated_conjunction() {
+    for lo in [0u64, 5, 10] {
+        for x in SAMPLES {
+            for hi in [0u64, 7, 10] {
+                let expected = native_outside(lo, x, hi);
+                assert_eq!(outside(lo, x, hi), expected, "!({lo} <= {x} <= {hi})");
+                assert_eq!(outside_standard(lo, x, hi), expected, "!({lo} <= {x} <= {hi})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_negated_disjunction_operands() {
+    for a in SAMPLES {
+        for b in SAMPLES {
+            assert_eq!(either_not(a, b), a != 0 || b >= 10, "({a}, {b})");
+        }
+    }
+}
+
+#[test]
+fn test_de_morgan_always_holds() {
+    for a in SAMPLES {
+        for b in SAMPLES {
+            assert!(de_morgan(a, b), "({a}, {b})");
+        }
+    }
+}
+
+#[test]
+fn test_bool_inequality() {
+    for a in SAMPLES {
+        for b in SAMPLES {
+            assert_eq!(xor_bool(a, b), (a > 10) ^ (b > 10), "({a}, {b})");
+        }
+    }
+}
+
+#[test]
+fn test_negated_comparison_is_zero_or_one() {
+    for x in SAMPLES {
+        assert_eq!(not_as_number(x), (x < 10) as u64 + 100, "x = {x}");
+    }
+}
+
+#[test]
+fn test_negated_comparisons_count_as_one() {
+    for a in SAMPLES {
+        for b in SAMPLES {
+            for c in [0u64, 10] {
+                let expected = [a, b, c].iter().filter(|&&v| v < 10).count() as u64;
+                assert_eq!(count_small(a, b, c), expected, "({a}, {b}, {c})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bool_locals() {
+    for x in SAMPLES.into_iter().chain([1000, 1002]) {
+        for y in [0u64, 7, 1001] {
+            let expected = native_flags(x, y);
+            assert_eq!(flags(x, y), expected, "({x}, {y})");
+            assert_eq!(flags_paranoid(x, y), expected, "({x}, {y})");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Region Bounds Error Tests
+//!
+//! Input, output and heap overflows each report their own error so a
+//! marshaling bug points at the region that failed.
+
+use aegis_vm::{
+    execute, VmError, VmState,
+    state::MAX_OUTPUT_SIZE,
+    build_config::opcodes::{stack, register, arithmetic, memory, heap, native, exec},
+};
+
+// ============================================================================
+// Input
+// ============================================================================
+
+#[test]
+fn test_native_read_past_input() {
+    let code = [native::NATIVE_READ, 4, 0, exec::HALT];
+    assert_eq!(execute(&code, &[0; 8]), Err(VmError::InputOutOfBounds));
+    assert!(execute(&code, &[0; 12]).is_ok());
+}
+
+#[test]
+fn test_sized_load_past_input() {
+    let code = [memory::LOAD32, 2, 0, exec::HALT];
+    assert_eq!(execute(&code, &[0; 5]), Err(VmError::InputOutOfBounds));
+}
+
+#[test]
+fn test_load_mem_past_input() {
+    let code = [
+        register::MOV_IMM, 1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        register::LOAD_MEM, 0, 1,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[0; 8]), Err(VmError::InputOutOfBounds));
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+#[test]
+fn test_output_write_past_limit() {
+    let mut state = VmState::new(&[], &[]);
+    assert_eq!(state.write_output_u64(MAX_OUTPUT_SIZE - 4, 1), Err(VmError::OutputOutOfBounds));
+    assert_eq!(state.write_output_u8(usize::MAX, 1), Err(VmError::OutputOutOfBounds));
+    assert!(state.output.is_empty());
+
+    state.write_output_u32(4, 0xAABBCCDD).unwrap();
+    assert_eq!(state.output, [0, 0, 0, 0, 0xDD, 0xCC, 0xBB, 0xAA]);
+}
+
+#[test]
+fn test_output_append_past_limit() {
+    let mut state = VmState::new(&[], &[]);
+    state.output.resize(MAX_OUTPUT_SIZE, 0);
+    assert_eq!(state.append_output(&[1]), Err(VmError::OutputOutOfBounds));
+}
+
+#[test]
+fn test_store_within_output_limit() {
+    // Highest u16 offset is still well inside the limit
+    let code = [
+        stack::PUSH_IMM8, 7,
+        memory::STORE8, 0xFF, 0xFF,
+        stack::PUSH_IMM8, 0,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]), Ok(0));
+}
+
+// ============================================================================
+// Heap
+// ============================================================================
+
+#[test]
+fn test_heap_read_past_allocation() {
+    let code = [
+        stack::PUSH_IMM8, 8,
+        heap::HEAP_ALLOC,
+        stack::PUSH_IMM8, 4,
+        arithmetic::ADD,
+        heap::HEAP_LOAD64,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]), Err(VmError::HeapOutOfBounds));
+}
+
+#[test]
+fn test_regions_report_distinct_errors() {
+    assert_ne!(VmError::InputOutOfBounds.code(), VmError::OutputOutOfBounds.code());
+    assert_ne!(VmError::InputOutOfBounds.code(), VmError::HeapOutOfBounds.code());
+    assert_ne!(VmError::OutputOutOfBounds.code(), VmError::HeapOutOfBounds.code());
+}
@@ -0,0 +1,117 @@
+//! Tests for the BSWAP / BSWAP32 / BSWAP16 opcodes
+//!
+//! BSWAP reverses all 8 bytes like `u64::swap_bytes`; the narrow forms
+//! swap the low 32/16 bits like `u32::swap_bytes` / `u16::swap_bytes` and
+//! clear everything above.
+
+use aegis_vm::{
+    execute, BytecodeBuilder,
+    bytecode::instruction_length,
+    build_config::OPCODE_DECODE,
+    build_config::opcodes::convert,
+};
+
+/// x from the input, then `swap` applied once
+fn swap_code(swap: fn(&mut BytecodeBuilder) -> &mut BytecodeBuilder) -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.native_read(0);
+    swap(&mut asm);
+    asm.halt().build().unwrap()
+}
+
+fn run(code: &[u8], x: u64) -> u64 {
+    execute(code, &x.to_le_bytes()).unwrap()
+}
+
+fn values() -> Vec<u64> {
+    vec![
+        0,
+        1,
+        u64::MAX,
+        0x0102_0304_0506_0708,
+        0xDEAD_BEEF_CAFE_BABE,
+        0x8000_0000_0000_0000,
+        0xFF,
+        0xFF00,
+        0x1234_5678,
+        0x9E37_79B9_7F4A_7C15,
+    ]
+}
+
+/// Byte palindromes: swapping leaves them unchanged
+const PALINDROMES: [u64; 4] = [
+    0x0102_0304_0403_0201,
+    0xAB00_CDEF_EFCD_00AB,
+    0x7E7E_7E7E_7E7E_7E7E,
+    0x8001_0000_0000_0180,
+];
+
+// ============================================================================
+// BSWAP
+// ============================================================================
+
+#[test]
+fn test_bswap_matches_swap_bytes() {
+    let code = swap_code(BytecodeBuilder::bswap);
+    for x in values() {
+        assert_eq!(run(&code, x), x.swap_bytes(), "x = {x:#018x}");
+    }
+}
+
+#[test]
+fn test_bswap_palindromes_unchanged() {
+    let code = swap_code(BytecodeBuilder::bswap);
+    for x in PALINDROMES {
+        assert_eq!(x.swap_bytes(), x);
+        assert_eq!(run(&code, x), x, "x = {x:#018x}");
+    }
+}
+
+#[test]
+fn test_bswap_twice_is_identity() {
+    let code = BytecodeBuilder::new().native_read(0).bswap().bswap().halt().build().unwrap();
+    for x in values() {
+        assert_eq!(run(&code, x), x, "x = {x:#018x}");
+    }
+}
+
+#[test]
+fn test_bswap_is_big_endian_conversion() {
+    let code = swap_code(BytecodeBuilder::bswap);
+    for x in values() {
+        assert_eq!(run(&code, x), u64::from_be_bytes(x.to_le_bytes()), "x = {x:#018x}");
+    }
+}
+
+// ============================================================================
+// Narrow swaps
+// ============================================================================
+
+#[test]
+fn test_bswap32_matches_u32_swap_bytes() {
+    let code = swap_code(BytecodeBuilder::bswap32);
+    for x in values() {
+        assert_eq!(run(&code, x), (x as u32).swap_bytes() as u64, "x = {x:#018x}");
+    }
+    assert_eq!(run(&code, 0x1234_5678_0102_0201), 0x0102_0201);
+}
+
+#[test]
+fn test_bswap16_matches_u16_swap_bytes() {
+    let code = swap_code(BytecodeBuilder::bswap16);
+    for x in values() {
+        assert_eq!(run(&code, x), (x as u16).swap_bytes() as u64, "x = {x:#018x}");
+    }
+    assert_eq!(run(&code, 0xFFFF_FFFF_FFFF_5A5A), 0x5A5A);
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+#[test]
+fn test_bswap_opcodes_are_single_byte() {
+    for op in [convert::BSWAP16, convert::BSWAP32, convert::BSWAP] {
+        assert_eq!(instruction_length(OPCODE_DECODE[op as usize]), 1, "opcode {op:#04x}");
+    }
+}
@@ -97,3 +97,122 @@ fn test_build_seq_exists() {
     // In dev mode it defaults to 0
     assert!(BUILD_SEQ < u32::MAX);
 }
+
+// =============================================================================
+// Shared seed file tests
+// =============================================================================
+
+/// Directory build.rs writes the shared seed/opcode table to
+fn shared_dir() -> std::path::PathBuf {
+    // CARGO_TARGET_TMPDIR is <target>/tmp for every target triple
+    std::path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn read_hex_file(name: &str) -> Vec<u8> {
+    let path = shared_dir().join(name);
+    let hex = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing {}: {}", path.display(), e));
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_shared_seed_matches_runtime() {
+    // vm-macro reads this file; it must be the seed the runtime was built with
+    assert_eq!(read_hex_file(".anticheat_build_seed"), get_build_seed());
+}
+
+#[test]
+fn test_shared_opcode_table_matches_runtime() {
+    use aegis_vm::build_config::OPCODE_ENCODE;
+    assert_eq!(read_hex_file(".anticheat_opcode_table"), OPCODE_ENCODE);
+}
+
+#[test]
+fn test_exported_config_json_matches_runtime() {
+    // Only written when the build ran with ANTICHEAT_EXPORT_CONFIG; CI has a
+    // step that sets it
+    if option_env!("ANTICHEAT_EXPORT_CONFIG").is_none_or(|v| v.is_empty() || v == "0") {
+        return;
+    }
+    use aegis_vm::build_config::{native_ids, OPCODE_DECODE, OPCODE_ENCODE};
+
+    let path = shared_dir().join("aegis_build_config.json");
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing {}: {}", path.display(), e));
+    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+    assert_eq!(json["build_id"], format!("0x{:016x}", BUILD_ID));
+    assert_eq!(json["watermark"], hex::encode(WATERMARK));
+    assert_eq!(json["native_ids"]["CHECK_ROOT"], native_ids::CHECK_ROOT);
+
+    let opcodes = json["opcodes"].as_array().unwrap();
+    assert!(!opcodes.is_empty());
+    for op in opcodes {
+        let base = op["base"].as_u64().unwrap() as usize;
+        let encoded = op["encoded"].as_u64().unwrap() as u8;
+        assert_eq!(OPCODE_ENCODE[base], encoded, "{}", op["name"]);
+        for alias in op["aliases"].as_array().unwrap() {
+            assert_eq!(OPCODE_DECODE[alias.as_u64().unwrap() as usize] as usize, base);
+        }
+    }
+}
+
+// =============================================================================
+// Opcode table consistency tests
+// =============================================================================
+
+#[test]
+fn test_verify_opcode_tables() {
+    assert!(aegis_vm::build_config::verify_opcode_tables());
+}
+
+#[test]
+fn test_aliases_decode_to_base() {
+    use aegis_vm::build_config::{opcode_aliases, OPCODE_DECODE};
+    // stable_opcodes builds have no handler duplication
+    assert_eq!(opcode_aliases::ALL.is_empty(), cfg!(feature = "stable_opcodes"));
+    for &(base, aliases) in opcode_aliases::ALL {
+        for &alias in aliases {
+            assert_eq!(OPCODE_DECODE[alias as usize], base);
+        }
+    }
+}
+
+// =============================================================================
+// Pinned seed file tests
+// =============================================================================
+
+#[test]
+fn test_build_id_derived_from_seed() {
+    // BUILD_ID is a pure function of the seed, so identical seeds
+    // (same ANTICHEAT_SEED_FILE / ANTICHEAT_BUILD_KEY) give identical IDs
+    assert_eq!(BUILD_ID, aegis_vm::crypto::derive_build_id(&get_build_seed()));
+}
+
+#[test]
+fn test_seed_file_is_authoritative() {
+    // Only meaningful when the build was pinned with ANTICHEAT_SEED_FILE
+    let Some(path) = option_env!("ANTICHEAT_SEED_FILE") else { return };
+    let contents = std::fs::read(path).unwrap();
+
+    // Same forms build.rs accepts: 64 hex characters, else 32 raw bytes
+    let text = std::str::from_utf8(&contents).map(str::trim).unwrap_or("");
+    let seed: Vec<u8> = if text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        (0..32)
+            .map(|i| u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).unwrap())
+            .collect()
+    } else {
+        assert_eq!(contents.len(), 32, "seed file is neither hex nor raw");
+        contents
+    };
+
+    assert_eq!(get_build_seed().to_vec(), seed);
+    let seed: [u8; 32] = seed.try_into().unwrap();
+    assert_eq!(BUILD_ID, aegis_vm::crypto::derive_build_id(&seed));
+}
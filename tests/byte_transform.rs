@@ -0,0 +1,164 @@
+//! Byte-vector transform tests
+//!
+//! Bytecode equivalent of `fn transform(data: Vec<u8>) -> Vec<u8>` using a
+//! u8-typed vector (elem_size = 1): per-byte VEC_GET/VEC_SET with a rolling
+//! XOR key, results streamed to the output buffer. Checked against a native
+//! reference and for round-trip. `vm_protect` only takes u64 arguments, so
+//! the protected variant applies the same transform to the bytes of a word.
+
+use aegis_vm::engine::execute_with_state;
+use aegis_vm_macro::vm_protect;
+use aegis_vm::build_config::opcodes::{arithmetic, control, exec, native, register, stack, vector};
+
+/// Native reference: data[i] ^ (key + i)
+fn xor_native(data: &[u8], key: u8) -> Vec<u8> {
+    data.iter()
+        .enumerate()
+        .map(|(i, &b)| b ^ key.wrapping_add(i as u8))
+        .collect()
+}
+
+/// Build bytecode that XOR-transforms `data` in a u8 vector and writes it out
+/// R0 = vec, R1 = i, R2 = len
+fn xor_bytecode(data: &[u8], key: u8) -> Vec<u8> {
+    let len = data.len() as u16;
+    let mut code = vec![];
+    code.push(stack::PUSH_IMM16);
+    code.extend_from_slice(&len.to_le_bytes());
+    code.extend_from_slice(&[
+        stack::PUSH_IMM8, 1,            // elem_size = 1 (u8)
+        vector::VEC_NEW,
+        stack::POP_REG, 0,
+    ]);
+    for &b in data {
+        code.extend_from_slice(&[stack::PUSH_REG, 0, stack::PUSH_IMM8, b, vector::VEC_PUSH]);
+    }
+    code.extend_from_slice(&[
+        stack::PUSH_REG, 0,
+        vector::VEC_LEN,
+        stack::POP_REG, 2,
+        register::MOV_IMM, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]);
+
+    let loop_start = code.len();
+    code.extend_from_slice(&[
+        stack::PUSH_REG, 1,
+        stack::PUSH_REG, 2,
+        control::CMP,
+        stack::DROP,
+        stack::DROP,
+        control::JGE, 0x00, 0x00,       // -> exit (patched)
+    ]);
+    let exit_patch = code.len() - 2;
+    code.extend_from_slice(&[
+        // vec[i] = vec[i] ^ (key + i)
+        stack::PUSH_REG, 0,
+        stack::PUSH_REG, 1,
+        stack::PUSH_REG, 0,
+        stack::PUSH_REG, 1,
+        vector::VEC_GET,
+        stack::PUSH_IMM8, key,
+        stack::PUSH_REG, 1,
+        arithmetic::ADD,
+        arithmetic::XOR,
+        vector::VEC_SET,
+        // output.push(vec[i])
+        stack::PUSH_REG, 0,
+        stack::PUSH_REG, 1,
+        vector::VEC_GET,
+        native::NATIVE_WRITE, 0x00, 0x00,
+        // i += 1
+        stack::PUSH_REG, 1,
+        arithmetic::INC,
+        stack::POP_REG, 1,
+        control::JMP, 0x00, 0x00,       // -> loop_start (patched)
+    ]);
+    let back = loop_start as i32 - code.len() as i32;
+    let n = code.len();
+    code[n - 2..].copy_from_slice(&(back as i16).to_le_bytes());
+
+    let exit = code.len() as i32 - (exit_patch as i32 + 2);
+    code[exit_patch..exit_patch + 2].copy_from_slice(&(exit as i16).to_le_bytes());
+    code.extend_from_slice(&[stack::PUSH_REG, 2, exec::HALT]);
+    code
+}
+
+fn transform(data: &[u8], key: u8) -> Vec<u8> {
+    let code = xor_bytecode(data, key);
+    let state = execute_with_state(&code, &[]).unwrap();
+    assert_eq!(state.result, data.len() as u64);
+    state.take_output()
+}
+
+#[test]
+fn test_xor_matches_native() {
+    let data = b"Attack at dawn!";
+    assert_eq!(transform(data, 0x5A), xor_native(data, 0x5A));
+}
+
+#[test]
+fn test_xor_round_trip() {
+    let data: Vec<u8> = (0..=255u8).collect();
+    let encrypted = transform(&data, 0xC3);
+    assert_ne!(encrypted, data);
+    assert_eq!(transform(&encrypted, 0xC3), data);
+}
+
+#[test]
+fn test_xor_empty() {
+    assert!(transform(&[], 0x11).is_empty());
+}
+
+// =============================================================================
+// Protected word transform
+// =============================================================================
+
+/// XOR each little-endian byte of `word` with `key + i`
+#[vm_protect(level = "debug")]
+fn protected_xor_word(word: u64, key: u64) -> u64 {
+    let mut out = 0;
+    for i in 0..8 {
+        let b = (word >> (i * 8)) & 0xFF;
+        let k = (key + i) as u8 as u64;
+        out = out | ((b ^ k) << (i * 8));
+    }
+    out
+}
+
+#[vm_protect(level = "paranoid")]
+fn protected_xor_word_paranoid(word: u64, key: u64) -> u64 {
+    let mut out = 0;
+    for i in 0..8 {
+        let b = (word >> (i * 8)) & 0xFF;
+        let k = (key + i) as u8 as u64;
+        out = out | ((b ^ k) << (i * 8));
+    }
+    out
+}
+
+fn xor_word_native(word: u64, key: u8) -> u64 {
+    let bytes: [u8; 8] = xor_native(&word.to_le_bytes(), key).try_into().unwrap();
+    u64::from_le_bytes(bytes)
+}
+
+const WORDS: [u64; 4] = [0, 0x0123_4567_89AB_CDEF, u64::from_le_bytes(*b"dawn!..."), u64::MAX];
+
+#[test]
+fn test_protected_xor_matches_native() {
+    for word in WORDS {
+        for key in [0x00u8, 0x5A, 0xFC] {
+            let expected = xor_word_native(word, key);
+            assert_eq!(protected_xor_word(word, key as u64), expected, "{word:#x} ^ {key:#x}");
+            assert_eq!(protected_xor_word_paranoid(word, key as u64), expected, "{word:#x} ^ {key:#x}");
+        }
+    }
+}
+
+#[test]
+fn test_protected_xor_round_trip() {
+    for word in WORDS {
+        let encrypted = protected_xor_word(word, 0xC3);
+        assert_ne!(encrypted, word);
+        assert_eq!(protected_xor_word(encrypted, 0xC3), word);
+    }
+}
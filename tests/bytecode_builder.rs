@@ -0,0 +1,132 @@
+//! Bytecode Builder Tests
+//!
+//! `BytecodeBuilder` must emit exactly the bytes a hand-written program
+//! would, with label jumps resolved to the right relative offsets.
+
+use aegis_vm::{
+    execute, BytecodeBuilder, VmError,
+    build_config::opcodes::{stack, arithmetic, control, register, exec},
+};
+
+// ============================================================================
+// Equivalence with hand-coded bytecode
+// ============================================================================
+
+#[test]
+fn test_forty_plus_two() {
+    let hand = [
+        stack::PUSH_IMM8, 40,
+        stack::PUSH_IMM8, 2,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+    let built = BytecodeBuilder::new()
+        .push_imm8(40)
+        .push_imm8(2)
+        .add()
+        .halt()
+        .build()
+        .unwrap();
+
+    assert_eq!(built, hand);
+    assert_eq!(execute(&built, &[]).unwrap(), 42);
+}
+
+#[test]
+fn test_loop_with_backward_jump() {
+    // Sum 5..=1 = 15 (same program as tests/encrypted_execution.rs)
+    let hand = [
+        register::MOV_IMM, 0, 5, 0, 0, 0, 0, 0, 0, 0,
+        register::MOV_IMM, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+        // loop:
+        stack::PUSH_REG, 0,
+        stack::PUSH_REG, 1,
+        arithmetic::ADD,
+        stack::POP_REG, 1,
+        stack::PUSH_REG, 0,
+        arithmetic::DEC,
+        stack::DUP,
+        stack::POP_REG, 0,
+        stack::PUSH_IMM8, 0,
+        arithmetic::SUB,
+        control::JNZ, (256 - 19) as u8, 0xFF,
+        stack::PUSH_REG, 1,
+        exec::HALT,
+    ];
+    let built = BytecodeBuilder::new()
+        .mov_imm(0, 5)
+        .mov_imm(1, 0)
+        .label("loop")
+        .push_reg(0).push_reg(1).add().pop_reg(1)
+        .push_reg(0).dec().dup().pop_reg(0)
+        .push_imm8(0).sub()
+        .jnz("loop")
+        .push_reg(1)
+        .halt()
+        .build()
+        .unwrap();
+
+    assert_eq!(built, hand);
+    assert_eq!(execute(&built, &[]).unwrap(), 15);
+}
+
+#[test]
+fn test_forward_jump_and_call() {
+    // Skip an error exit, call a doubling subroutine: 21 * 2
+    let built = BytecodeBuilder::new()
+        .jmp("main")
+        .halt_err(1)
+        .label("main")
+        .push_imm8(21)
+        .call("double")
+        .halt()
+        .label("double")
+        .dup().add().ret()
+        .build()
+        .unwrap();
+
+    assert_eq!(&built[..3], [control::JMP, 2, 0]);
+    assert_eq!(execute(&built, &[]).unwrap(), 42);
+}
+
+#[test]
+fn test_push_picks_shortest_encoding() {
+    let mut asm = BytecodeBuilder::new();
+    asm.push(0xFF);
+    assert_eq!(asm.len(), 2);
+    asm.push(0x1_0000);
+    assert_eq!(asm.len(), 2 + 5);
+    asm.push(u64::MAX).add().add().halt();
+    assert_eq!(asm.len(), 2 + 5 + 9 + 3);
+    assert_eq!(
+        execute(&asm.build().unwrap(), &[]).unwrap(),
+        0xFFu64.wrapping_add(0x1_0000).wrapping_add(u64::MAX)
+    );
+}
+
+// ============================================================================
+// Label errors
+// ============================================================================
+
+#[test]
+fn test_undefined_label() {
+    let result = BytecodeBuilder::new().jmp("nowhere").halt().build();
+    assert_eq!(result, Err(VmError::InvalidJumpTarget));
+}
+
+#[test]
+fn test_duplicate_label() {
+    let result = BytecodeBuilder::new().label("a").nop().label("a").halt().build();
+    assert_eq!(result, Err(VmError::InvalidBytecode));
+}
+
+#[test]
+fn test_jump_out_of_i16_range() {
+    let mut asm = BytecodeBuilder::new();
+    asm.jmp("far");
+    for _ in 0..40_000 {
+        asm.nop();
+    }
+    asm.label("far").halt();
+    assert_eq!(asm.build(), Err(VmError::InvalidJumpTarget));
+}
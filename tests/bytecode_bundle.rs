@@ -0,0 +1,128 @@
+//! Bytecode Bundle Tests
+//!
+//! Several routines sealed into one package share a key, nonce and tag;
+//! each named entry must run exactly like its standalone bytecode.
+
+use aegis_vm::{
+    execute, BytecodeBundle, BytecodePackage, VmError,
+    bytecode::BytecodeFlags,
+    crypto::CryptoContext,
+    build_config::opcodes::{stack, arithmetic, control, native, exec},
+};
+
+/// input[0] + input[1]
+fn add_code() -> Vec<u8> {
+    vec![
+        native::NATIVE_READ, 0, 0,
+        native::NATIVE_READ, 8, 0,
+        arithmetic::ADD,
+        exec::HALT,
+    ]
+}
+
+/// max(input[0], input[1]), with a forward branch
+fn max_code() -> Vec<u8> {
+    vec![
+        native::NATIVE_READ, 0, 0,
+        native::NATIVE_READ, 8, 0,
+        control::CMP,
+        control::JGT, 1, 0,
+        stack::SWAP,
+        stack::DROP,
+        exec::HALT,
+    ]
+}
+
+fn input(a: u64, b: u64) -> Vec<u8> {
+    [a, b].iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn core_bundle() -> BytecodeBundle {
+    let mut bundle = BytecodeBundle::new();
+    bundle.add_entry("add", &add_code()).unwrap();
+    bundle.add_entry("max", &max_code()).unwrap();
+    bundle
+}
+
+// ============================================================================
+// Entries
+// ============================================================================
+
+#[test]
+fn test_entries_match_standalone_code() {
+    let bundle = core_bundle();
+    for (a, b) in [(3, 9), (9, 3), (7, 7)] {
+        let data = input(a, b);
+        assert_eq!(bundle.execute_entry("add", &data), execute(&add_code(), &data));
+        assert_eq!(bundle.execute_entry("max", &data), execute(&max_code(), &data));
+    }
+    assert_eq!(bundle.execute_entry("add", &input(40, 2)), Ok(42));
+    assert_eq!(bundle.execute_entry("max", &input(40, 2)), Ok(40));
+}
+
+#[test]
+fn test_entry_table() {
+    let bundle = core_bundle();
+    let names: Vec<&str> = bundle.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["add", "max"]);
+    assert_eq!(bundle.entry("max").unwrap().offset as usize, add_code().len());
+    assert_eq!(bundle.entry_code("max").unwrap(), &max_code()[..]);
+}
+
+#[test]
+fn test_unknown_entry() {
+    assert_eq!(core_bundle().execute_entry("sub", &input(1, 2)), Err(VmError::InvalidBytecode));
+}
+
+#[test]
+fn test_duplicate_entry_rejected() {
+    let mut bundle = core_bundle();
+    assert_eq!(bundle.add_entry("add", &max_code()), Err(VmError::InvalidBytecode));
+    assert_eq!(bundle.entries().len(), 2);
+}
+
+// ============================================================================
+// Packaging
+// ============================================================================
+
+#[test]
+fn test_sealed_bundle_roundtrip() {
+    let mut ctx = CryptoContext::new([0x42u8; 32]);
+    let package = core_bundle().seal(&mut ctx, 1_700_000_000).unwrap();
+    assert!(package.header.is_encrypted());
+
+    // One blob on the wire; no entry names in the clear
+    let bytes = package.to_bytes();
+    assert!(!bytes.windows(3).any(|w| w == b"max"));
+
+    let parsed = BytecodePackage::from_bytes(&bytes).unwrap();
+    let bundle = BytecodeBundle::open(&parsed, &ctx).unwrap();
+    assert_eq!(bundle.execute_entry("add", &input(40, 2)), Ok(42));
+    assert_eq!(bundle.execute_entry("max", &input(5, 11)), Ok(11));
+}
+
+#[test]
+fn test_plaintext_bundle_roundtrip() {
+    let ctx = CryptoContext::new([0x42u8; 32]);
+    let package = core_bundle().to_plaintext_package(ctx.build_id);
+    assert!(!package.header.is_encrypted());
+
+    let bundle = BytecodeBundle::open(&package, &ctx).unwrap();
+    assert_eq!(bundle.execute_entry("add", &input(1, 2)), Ok(3));
+}
+
+#[test]
+fn test_tampered_bundle_rejected() {
+    let mut ctx = CryptoContext::new([0x42u8; 32]);
+    let mut package = core_bundle().seal(&mut ctx, 0).unwrap();
+    package.code[0] ^= 0xFF;
+    assert!(BytecodeBundle::open(&package, &ctx).is_err());
+}
+
+#[test]
+fn test_single_function_package_is_not_a_bundle() {
+    let ctx = CryptoContext::new([0x42u8; 32]);
+    let package = BytecodePackage::new_plaintext(add_code(), ctx.build_id);
+    assert_eq!(package.header.flags & BytecodeFlags::Bundle as u16, 0);
+    assert!(BytecodeBundle::open(&package, &ctx).is_err());
+}
@@ -0,0 +1,324 @@
+//! Bytecode Pass Tests
+//!
+//! Every built-in obfuscation pass must leave results unchanged when the
+//! rewritten bytecode is executed.
+
+use aegis_vm::{
+    execute,
+    bytecode::instruction_extent,
+    opcodes as base,
+    passes::{
+        AliasSubstitutionPass, BytecodePass, JunkNopPass, OpaquePredicatePass, PassPipeline,
+        RegisterPermutationPass,
+    },
+    build_config::{OPCODE_DECODE, opcodes::{stack, arithmetic, control, register, special, exec}},
+};
+
+/// Sum 10..=1 in a loop, then double it in a subroutine: 110
+fn loop_call_code() -> Vec<u8> {
+    let mut code = vec![
+        register::MOV_IMM, 0, 0, 0, 0, 0, 0, 0, 0, 0,   // 0-9:   R0 = 0
+        register::MOV_IMM, 1, 10, 0, 0, 0, 0, 0, 0, 0,  // 10-19: R1 = 10
+        // Loop start (20)
+        stack::PUSH_REG, 0,          // 20
+        stack::PUSH_REG, 1,          // 22
+        arithmetic::ADD,             // 24
+        stack::POP_REG, 0,           // 25
+        stack::PUSH_REG, 1,          // 27
+        arithmetic::DEC,             // 29
+        stack::POP_REG, 1,           // 30
+        stack::PUSH_REG, 1,          // 32
+        stack::PUSH_IMM8, 0,         // 34
+        control::CMP,                // 36
+        stack::DROP,                 // 37
+        stack::DROP,                 // 38
+    ];
+    code.push(control::JNZ);         // 39: back to 20
+    code.extend_from_slice(&(-22i16).to_le_bytes());
+    code.extend_from_slice(&[
+        stack::PUSH_REG, 0,          // 42
+        control::CALL, 1, 0,         // 44: to 48
+        exec::HALT,                  // 47
+        // Subroutine (48): double
+        stack::DUP,
+        arithmetic::ADD,
+        control::RET,
+    ]);
+    code
+}
+
+/// Forward branch over an unreachable HALT_ERR: 7
+fn forward_jump_code() -> Vec<u8> {
+    vec![
+        stack::PUSH_IMM8, 3,
+        stack::PUSH_IMM8, 3,
+        control::CMP,
+        stack::DROP,
+        stack::DROP,
+        control::JZ, 2, 0,
+        exec::HALT_ERR, 1,
+        stack::PUSH_IMM8, 7,
+        exec::HALT,
+    ]
+}
+
+/// Register operands in instruction order, as a disassembler shows them
+fn register_operands(code: &[u8]) -> Vec<u8> {
+    let mut regs = Vec::new();
+    let mut pos = 0;
+    while pos < code.len() {
+        let count = match OPCODE_DECODE[code[pos] as usize] {
+            base::stack::PUSH_REG | base::stack::POP_REG | base::register::MOV_IMM => 1,
+            base::register::MOV_REG | base::register::LOAD_MEM | base::register::STORE_MEM => 2,
+            _ => 0,
+        };
+        regs.extend_from_slice(&code[pos + 1..pos + 1 + count]);
+        pos += instruction_extent(code, pos);
+    }
+    regs
+}
+
+fn assert_preserves<P: BytecodePass>(pass: &P) {
+    for code in [loop_call_code(), forward_jump_code()] {
+        let expected = execute(&code, &[]).unwrap();
+        let transformed = pass.transform(code);
+        assert_eq!(execute(&transformed, &[]).unwrap(), expected);
+    }
+}
+
+// ============================================================================
+// Built-in passes
+// ============================================================================
+
+#[test]
+fn test_programs_baseline() {
+    assert_eq!(execute(&loop_call_code(), &[]).unwrap(), 110);
+    assert_eq!(execute(&forward_jump_code(), &[]).unwrap(), 7);
+}
+
+#[test]
+fn test_junk_nop_pass_preserves_semantics() {
+    for seed in 0..32 {
+        assert_preserves(&JunkNopPass::new(seed));
+        assert_preserves(&JunkNopPass::new(seed).with_density(1));
+    }
+}
+
+#[test]
+fn test_junk_nop_pass_inserts_padding() {
+    let code = loop_call_code();
+    let transformed = JunkNopPass::new(1).with_density(1).transform(code.clone());
+    assert!(transformed.len() > code.len());
+}
+
+#[test]
+fn test_alias_substitution_preserves_semantics() {
+    for seed in 0..32 {
+        assert_preserves(&AliasSubstitutionPass::new(seed));
+    }
+}
+
+#[test]
+fn test_alias_substitution_keeps_length_and_meaning() {
+    let code = loop_call_code();
+    let transformed = AliasSubstitutionPass::new(7).transform(code.clone());
+    assert_eq!(transformed.len(), code.len());
+    for (a, b) in code.iter().zip(&transformed) {
+        assert_eq!(OPCODE_DECODE[*a as usize], OPCODE_DECODE[*b as usize]);
+    }
+}
+
+#[test]
+fn test_opaque_predicate_pass_preserves_semantics() {
+    for seed in 0..32 {
+        assert_preserves(&OpaquePredicatePass::new(seed));
+    }
+}
+
+#[test]
+fn test_opaque_predicate_pass_inserts_before_cmp() {
+    let code = forward_jump_code();
+    let transformed = OpaquePredicatePass::new(3).transform(code.clone());
+    assert!(transformed.len() > code.len());
+}
+
+#[test]
+fn test_register_permutation_preserves_semantics() {
+    for seed in 0..32 {
+        assert_preserves(&RegisterPermutationPass::new(seed));
+    }
+}
+
+#[test]
+fn test_register_permutation_renames_every_operand() {
+    let code = loop_call_code();
+    for seed in 0..8 {
+        let pass = RegisterPermutationPass::new(seed);
+        let map = pass.map();
+        let mut sorted = map;
+        sorted.sort_unstable();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+        let transformed = pass.transform(code.clone());
+        assert_eq!(transformed.len(), code.len());
+        let expected: Vec<u8> = register_operands(&code).iter().map(|&r| map[r as usize]).collect();
+        assert_eq!(register_operands(&transformed), expected, "seed {seed}");
+    }
+}
+
+#[test]
+fn test_register_permutation_leaves_high_registers() {
+    let code = vec![
+        register::MOV_IMM, 9, 5, 0, 0, 0, 0, 0, 0, 0,
+        stack::PUSH_REG, 9,
+        exec::HALT,
+    ];
+    assert_eq!(RegisterPermutationPass::new(1).transform(code.clone()), code);
+}
+
+#[test]
+fn test_same_body_functions_get_different_registers() {
+    let code = loop_call_code();
+    let names = ["check_a", "check_b", "check_c", "check_d", "check_e", "check_f", "check_g", "check_h"];
+    let variants: Vec<Vec<u8>> = names
+        .iter()
+        .map(|name| RegisterPermutationPass::for_function(name).transform(code.clone()))
+        .collect();
+
+    for (name, variant) in names.iter().zip(&variants) {
+        assert_eq!(execute(variant, &[]).unwrap(), 110, "{name}");
+        assert_eq!(&RegisterPermutationPass::for_function(name).transform(code.clone()), variant);
+    }
+    let mut layouts: Vec<Vec<u8>> = variants.iter().map(|v| register_operands(v)).collect();
+    layouts.dedup();
+    assert!(layouts.len() > 1, "every name picked the same registers");
+}
+
+// ============================================================================
+// Pipeline
+// ============================================================================
+
+#[test]
+fn test_paranoid_pipeline_preserves_semantics() {
+    for seed in 0..32 {
+        let pipeline = PassPipeline::paranoid(seed);
+        assert_eq!(pipeline.len(), 3);
+        assert_preserves(&pipeline);
+    }
+}
+
+#[test]
+fn test_paranoid_function_pipeline_permutes_per_name() {
+    let code = loop_call_code();
+    assert_ne!(
+        RegisterPermutationPass::for_function("check_a").map(),
+        RegisterPermutationPass::for_function("check_b").map()
+    );
+
+    let a = PassPipeline::paranoid_function("check_a", 5);
+    let b = PassPipeline::paranoid_function("check_b", 5);
+    assert_eq!(a.len(), PassPipeline::paranoid(5).len() + 1);
+
+    let (a, b) = (a.run(code.clone()), b.run(code.clone()));
+    assert_ne!(a, b);
+    assert_eq!(execute(&a, &[]).unwrap(), 110);
+    assert_eq!(execute(&b, &[]).unwrap(), 110);
+}
+
+#[test]
+fn test_custom_closure_pass() {
+    // Prepend a NOP
+    let pipeline = PassPipeline::new()
+        .with_pass(|code: Vec<u8>| {
+            let mut out = vec![special::NOP];
+            out.extend(code);
+            out
+        })
+        .with_pass(JunkNopPass::new(5));
+
+    let code = forward_jump_code();
+    let transformed = pipeline.run(code.clone());
+    assert_eq!(transformed[0], special::NOP);
+    assert_eq!(execute(&transformed, &[]).unwrap(), 7);
+}
+
+#[test]
+fn test_empty_pipeline_is_identity() {
+    let pipeline = PassPipeline::new();
+    assert!(pipeline.is_empty());
+    assert_eq!(pipeline.run(loop_call_code()), loop_call_code());
+}
+
+// ============================================================================
+// Seeding
+// ============================================================================
+
+/// Which predicate (OPAQUE_TRUE / OPAQUE_FALSE) guards each CMP
+fn predicate_choices(code: &[u8]) -> Vec<u8> {
+    code.iter()
+        .filter(|&&b| b == special::OPAQUE_TRUE || b == special::OPAQUE_FALSE)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_obfuscation_seed_reproducible() {
+    let code = loop_call_code();
+
+    aegis_vm::set_obfuscation_seed(0xA3615);
+    let first = OpaquePredicatePass::default().transform(code.clone());
+    aegis_vm::set_obfuscation_seed(0xA3615);
+    let second = OpaquePredicatePass::default().transform(code.clone());
+
+    assert_eq!(first, second);
+    assert_eq!(predicate_choices(&first), predicate_choices(&second));
+    assert_eq!(execute(&first, &[]).unwrap(), 110);
+}
+
+#[test]
+fn test_obfuscation_seed_drives_default_passes() {
+    // A pinned seed gives the same output for every Default pass
+    let code = loop_call_code();
+    let run = || {
+        aegis_vm::set_obfuscation_seed(7);
+        PassPipeline::new()
+            .with_pass(OpaquePredicatePass::default())
+            .with_pass(JunkNopPass::default())
+            .with_pass(AliasSubstitutionPass::default())
+            .run(code.clone())
+    };
+    let transformed = run();
+    assert_eq!(transformed, run());
+    assert_eq!(execute(&transformed, &[]).unwrap(), 110);
+}
+
+#[test]
+fn test_obfuscation_seed_leaves_global_rng_alone() {
+    fastrand::seed(0x5EED);
+    let expected = fastrand::u64(..);
+
+    fastrand::seed(0x5EED);
+    aegis_vm::set_obfuscation_seed(7);
+    let _ = OpaquePredicatePass::default();
+    assert_eq!(fastrand::u64(..), expected);
+}
+
+// ============================================================================
+// Fallbacks
+// ============================================================================
+
+#[test]
+fn test_hash_check_code_left_untouched() {
+    let code = vec![
+        special::HASH_CHECK, 0, 0, 0, 0,
+        stack::PUSH_IMM8, 1,
+        exec::HALT,
+    ];
+    assert_eq!(PassPipeline::paranoid(1).run(code.clone()), code);
+}
+
+#[test]
+fn test_truncated_code_left_untouched() {
+    let code = vec![stack::PUSH_IMM8, 1, stack::PUSH_IMM];
+    assert_eq!(JunkNopPass::new(1).with_density(1).transform(code.clone()), code);
+}
@@ -0,0 +1,82 @@
+//! Captured Execution Tests
+//!
+//! `execute_capturing` returns the final output, heap, registers, flags and
+//! instruction count alongside the result, as an owned `VmOutcome`.
+
+use aegis_vm::{
+    execute, execute_capturing, BytecodeBuilder, VmError, VmOutcome,
+    opcodes::flags,
+};
+
+/// Write "OK" to the output, keep 0x2A in a heap cell and R3, then compare
+/// equal values and halt with 7
+fn reporting_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .push(b'O' as u64).native_write(0)
+        .push(b'K' as u64).native_write(0)
+        .push_imm8(8).heap_alloc().pop_reg(0)
+        .push_reg(0).push_imm8(0x2A).heap_store64()
+        .push_reg(0).heap_load64().pop_reg(3)
+        .push_imm8(5).push_imm8(5).cmp().drop().drop()
+        .push_imm8(7)
+        .halt()
+        .build()
+        .unwrap()
+}
+
+fn capture(code: &[u8]) -> VmOutcome {
+    execute_capturing(code, &[]).unwrap()
+}
+
+// ============================================================================
+// Captured fields
+// ============================================================================
+
+#[test]
+fn test_output_is_captured() {
+    let outcome = capture(&reporting_code());
+    assert_eq!(outcome.result, 7);
+    assert_eq!(outcome.output, b"OK");
+}
+
+#[test]
+fn test_heap_and_registers_are_captured() {
+    let outcome = capture(&reporting_code());
+    assert_eq!(outcome.regs[3], 0x2A);
+    let addr = outcome.regs[0] as usize;
+    assert_eq!(outcome.heap[addr..addr + 8], 0x2Au64.to_le_bytes());
+}
+
+#[test]
+fn test_flags_and_instruction_count_are_captured() {
+    let code = reporting_code();
+    let outcome = capture(&code);
+    assert_ne!(outcome.flags & flags::ZERO, 0, "last CMP compared equal values");
+    // 20 instructions, HALT included
+    assert_eq!(outcome.instruction_count, 20);
+}
+
+// ============================================================================
+// Consistency with execute
+// ============================================================================
+
+#[test]
+fn test_result_matches_execute() {
+    let code = reporting_code();
+    assert_eq!(execute_capturing(&code, &[]).map(|o| o.result), execute(&code, &[]));
+}
+
+#[test]
+fn test_outcome_outlives_bytecode() {
+    let outcome = {
+        let code = reporting_code();
+        capture(&code)
+    };
+    assert_eq!(outcome.output, b"OK");
+}
+
+#[test]
+fn test_error_is_propagated() {
+    let code = BytecodeBuilder::new().halt_err(1).build().unwrap();
+    assert_eq!(execute_capturing(&code, &[]), Err(VmError::InvalidOpcode));
+}
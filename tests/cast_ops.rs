@@ -0,0 +1,218 @@
+//! Cast lowering tests
+//!
+//! Raw bytecode for the sequences `vm_protect` emits for `as` casts and
+//! bool-to-int conversion, checked against native Rust semantics, plus the
+//! same casts written in `#[vm_protect]` functions.
+
+use aegis_vm::engine::execute;
+use aegis_vm_macro::vm_protect;
+use aegis_vm::build_config::opcodes::{arithmetic, control, convert, exec, stack};
+
+/// PUSH_IMM <value> followed by `ops` and HALT
+fn run_ops(value: u64, ops: &[u8]) -> u64 {
+    let mut code = vec![stack::PUSH_IMM];
+    code.extend_from_slice(&value.to_le_bytes());
+    code.extend_from_slice(ops);
+    code.push(exec::HALT);
+    execute(&code, &[]).unwrap()
+}
+
+// =============================================================================
+// Truncation / zero-extension (uN as uM)
+// =============================================================================
+
+#[test]
+fn test_u64_as_u8_as_u64() {
+    for v in [0u64, 0x7F, 0xFF, 0x100, 0x1234_5678_9ABC_DEF0, u64::MAX] {
+        assert_eq!(run_ops(v, &[convert::TRUNC8]), v as u8 as u64);
+    }
+}
+
+#[test]
+fn test_u64_as_u16_as_u64() {
+    for v in [0u64, 0xFFFF, 0x1_0000, 0xDEAD_BEEF, u64::MAX] {
+        assert_eq!(run_ops(v, &[convert::TRUNC16]), v as u16 as u64);
+    }
+}
+
+#[test]
+fn test_u64_as_u32_as_u64() {
+    for v in [0u64, 0xFFFF_FFFF, 0x1_0000_0000, u64::MAX] {
+        assert_eq!(run_ops(v, &[convert::TRUNC32]), v as u32 as u64);
+    }
+}
+
+#[test]
+fn test_chained_narrowing() {
+    // x as u32 as u8 as u64 == x as u8 as u64
+    let v = 0xAABB_CCDD_EEFF_1122u64;
+    assert_eq!(
+        run_ops(v, &[convert::TRUNC32, convert::TRUNC8]),
+        v as u32 as u8 as u64
+    );
+}
+
+// =============================================================================
+// Sign extension (iN as i64)
+// =============================================================================
+
+#[test]
+fn test_i8_as_i64() {
+    for v in [0i8, 1, 127, -1, -128] {
+        let got = run_ops(v as u8 as u64, &[convert::SEXT8]);
+        assert_eq!(got as i64, v as i64);
+    }
+}
+
+#[test]
+fn test_i16_as_i64() {
+    for v in [0i16, 32767, -1, -32768] {
+        let got = run_ops(v as u16 as u64, &[convert::SEXT16]);
+        assert_eq!(got as i64, v as i64);
+    }
+}
+
+#[test]
+fn test_i32_as_i64() {
+    for v in [0i32, i32::MAX, -1, i32::MIN] {
+        let got = run_ops(v as u32 as u64, &[convert::SEXT32]);
+        assert_eq!(got as i64, v as i64);
+    }
+}
+
+#[test]
+fn test_u64_as_i8_as_i64() {
+    // Truncate then sign-extend: 0x1FF as i8 == -1
+    let got = run_ops(0x1FF, &[convert::TRUNC8, convert::SEXT8]);
+    assert_eq!(got as i64, 0x1FFu64 as i8 as i64);
+}
+
+#[test]
+fn test_i64_as_u8_drops_sign() {
+    // -1i64 as u8 as u64 == 255
+    assert_eq!(run_ops(-1i64 as u64, &[convert::TRUNC8]), 255);
+}
+
+// =============================================================================
+// Bool normalization ((a > b) as u64)
+// =============================================================================
+
+/// (a > b) as u64 + 1, lowered as CMP + JGT materializing 0/1
+fn gt_as_u64_plus_one(a: u8, b: u8) -> u64 {
+    let code = [
+        stack::PUSH_IMM8, a,
+        stack::PUSH_IMM8, b,
+        control::CMP,
+        stack::DROP,
+        stack::DROP,
+        control::JGT, 0x05, 0x00,   // -> true branch
+        stack::PUSH_IMM8, 0,
+        control::JMP, 0x02, 0x00,   // -> join
+        stack::PUSH_IMM8, 1,
+        // join
+        stack::PUSH_IMM8, 1,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+    execute(&code, &[]).unwrap()
+}
+
+#[test]
+fn test_bool_as_u64() {
+    assert_eq!(gt_as_u64_plus_one(5, 3), (5 > 3) as u64 + 1);
+    assert_eq!(gt_as_u64_plus_one(3, 5), (3 > 5) as u64 + 1);
+    assert_eq!(gt_as_u64_plus_one(4, 4), (4 > 4) as u64 + 1);
+}
+
+// =============================================================================
+// Casts inside #[vm_protect]
+// =============================================================================
+
+#[vm_protect(level = "debug")]
+fn protected_narrow8(x: u64) -> u64 {
+    x as u8 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_narrow16(x: u64) -> u64 {
+    x as u16 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_narrow32(x: u64) -> u64 {
+    x as u32 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_chained(x: u64) -> u64 {
+    x as u32 as u8 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_sext8(x: u64) -> u64 {
+    x as i8 as i64 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_sext16(x: u64) -> u64 {
+    x as i16 as i64 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_sext32(x: u64) -> u64 {
+    x as i32 as i64 as u64
+}
+
+#[vm_protect(level = "debug")]
+fn protected_gt_plus_one(a: u64, b: u64) -> u64 {
+    (a > b) as u64 + 1
+}
+
+#[vm_protect]
+fn protected_sext8_standard(x: u64) -> u64 {
+    x as i8 as i64 as u64
+}
+
+#[vm_protect(level = "paranoid")]
+fn protected_sext8_paranoid(x: u64) -> u64 {
+    x as i8 as i64 as u64
+}
+
+const CAST_SAMPLES: [u64; 10] = [
+    0, 1, 0x7F, 0x80, 0xFF, 0x1FF, 0x8000, 0xFFFF_FFFF, 0x8000_0000_0000_0000, u64::MAX,
+];
+
+#[test]
+fn test_protected_narrowing() {
+    for x in CAST_SAMPLES {
+        assert_eq!(protected_narrow8(x), x as u8 as u64, "x = {x:#x}");
+        assert_eq!(protected_narrow16(x), x as u16 as u64, "x = {x:#x}");
+        assert_eq!(protected_narrow32(x), x as u32 as u64, "x = {x:#x}");
+        assert_eq!(protected_chained(x), x as u32 as u8 as u64, "x = {x:#x}");
+    }
+}
+
+#[test]
+fn test_protected_sign_extension() {
+    for x in CAST_SAMPLES {
+        assert_eq!(protected_sext8(x), x as i8 as i64 as u64, "x = {x:#x}");
+        assert_eq!(protected_sext16(x), x as i16 as i64 as u64, "x = {x:#x}");
+        assert_eq!(protected_sext32(x), x as i32 as i64 as u64, "x = {x:#x}");
+    }
+}
+
+#[test]
+fn test_protected_bool_as_u64() {
+    for (a, b) in [(5, 3), (3, 5), (4, 4)] {
+        assert_eq!(protected_gt_plus_one(a, b), (a > b) as u64 + 1, "{a} > {b}");
+    }
+}
+
+#[test]
+fn test_protected_casts_agree_across_levels() {
+    for x in CAST_SAMPLES {
+        let expected = x as i8 as i64 as u64;
+        assert_eq!(protected_sext8_standard(x), expected, "x = {x:#x}");
+        assert_eq!(protected_sext8_paranoid(x), expected, "x = {x:#x}");
+    }
+}
@@ -0,0 +1,200 @@
+//! Command-Line Tool Tests
+//!
+//! Drives the `aegis` binary end to end: assemble a sample program, run it,
+//! disassemble it back to source that reassembles to the same bytes, and
+//! validate good and broken bytecode.
+
+#![cfg(feature = "vm_debug")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use aegis_vm::{bytecode::BytecodePackage, compress::decompress, BytecodeBuilder};
+
+/// sum = 0; for i in input..0 { sum += i }; output sum byte
+const SUM_ASM: &str = "
+    ; sum of 1..=input[0]
+    NATIVE_READ 0
+    POP_REG 0
+    PUSH_IMM8 0             ; accumulator
+loop:
+    PUSH_REG 0
+    ADD
+    PUSH_REG 0
+    DEC
+    POP_REG 0
+    PUSH_REG 0, ; trailing separators are fine
+    PUSH_IMM8 0x0
+    CMP
+    DROP
+    DROP
+    JNZ loop
+    DUP
+    STORE8 0
+    HALT
+";
+
+struct Workdir(PathBuf);
+
+impl Workdir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("aegis-cli-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Workdir(dir)
+    }
+
+    fn file(&self, name: &str, contents: &[u8]) -> String {
+        let path = self.0.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn aegis(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aegis")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn assemble(dir: &Workdir, name: &str, source: &str) -> String {
+    let source = dir.file(&format!("{name}.asm"), source.as_bytes());
+    let out = dir.path(&format!("{name}.bc"));
+    let result = aegis(&["asm", &source, "-o", &out]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    out
+}
+
+// ============================================================================
+// asm / run
+// ============================================================================
+
+#[test]
+fn test_asm_then_run() {
+    let dir = Workdir::new("run");
+    let bc = assemble(&dir, "sum", SUM_ASM);
+
+    let out = aegis(&["run", &bc, "--u64", "10"]);
+    assert!(out.status.success());
+    let text = stdout(&out);
+    assert!(text.contains("result: 55 (0x37)"), "{text}");
+    assert!(text.contains("output: 37"), "{text}");
+
+    // Same input as raw hex
+    let out = aegis(&["run", &bc, "--input", "0400000000000000"]);
+    assert!(stdout(&out).contains("result: 10 "));
+}
+
+#[test]
+fn test_asm_matches_builder() {
+    let dir = Workdir::new("builder");
+    let bc = assemble(&dir, "mix", "MOV_IMM 1, 0x1122334455667788\nPUSH_IMM16 -2\nMOV_REG 2, 1\nstart: JMP start\n");
+    let expected = BytecodeBuilder::new()
+        .mov_imm(1, 0x1122_3344_5566_7788)
+        .push_imm16(0xFFFE)
+        .mov_reg(2, 1)
+        .label("start")
+        .jmp("start")
+        .build()
+        .unwrap();
+    assert_eq!(std::fs::read(&bc).unwrap(), expected);
+}
+
+#[test]
+fn test_asm_reports_bad_source() {
+    let dir = Workdir::new("bad-asm");
+    let source = dir.file("bad.asm", b"PUSH_IMM8 1\nFROB 2\n");
+    let out = aegis(&["asm", &source]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("line 2: unknown instruction `FROB`"));
+
+    let source = dir.file("label.asm", b"JMP nowhere\n");
+    assert!(!aegis(&["asm", &source]).status.success());
+}
+
+#[test]
+fn test_run_failure_exits_nonzero() {
+    let dir = Workdir::new("run-fail");
+    let bc = assemble(&dir, "underflow", "ADD\nHALT\n");
+    let out = aegis(&["run", &bc]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("VM_ERR_STACK_UNDERFLOW"));
+}
+
+// ============================================================================
+// disasm
+// ============================================================================
+
+#[test]
+fn test_disasm_roundtrips_through_asm() {
+    let dir = Workdir::new("disasm");
+    let bc = assemble(&dir, "sum", SUM_ASM);
+
+    let out = aegis(&["disasm", &bc]);
+    assert!(out.status.success());
+    let listing = stdout(&out);
+    assert!(listing.contains("NATIVE_READ"), "{listing}");
+    assert!(listing.contains("JNZ"), "{listing}");
+    assert!(listing.lines().any(|l| l.starts_with("L_")), "{listing}");
+
+    let again = assemble(&dir, "again", &listing);
+    assert_eq!(std::fs::read(&again).unwrap(), std::fs::read(&bc).unwrap());
+}
+
+#[test]
+fn test_disasm_reads_compressed_package() {
+    let dir = Workdir::new("package");
+    let code = BytecodeBuilder::new().push_imm8(7).push_imm8(6).mul().halt().build().unwrap();
+    let package = BytecodePackage::new_compressed(&code, 1);
+    assert_eq!(decompress(&package.code).unwrap(), code);
+    let path = dir.file("pkg.bc", &package.to_bytes());
+
+    assert!(stdout(&aegis(&["disasm", &path])).contains("MUL"));
+    assert!(stdout(&aegis(&["run", &path])).contains("result: 42 "));
+}
+
+// ============================================================================
+// validate
+// ============================================================================
+
+#[test]
+fn test_validate_accepts_good_code() {
+    let dir = Workdir::new("valid");
+    let bc = assemble(&dir, "sum", SUM_ASM);
+    let out = aegis(&["validate", &bc]);
+    assert!(out.status.success());
+    assert!(stdout(&out).starts_with("ok: "));
+}
+
+#[test]
+fn test_validate_rejects_truncated_code() {
+    let dir = Workdir::new("truncated");
+    let mut code = BytecodeBuilder::new().push_imm(1).halt().build().unwrap();
+    code.truncate(4);
+    let path = dir.file("cut.bc", &code);
+
+    let out = aegis(&["validate", &path]);
+    assert!(!out.status.success());
+    assert!(stdout(&out).contains("PUSH_IMM runs past the end"));
+}
+
+#[test]
+fn test_validate_rejects_jump_into_instruction() {
+    let dir = Workdir::new("mid-jump");
+    // JMP +1 lands inside the 9-byte PUSH_IMM
+    let bc = assemble(&dir, "mid", "JMP 1\nPUSH_IMM 0\nHALT\n");
+    let out = aegis(&["validate", &bc]);
+    assert!(!out.status.success());
+    assert!(stdout(&out).contains("JMP target 4 is not an instruction start"));
+}
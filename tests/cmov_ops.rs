@@ -0,0 +1,110 @@
+//! Conditional Move Tests
+//!
+//! CMOV pops (cond, a, b) and pushes `a` when cond is nonzero, `b`
+//! otherwise. A select built on it must contain no branch instructions.
+
+use aegis_vm::{
+    execute, BytecodeBuilder, VmError,
+    bytecode::instruction_length,
+    opcodes as base,
+    build_config::{OPCODE_DECODE, opcodes::{stack, arithmetic, control, exec}},
+};
+
+/// input[0] != 0 ? input[1] : input[2]
+fn select_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0)
+        .native_read(8)
+        .native_read(16)
+        .cmov()
+        .halt()
+        .build()
+        .unwrap()
+}
+
+fn input(cond: u64, a: u64, b: u64) -> Vec<u8> {
+    [cond, a, b].iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Decode bytecode into base opcodes, one per instruction
+fn disassemble(code: &[u8]) -> Vec<u8> {
+    let mut ops = Vec::new();
+    let mut ip = 0;
+    while ip < code.len() {
+        let op = OPCODE_DECODE[code[ip] as usize];
+        let len = instruction_length(op);
+        assert_ne!(len, 0, "undecodable byte {:#04x} at {}", code[ip], ip);
+        ops.push(op);
+        ip += len;
+    }
+    ops
+}
+
+// ============================================================================
+// Selection semantics
+// ============================================================================
+
+#[test]
+fn test_nonzero_cond_selects_a() {
+    let code = select_code();
+    for cond in [1, 2, 0x8000_0000_0000_0000, u64::MAX] {
+        assert_eq!(execute(&code, &input(cond, 11, 22)), Ok(11), "cond = {cond:#x}");
+    }
+}
+
+#[test]
+fn test_zero_cond_selects_b() {
+    assert_eq!(execute(&select_code(), &input(0, 11, 22)), Ok(22));
+}
+
+#[test]
+fn test_extreme_operands_pass_through() {
+    let code = select_code();
+    assert_eq!(execute(&code, &input(1, u64::MAX, 0)), Ok(u64::MAX));
+    assert_eq!(execute(&code, &input(0, u64::MAX, 0)), Ok(0));
+    assert_eq!(execute(&code, &input(0, 0, u64::MAX)), Ok(u64::MAX));
+}
+
+#[test]
+fn test_consumes_three_operands() {
+    // The value beneath the operands is untouched: 100 + (0 ? 1 : 2)
+    let code = [
+        stack::PUSH_IMM8, 100,
+        stack::PUSH_IMM8, 0,
+        stack::PUSH_IMM8, 1,
+        stack::PUSH_IMM8, 2,
+        control::CMOV,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]), Ok(102));
+}
+
+#[test]
+fn test_stack_underflow() {
+    let code = [stack::PUSH_IMM8, 1, stack::PUSH_IMM8, 2, control::CMOV, exec::HALT];
+    assert_eq!(execute(&code, &[]), Err(VmError::StackUnderflow));
+}
+
+// ============================================================================
+// Branch-free encoding
+// ============================================================================
+
+#[test]
+fn test_select_has_no_jumps() {
+    let ops = disassemble(&select_code());
+    assert_eq!(ops, [
+        base::native::NATIVE_READ,
+        base::native::NATIVE_READ,
+        base::native::NATIVE_READ,
+        base::control::CMOV,
+        base::exec::HALT,
+    ]);
+
+    let branches = [
+        base::control::JMP, base::control::JZ, base::control::JNZ,
+        base::control::JGT, base::control::JLT, base::control::JGE,
+        base::control::JLE, base::control::CALL,
+    ];
+    assert!(ops.iter().all(|op| !branches.contains(op)));
+}
@@ -0,0 +1,143 @@
+//! Bytecode Compression Tests
+//!
+//! Compressed bytecode must decompress to the exact original and execute
+//! identically, plaintext or sealed, and repetitive routines must shrink.
+
+use aegis_vm::{
+    execute, BytecodeBuilder, VmError,
+    bytecode::{BytecodePackage, BytecodeFlags},
+    compress::{compress, decompress},
+    crypto::CryptoContext,
+    passes::PassPipeline,
+};
+
+/// Unrolled `acc = acc * 31 + input[i % 4]`, 64 times: large and repetitive
+fn repetitive_code() -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.push_imm8(0);
+    for i in 0..64 {
+        asm.push_imm8(31).mul().native_read((i % 4) * 8).add();
+    }
+    asm.halt().build().unwrap()
+}
+
+fn input() -> Vec<u8> {
+    [3u64, 1, 4, 1].iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn roundtrip(data: &[u8]) {
+    let packed = compress(data);
+    assert_eq!(decompress(&packed).as_deref(), Ok(data), "len {}", data.len());
+}
+
+// ============================================================================
+// Round trips
+// ============================================================================
+
+#[test]
+fn test_roundtrip_edge_inputs() {
+    roundtrip(&[]);
+    roundtrip(&[7]);
+    roundtrip(&[1, 2]);
+    roundtrip(&[0; 1000]);
+    roundtrip(&(0..=255).collect::<Vec<u8>>());
+    roundtrip(&(0..1000).map(|i| (i % 3) as u8).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_roundtrip_pseudo_random() {
+    let mut x = 0x2545_F491_4F6C_DD1Du64;
+    let data: Vec<u8> = (0..70_000)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect();
+    roundtrip(&data);
+    // Incompressible input only pays the header and literal tokens
+    assert!(compress(&data).len() <= 4 + data.len() + data.len() / 128 + 1);
+}
+
+#[test]
+fn test_roundtrip_obfuscated_bytecode() {
+    for seed in 0..8 {
+        roundtrip(&PassPipeline::paranoid(seed).run(repetitive_code()));
+    }
+}
+
+// ============================================================================
+// Size and execution
+// ============================================================================
+
+#[test]
+fn test_repetitive_code_shrinks() {
+    let code = repetitive_code();
+    let packed = compress(&code);
+    assert!(
+        packed.len() * 3 < code.len(),
+        "{} bytes compressed to {}",
+        code.len(),
+        packed.len()
+    );
+}
+
+#[test]
+fn test_decompressed_code_executes_identically() {
+    let code = repetitive_code();
+    let restored = decompress(&compress(&code)).unwrap();
+    assert_eq!(execute(&restored, &input()), execute(&code, &input()));
+}
+
+#[test]
+fn test_plaintext_compressed_package() {
+    let code = repetitive_code();
+    let package = BytecodePackage::new_compressed(&code, 1);
+    assert!(package.header.is_compressed());
+    assert!(!package.header.is_encrypted());
+
+    let parsed = BytecodePackage::from_bytes(&package.to_bytes()).unwrap();
+    assert!(parsed.header.is_compressed());
+    assert_eq!(decompress(&parsed.code).unwrap(), code);
+}
+
+#[test]
+fn test_sealed_compressed_package() {
+    let code = repetitive_code();
+    let mut ctx = CryptoContext::new([0x42; 32]);
+    let package = BytecodePackage::seal_compressed(&code, &mut ctx, 1234).unwrap();
+
+    let flags = package.header.flags;
+    assert_ne!(flags & BytecodeFlags::Encrypted as u16, 0);
+    assert_ne!(flags & BytecodeFlags::Compressed as u16, 0);
+    assert!(package.code.len() < code.len());
+
+    let parsed = BytecodePackage::from_bytes(&package.to_bytes()).unwrap();
+    let restored = parsed.unpack(&ctx).unwrap();
+    assert_eq!(execute(&restored, &input()), execute(&code, &input()));
+}
+
+// ============================================================================
+// Malformed input
+// ============================================================================
+
+#[test]
+fn test_malformed_streams_rejected() {
+    let good = compress(&repetitive_code());
+
+    // Missing header, truncated body
+    assert_eq!(decompress(&[1, 0]), Err(VmError::InvalidBytecode));
+    assert_eq!(decompress(&good[..good.len() - 1]), Err(VmError::InvalidBytecode));
+
+    // Wrong declared length
+    let mut wrong_len = good.clone();
+    wrong_len[0] ^= 1;
+    assert_eq!(decompress(&wrong_len), Err(VmError::InvalidBytecode));
+
+    // Reference before the start of the output
+    assert_eq!(decompress(&[3, 0, 0, 0, 0x80, 1, 0]), Err(VmError::InvalidBytecode));
+
+    // Declared length the body could never produce
+    assert_eq!(decompress(&[0xFF, 0xFF, 0xFF, 0xFF, 0x00, 7]), Err(VmError::InvalidBytecode));
+}
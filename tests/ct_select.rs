@@ -0,0 +1,63 @@
+//! Constant-Time Select Tests
+//!
+//! `aegis_vm::select` must pick `a` for every nonzero cond and `b` for zero,
+//! and agree with the CMOV opcode, which selects with the same mask.
+
+use aegis_vm::{execute, select, BytecodeBuilder};
+
+/// Zero, every single-bit value, and a spread of multi-bit values
+fn cond_values() -> Vec<u64> {
+    let mut values = vec![0, u64::MAX, 0x8000_0000_0000_0001, 0x5555_5555_5555_5555];
+    values.extend((0..64).map(|bit| 1u64 << bit));
+    values.extend((0..64).map(|bit| u64::MAX >> bit));
+    let mut x = 0x9E37_79B9_7F4A_7C15u64;
+    for _ in 0..256 {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        values.push(x);
+    }
+    values
+}
+
+// ============================================================================
+// Selection
+// ============================================================================
+
+#[test]
+fn test_select_all_cond_values() {
+    for cond in cond_values() {
+        let expected = if cond != 0 { 0xAAAA } else { 0xBBBB };
+        assert_eq!(select(cond, 0xAAAA, 0xBBBB), expected, "cond = {cond:#x}");
+    }
+}
+
+#[test]
+fn test_select_passes_operands_through_unchanged() {
+    for v in [0, 1, u64::MAX, 0x0123_4567_89AB_CDEF] {
+        assert_eq!(select(1, v, !v), v);
+        assert_eq!(select(0, !v, v), v);
+    }
+}
+
+// ============================================================================
+// Agreement with CMOV
+// ============================================================================
+
+#[test]
+fn test_select_matches_cmov() {
+    let code = BytecodeBuilder::new()
+        .native_read(0)
+        .native_read(8)
+        .native_read(16)
+        .cmov()
+        .halt()
+        .build()
+        .unwrap();
+
+    for cond in cond_values() {
+        let (a, b) = (cond.rotate_left(7), !cond);
+        let input: Vec<u8> = [cond, a, b].iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(execute(&code, &input), Ok(select(cond, a, b)), "cond = {cond:#x}");
+    }
+}
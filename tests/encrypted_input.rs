@@ -0,0 +1,93 @@
+//! Encrypted Input Tests
+//!
+//! The input buffer stays whitebox-encrypted in memory and is decrypted per
+//! read; results must match running the same code over the plaintext.
+
+#![cfg(feature = "whitebox")]
+
+use aegis_vm::{
+    execute, execute_with_encrypted_input,
+    build_config::whitebox_config::get_nonce_domain_hash,
+    build_config::opcodes::{stack, arithmetic, exec, native},
+    whitebox::{cached_tables, whitebox_encrypt, WhiteboxCryptoContext, AES_BLOCK_SIZE},
+};
+
+/// Sum the u64 values at the given input offsets
+fn sum_code(offsets: &[u16]) -> Vec<u8> {
+    let mut code = vec![stack::PUSH_IMM8, 0];
+    for &offset in offsets {
+        code.push(native::NATIVE_READ);
+        code.extend_from_slice(&offset.to_le_bytes());
+        code.push(arithmetic::ADD);
+    }
+    code.push(exec::HALT);
+    code
+}
+
+fn plaintext() -> Vec<u8> {
+    [11u64, 22_000, 0xDEAD_BEEF, u64::MAX / 3]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+}
+
+/// CTR encryption written out with the raw whitebox primitive
+fn encrypt_with_whitebox(input: &[u8]) -> Vec<u8> {
+    let nonce_hash = get_nonce_domain_hash();
+    input
+        .chunks(AES_BLOCK_SIZE)
+        .enumerate()
+        .flat_map(|(i, chunk)| {
+            let mut block = [0u8; AES_BLOCK_SIZE];
+            block[0..8].copy_from_slice(&(i as u64).to_le_bytes());
+            block[8..16].copy_from_slice(&nonce_hash[8..16]);
+            whitebox_encrypt(&mut block, cached_tables());
+            chunk.iter().zip(block).map(|(b, k)| b ^ k).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sum_over_encrypted_input() {
+    let ctx = WhiteboxCryptoContext::new();
+    let plain = plaintext();
+    let encrypted = encrypt_with_whitebox(&plain);
+    assert_ne!(encrypted, plain);
+
+    let code = sum_code(&[0, 8, 16, 24]);
+    let expected = execute(&code, &plain).unwrap();
+    assert_eq!(execute_with_encrypted_input(&code, &encrypted, &ctx).unwrap(), expected);
+    assert_eq!(
+        expected,
+        11u64.wrapping_add(22_000).wrapping_add(0xDEAD_BEEF).wrapping_add(u64::MAX / 3)
+    );
+}
+
+#[test]
+fn test_encrypt_input_matches_whitebox_ctr() {
+    let ctx = WhiteboxCryptoContext::new();
+    let plain = plaintext();
+    assert_eq!(ctx.encrypt_input(&plain), encrypt_with_whitebox(&plain));
+}
+
+#[test]
+fn test_unaligned_reads_across_blocks() {
+    let ctx = WhiteboxCryptoContext::new();
+    let plain = plaintext();
+    let encrypted = ctx.encrypt_input(&plain);
+
+    // Offsets 5 and 13 straddle the first AES block boundary
+    let code = sum_code(&[5, 13, 20]);
+    assert_eq!(
+        execute_with_encrypted_input(&code, &encrypted, &ctx).unwrap(),
+        execute(&code, &plain).unwrap()
+    );
+}
+
+#[test]
+fn test_encrypted_input_bounds_checked() {
+    let ctx = WhiteboxCryptoContext::new();
+    let encrypted = ctx.encrypt_input(&plaintext());
+    let code = sum_code(&[28]);
+    assert!(execute_with_encrypted_input(&code, &encrypted, &ctx).is_err());
+}
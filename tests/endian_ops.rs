@@ -0,0 +1,85 @@
+//! Endianness Tests
+//!
+//! HEAP_LOAD*/HEAP_STORE* are little-endian, the *_BE variants big-endian.
+//! Storing in one order and loading in the other must byte-swap.
+
+use aegis_vm::{
+    execute,
+    build_config::opcodes::{stack, arithmetic, exec, heap},
+};
+
+/// Allocate 16 bytes (R0 = address), store `value` with `store`, load with `load`
+fn store_then_load(value: u64, store: u8, load: u8) -> u64 {
+    let mut code = vec![
+        stack::PUSH_IMM8, 16,
+        heap::HEAP_ALLOC,
+        stack::POP_REG, 0,
+        stack::PUSH_REG, 0,
+        stack::PUSH_IMM,
+    ];
+    code.extend_from_slice(&value.to_le_bytes());
+    code.extend_from_slice(&[
+        store,
+        stack::PUSH_REG, 0,
+        load,
+        exec::HALT,
+    ]);
+    execute(&code, &[]).unwrap()
+}
+
+const VALUE: u64 = 0x0102_0304_0506_0708;
+
+// ============================================================================
+// Round trips
+// ============================================================================
+
+#[test]
+fn test_little_endian_round_trip() {
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE16, heap::HEAP_LOAD16), VALUE as u16 as u64);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE32, heap::HEAP_LOAD32), VALUE as u32 as u64);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE64, heap::HEAP_LOAD64), VALUE);
+}
+
+#[test]
+fn test_big_endian_round_trip() {
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE16_BE, heap::HEAP_LOAD16_BE), VALUE as u16 as u64);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE32_BE, heap::HEAP_LOAD32_BE), VALUE as u32 as u64);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE64_BE, heap::HEAP_LOAD64_BE), VALUE);
+}
+
+// ============================================================================
+// Byte swapping
+// ============================================================================
+
+#[test]
+fn test_store_le_load_be_swaps() {
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE16, heap::HEAP_LOAD16_BE), 0x0807);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE32, heap::HEAP_LOAD32_BE), 0x0807_0605);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE64, heap::HEAP_LOAD64_BE), VALUE.swap_bytes());
+}
+
+#[test]
+fn test_store_be_load_le_swaps() {
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE64_BE, heap::HEAP_LOAD64), VALUE.swap_bytes());
+}
+
+#[test]
+fn test_big_endian_byte_order_in_memory() {
+    // Most significant byte lands at the lowest address
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE64_BE, heap::HEAP_LOAD8), 0x01);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE32_BE, heap::HEAP_LOAD8), 0x05);
+    assert_eq!(store_then_load(VALUE, heap::HEAP_STORE16_BE, heap::HEAP_LOAD8), 0x07);
+}
+
+#[test]
+fn test_big_endian_bounds_checked() {
+    let code = [
+        stack::PUSH_IMM8, 8,
+        heap::HEAP_ALLOC,
+        stack::PUSH_IMM8, 4,
+        arithmetic::ADD,
+        heap::HEAP_LOAD64_BE,
+        exec::HALT,
+    ];
+    assert!(execute(&code, &[]).is_err());
+}
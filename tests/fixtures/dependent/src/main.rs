@@ -0,0 +1,41 @@
+//! Dependent crate check
+//!
+//! Runs `vm_protect` functions from a crate that pulls aegis_vm in as a
+//! dependency, so the macro and the runtime have to agree on the shared
+//! seed and opcode table. Exits non-zero on any mismatch.
+
+use aegis_vm::vm_protect;
+
+#[vm_protect(level = "debug")]
+fn add_mul(a: u64, b: u64) -> u64 {
+    (a + b) * 3
+}
+
+#[vm_protect(level = "standard")]
+fn sum_to(n: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 1;
+    while i <= n {
+        sum += i;
+        i += 1;
+    }
+    sum
+}
+
+#[vm_protect(level = "paranoid")]
+fn classify(x: u64) -> u64 {
+    match x {
+        0 => 10,
+        1 => 20,
+        _ => x ^ 0xFF,
+    }
+}
+
+fn main() {
+    assert_eq!(add_mul(4, 5), 27);
+    assert_eq!(sum_to(100), 5050);
+    assert_eq!(classify(0), 10);
+    assert_eq!(classify(1), 20);
+    assert_eq!(classify(0x0F), 0xF0);
+    println!("aegis_dependent: ok");
+}
@@ -1087,3 +1087,114 @@ mod integration {
         assert_eq!(execute(&code, &[]), Ok(42));
     }
 }
+
+// =============================================================================
+// SECTION 11: Growing Store Tests (HEAP_STORE*_GROW)
+// =============================================================================
+
+mod grow_store {
+    use super::*;
+    use aegis_vm::engine::run;
+    use aegis_vm::state::VmState;
+
+    #[test]
+    fn test_store8_grow_empty_heap() {
+        // Plain HEAP_STORE8 at 100 fails, the GROW variant extends the heap
+        let code = [
+            stack::PUSH_IMM8, 100,
+            stack::PUSH_IMM8, 42,
+            heap::HEAP_STORE8_GROW,
+            stack::PUSH_IMM8, 100,
+            heap::HEAP_LOAD8,
+            exec::HALT,
+        ];
+        assert_eq!(execute(&code, &[]), Ok(42));
+    }
+
+    #[test]
+    fn test_store_all_sizes_grow() {
+        let code = [
+            stack::PUSH_IMM8, 0,
+            stack::PUSH_IMM8, 0x11,
+            heap::HEAP_STORE8_GROW,
+            stack::PUSH_IMM8, 8,
+            stack::PUSH_IMM16, 0x22, 0x22,
+            heap::HEAP_STORE16_GROW,
+            stack::PUSH_IMM8, 16,
+            stack::PUSH_IMM32, 0x33, 0x33, 0x33, 0x33,
+            heap::HEAP_STORE32_GROW,
+            stack::PUSH_IMM8, 24,
+            stack::PUSH_IMM, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+            heap::HEAP_STORE64_GROW,
+
+            stack::PUSH_IMM8, 0,
+            heap::HEAP_LOAD8,
+            stack::PUSH_IMM8, 8,
+            heap::HEAP_LOAD16,
+            arithmetic::ADD,
+            stack::PUSH_IMM8, 16,
+            heap::HEAP_LOAD32,
+            arithmetic::ADD,
+            stack::PUSH_IMM8, 24,
+            heap::HEAP_LOAD64,
+            arithmetic::ADD,
+            exec::HALT,
+        ];
+        let expected = 0x11u64 + 0x2222 + 0x3333_3333 + 0x4444_4444_4444_4444;
+        assert_eq!(execute(&code, &[]), Ok(expected));
+    }
+
+    #[test]
+    fn test_grow_reserves_region_from_allocator() {
+        // Allocation after a growing store must not overlap the scratch region
+        let code = [
+            stack::PUSH_IMM8, 64,
+            stack::PUSH_IMM8, 1,
+            heap::HEAP_STORE64_GROW,
+            stack::PUSH_IMM8, 8,
+            heap::HEAP_ALLOC,
+            exec::HALT,
+        ];
+        assert_eq!(execute(&code, &[]), Ok(72 + 8));
+    }
+
+    #[test]
+    fn test_grow_within_limit() {
+        let code = [
+            stack::PUSH_IMM8, 120,
+            stack::PUSH_IMM8, 7,
+            heap::HEAP_STORE64_GROW,   // bytes 120..128, exactly at limit
+            stack::PUSH_IMM8, 120,
+            heap::HEAP_LOAD64,
+            exec::HALT,
+        ];
+        let mut state = VmState::with_heap_limit(&code, &[], 128);
+        assert_eq!(run(&mut state), Ok(()));
+        assert_eq!(state.result, 7);
+        assert_eq!(state.heap.len(), 128);
+    }
+
+    #[test]
+    fn test_grow_past_limit() {
+        let code = [
+            stack::PUSH_IMM8, 121,
+            stack::PUSH_IMM8, 7,
+            heap::HEAP_STORE64_GROW,   // bytes 121..129, one past limit
+            exec::HALT,
+        ];
+        let mut state = VmState::with_heap_limit(&code, &[], 128);
+        assert_eq!(run(&mut state), Err(VmError::HeapOutOfBounds));
+        assert!(state.heap.is_empty());
+    }
+
+    #[test]
+    fn test_grow_address_overflow() {
+        let code = [
+            stack::PUSH_IMM, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            stack::PUSH_IMM8, 1,
+            heap::HEAP_STORE16_GROW,
+            exec::HALT,
+        ];
+        assert_eq!(execute(&code, &[]), Err(VmError::HeapOutOfBounds));
+    }
+}
@@ -0,0 +1,83 @@
+//! Heap Preallocation Tests
+//!
+//! `alloc_stats().heap_growths` counts how often the heap buffer had to
+//! reallocate. With enough bytes reserved through `with_prealloc_heap`, a
+//! heavy-alloc routine must run without a single growth.
+
+use aegis_vm::{run, BytecodeBuilder, VmState};
+
+/// 64 blocks of 256 bytes (264 with header), kept alive, each tagged with
+/// its index; returns the sum of the tags
+fn heavy_alloc_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .mov_imm(1, 64)
+        .push_imm8(0)
+        .label("loop")
+        .push_imm16(256).heap_alloc().pop_reg(0)
+        .push_reg(0).push_reg(1).heap_store64()
+        .push_reg(0).heap_load64().add()
+        .push_reg(1).dec().pop_reg(1)
+        .push_reg(1).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .halt()
+        .build()
+        .unwrap()
+}
+
+const HEAVY_BYTES: usize = 64 * 264;
+const HEAVY_RESULT: u64 = 64 * 65 / 2;
+
+// ============================================================================
+// Growth counting
+// ============================================================================
+
+#[test]
+fn test_default_heap_grows() {
+    let code = heavy_alloc_code();
+    let mut state = VmState::new(&code, &[]);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, HEAVY_RESULT);
+    assert!(state.alloc_stats().heap_growths > 0);
+}
+
+#[test]
+fn test_prealloc_heap_never_grows() {
+    let code = heavy_alloc_code();
+    let mut state = VmState::with_prealloc_heap(&code, &[], HEAVY_BYTES);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, HEAVY_RESULT);
+    assert_eq!(state.alloc_stats().heap_growths, 0);
+    assert_eq!(state.alloc_stats().total_allocs, 64);
+}
+
+#[test]
+fn test_short_prealloc_still_counts_growth() {
+    let code = heavy_alloc_code();
+    let mut state = VmState::with_prealloc_heap(&code, &[], HEAVY_BYTES / 2);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, HEAVY_RESULT);
+    assert!(state.alloc_stats().heap_growths > 0);
+}
+
+// ============================================================================
+// Reuse
+// ============================================================================
+
+#[test]
+fn test_reset_keeps_grown_capacity() {
+    let code = heavy_alloc_code();
+    let mut state = VmState::new(&code, &[]);
+    run(&mut state).unwrap();
+
+    state.reset();
+    assert_eq!(state.alloc_stats().heap_growths, 0);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, HEAVY_RESULT);
+    assert_eq!(state.alloc_stats().heap_growths, 0);
+}
+
+#[test]
+fn test_prealloc_is_capped_at_heap_limit() {
+    let state = VmState::with_prealloc_heap(&[], &[], usize::MAX / 2);
+    assert!(state.heap.capacity() <= aegis_vm::state::DEFAULT_HEAP_SIZE);
+}
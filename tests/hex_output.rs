@@ -0,0 +1,122 @@
+//! Hex Output Tests
+//!
+//! `native::to_hex` and the `hex_char` / `hex_word` natives must produce the
+//! same 16-char lowercase text as `format!("{:016x}")`, whether protected
+//! code builds it in a STR or stores it big-endian into the heap.
+
+use aegis_vm::{
+    execute_capturing, natives, run_with_natives, run_with_native_table, BytecodeBuilder,
+    NativeRegistry, VmState,
+    native::{hex_char, hex_word, to_hex, NativeRegistryBuilder},
+};
+
+const HEX_CHAR: u8 = 0;
+const HEX_WORD: u8 = 1;
+
+fn values() -> Vec<u64> {
+    let mut values = vec![0, 1, 0xF, 0x10, u64::MAX, 0x0123_4567_89AB_CDEF, 0xDEAD_BEEF];
+    values.extend((0..64).map(|bit| 1u64 << bit));
+    values
+}
+
+/// STR_NEW(16), push hex_char(input[0], i) for each digit, then write the
+/// STR to the output one byte at a time
+fn str_hex_code() -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.push_imm8(16).str_new().pop_reg(0);
+    for i in 0..16 {
+        asm.push_reg(0)
+            .native_read(0).push_imm8(i).native_call(HEX_CHAR, 2)
+            .str_push();
+    }
+    for i in 0..16 {
+        asm.push_reg(0).push_imm8(i).str_get().native_write(0);
+    }
+    asm.push_reg(0).str_len().halt();
+    asm.build().unwrap()
+}
+
+/// Store hex_word(input[0], 0) and hex_word(input[0], 1) big-endian into a
+/// 16-byte heap block, returning its address
+fn heap_hex_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .push_imm8(16).heap_alloc().pop_reg(0)
+        .push_reg(0)
+        .native_read(0).push_imm8(0).native_call(HEX_WORD, 2)
+        .heap_store64_be()
+        .push_reg(0).push_imm8(8).add()
+        .native_read(0).push_imm8(1).native_call(HEX_WORD, 2)
+        .heap_store64_be()
+        .push_reg(0)
+        .halt()
+        .build()
+        .unwrap()
+}
+
+fn hex_registry() -> NativeRegistry {
+    NativeRegistryBuilder::new().with_hex(HEX_CHAR, HEX_WORD).build()
+}
+
+// ============================================================================
+// Host helpers
+// ============================================================================
+
+#[test]
+fn test_to_hex_matches_format() {
+    for v in values() {
+        assert_eq!(to_hex(v), format!("{v:016x}").as_bytes(), "value {v:#x}");
+    }
+}
+
+#[test]
+fn test_hex_char_digits() {
+    let v = 0x0123_4567_89AB_CDEF;
+    let digits: Vec<u8> = (0..16).map(|i| hex_char(&[v, i]) as u8).collect();
+    assert_eq!(digits, b"0123456789abcdef");
+    assert_eq!(hex_char(&[v, 16]), 0);
+    assert_eq!(hex_char(&[v, u64::MAX]), 0);
+}
+
+#[test]
+fn test_hex_word_is_big_endian() {
+    let v = 0x0123_4567_89AB_CDEF;
+    assert_eq!(hex_word(&[v, 0]).to_be_bytes(), *b"01234567");
+    assert_eq!(hex_word(&[v, 1]).to_be_bytes(), *b"89abcdef");
+    assert_eq!(hex_word(&[v, 2]), 0);
+}
+
+// ============================================================================
+// Protected code
+// ============================================================================
+
+#[test]
+fn test_str_hex_to_output() {
+    let code = str_hex_code();
+    let registry = hex_registry();
+    for v in values() {
+        let input = v.to_le_bytes();
+        let mut state = VmState::new(&code, &input);
+        run_with_natives(&mut state, &registry).unwrap();
+        assert_eq!(state.result, 16);
+        assert_eq!(state.output, format!("{v:016x}").as_bytes(), "value {v:#x}");
+    }
+}
+
+#[test]
+fn test_heap_hex_with_native_table() {
+    let code = heap_hex_code();
+    let table = natives![hex_char, hex_word];
+    for v in values() {
+        let input = v.to_le_bytes();
+        let mut state = VmState::new(&code, &input);
+        state.set_native_table(&table);
+        run_with_native_table(&mut state).unwrap();
+        let addr = state.result as usize;
+        assert_eq!(&state.heap[addr..addr + 16], format!("{v:016x}").as_bytes());
+    }
+}
+
+#[test]
+fn test_unregistered_hex_natives_fail() {
+    assert!(execute_capturing(&heap_hex_code(), &0u64.to_le_bytes()).is_err());
+}
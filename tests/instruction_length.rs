@@ -0,0 +1,73 @@
+//! Instruction Length Tests
+//!
+//! `bytecode::instruction_length` is the single width table shared by the
+//! SMC engine and the bytecode passes. Every base opcode must have a length,
+//! and the lengths must match the operand formats the handlers read.
+//! The table is keyed by base (unshuffled) opcodes.
+
+use aegis_vm::{
+    execute,
+    bytecode::instruction_length,
+    opcodes as base,
+    smc::{SmcConfig, execute_smc, encrypt_bytecode},
+    build_config::{BASE_OPCODES, opcodes::{stack, memory, control, exec}},
+};
+
+// ============================================================================
+// Table coverage
+// ============================================================================
+
+#[test]
+fn test_every_base_opcode_has_length() {
+    for &base in BASE_OPCODES.iter() {
+        assert_ne!(instruction_length(base), 0, "no length for base opcode {base:#04x}");
+    }
+}
+
+#[test]
+fn test_non_opcodes_have_no_length() {
+    for byte in 0..=255u8 {
+        if !BASE_OPCODES.contains(&byte) {
+            assert_eq!(instruction_length(byte), 0, "{byte:#04x} is not a base opcode");
+        }
+    }
+}
+
+#[test]
+fn test_lengths_match_operand_formats() {
+    assert_eq!(instruction_length(base::stack::DUP), 1);
+    assert_eq!(instruction_length(base::exec::HALT), 1);
+    assert_eq!(instruction_length(base::stack::PUSH_IMM8), 2);
+    assert_eq!(instruction_length(base::exec::HALT_ERR), 2);
+    assert_eq!(instruction_length(base::special::NOP_N), 2);
+    assert_eq!(instruction_length(base::control::CALL), 3);
+    assert_eq!(instruction_length(base::register::LOAD_MEM), 3);
+    assert_eq!(instruction_length(base::memory::LOAD64), 3);
+    assert_eq!(instruction_length(base::native::NATIVE_CALL), 3);
+    assert_eq!(instruction_length(base::special::HASH_CHECK), 5);
+    assert_eq!(instruction_length(base::stack::PUSH_IMM32), 5);
+    assert_eq!(instruction_length(base::stack::PUSH_IMM), 9);
+    assert_eq!(instruction_length(base::register::MOV_IMM), 10);
+}
+
+// ============================================================================
+// Consumers
+// ============================================================================
+
+#[test]
+fn test_smc_decrypts_memory_operands() {
+    // LOAD64's u16 offset must be decrypted along with the opcode
+    let mut code = vec![
+        memory::LOAD64, 8, 0,
+        memory::LOAD64, 0, 0,
+        control::CMP,
+        stack::DROP,
+        exec::HALT,
+    ];
+    let input: Vec<u8> = [3u64, 0x1234_5678].iter().flat_map(|v| v.to_le_bytes()).collect();
+    assert_eq!(execute(&code, &input), Ok(0x1234_5678));
+
+    let config = SmcConfig::from_build_seed(2366);
+    encrypt_bytecode(&mut code, &config);
+    assert_eq!(execute_smc(code, &input, &config), Ok(0x1234_5678));
+}
@@ -0,0 +1,114 @@
+//! Jump Guard Tests
+//!
+//! With the jump guard on, the engines record the extent of every
+//! instruction they execute. A jump that lands inside one of them, or onto
+//! an instruction whose operands cover one, fails with `InvalidJumpTarget`
+//! instead of decoding operand bytes as opcodes. The guard is opt-in for
+//! the run loops and always on for SMC.
+
+use aegis_vm::{
+    execute, run_with_natives, BytecodeBuilder, NativeRegistry, VmError, VmResult, VmState,
+    passes::PassPipeline,
+    smc::{SmcConfig, execute_smc, encrypt_bytecode},
+    build_config::opcodes::{stack, control, exec},
+};
+
+/// PUSH_IMM whose payload hides `PUSH_IMM8 42; HALT`, then a jump back
+/// into the payload
+fn hidden_payload_code() -> Vec<u8> {
+    vec![
+        stack::PUSH_IMM, stack::PUSH_IMM8, 42, exec::HALT, 0, 0, 0, 0, 0, // 0-8
+        stack::DROP,                                                      // 9
+        control::JMP, 0xF4, 0xFF,                                         // 10: -> 1
+        exec::HALT,                                                       // 13
+    ]
+}
+
+/// Run with the jump guard enabled
+fn guarded(code: &[u8], input: &[u8], registry: &NativeRegistry) -> VmResult<u64> {
+    let mut state = VmState::new(code, input);
+    state.set_jump_guard(true);
+    run_with_natives(&mut state, registry)?;
+    Ok(state.result)
+}
+
+/// sum(1..=input[0]) in a loop
+fn loop_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0).pop_reg(0)
+        .push_imm8(0)
+        .label("loop")
+        .push_reg(0).add()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .halt()
+        .build()
+        .unwrap()
+}
+
+// ============================================================================
+// Rejected targets
+// ============================================================================
+
+#[test]
+fn test_jump_into_push_imm_operand() {
+    assert_eq!(
+        guarded(&hidden_payload_code(), &[], &NativeRegistry::new()),
+        Err(VmError::InvalidJumpTarget)
+    );
+}
+
+#[test]
+fn test_instruction_covering_executed_start() {
+    // JMP to 5 runs the JMP there first; jumping back to 3 then decodes a
+    // PUSH_IMM16 whose operand covers it
+    let code = [
+        control::JMP, 2, 0,          // 0: -> 5
+        stack::PUSH_IMM16, 0,        // 3: operand runs into 5
+        control::JMP, 0xFB, 0xFF,    // 5: -> 3
+        exec::HALT,                  // 8
+    ];
+    assert_eq!(guarded(&code, &[], &NativeRegistry::new()), Err(VmError::InvalidJumpTarget));
+}
+
+#[test]
+fn test_smc_jump_into_operand() {
+    let config = SmcConfig::from_build_seed(0x4A4D50);
+    let mut code = hidden_payload_code();
+    encrypt_bytecode(&mut code, &config);
+    assert_eq!(execute_smc(code, &[], &config), Err(VmError::InvalidJumpTarget));
+}
+
+// ============================================================================
+// Valid programs are unaffected
+// ============================================================================
+
+#[test]
+fn test_guard_is_off_by_default() {
+    // Unguarded runs decode the hidden payload as written
+    assert_eq!(execute(&hidden_payload_code(), &[]), Ok(42));
+}
+
+#[test]
+fn test_loops_revisit_boundaries() {
+    let registry = NativeRegistry::new();
+    assert_eq!(guarded(&loop_code(), &100u64.to_le_bytes(), &registry), Ok(5050));
+}
+
+#[test]
+fn test_smc_loop() {
+    let config = SmcConfig::from_build_seed(0x4A4D50);
+    let mut code = loop_code();
+    encrypt_bytecode(&mut code, &config);
+    assert_eq!(execute_smc(code, &10u64.to_le_bytes(), &config), Ok(55));
+}
+
+#[test]
+fn test_skipped_junk_is_never_checked() {
+    // Opaque predicates jump over junk bytes that never execute
+    for seed in 0..16 {
+        let code = PassPipeline::paranoid(seed).run(loop_code());
+        assert_eq!(guarded(&code, &20u64.to_le_bytes(), &NativeRegistry::new()), Ok(210), "seed {seed}");
+    }
+}
@@ -0,0 +1,94 @@
+//! Tests for the MULH / IMULH opcodes
+//!
+//! MULH and IMULH push the high 64 bits of the full 128-bit product, exactly
+//! like `((a as u128 * b as u128) >> 64) as u64` and its `i128` counterpart.
+
+use aegis_vm::{execute, BytecodeBuilder};
+
+/// a, b from the input; push MULH (signed = false) or IMULH (signed = true)
+fn mulh_code(signed: bool) -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.native_read(0).native_read(8);
+    if signed {
+        asm.imulh();
+    } else {
+        asm.mulh();
+    }
+    asm.halt().build().unwrap()
+}
+
+fn input(a: u64, b: u64) -> Vec<u8> {
+    [a, b].iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn boundary_values() -> Vec<u64> {
+    let signed = [0i64, 1, -1, 2, -2, i64::MAX, i64::MIN, i64::MIN + 1, i32::MAX as i64, i32::MIN as i64];
+    let mut values: Vec<u64> = signed.iter().map(|&v| v as u64).collect();
+    values.extend([u64::MAX, u64::MAX - 1, 1 << 32, (1 << 32) - 1, 1 << 63, 0x9E37_79B9_7F4A_7C15]);
+    values
+}
+
+// ============================================================================
+// Reference comparison
+// ============================================================================
+
+#[test]
+fn test_mulh_matches_u128() {
+    let code = mulh_code(false);
+    for a in boundary_values() {
+        for b in boundary_values() {
+            let expected = ((a as u128 * b as u128) >> 64) as u64;
+            assert_eq!(execute(&code, &input(a, b)), Ok(expected), "{a:#x} * {b:#x}");
+        }
+    }
+}
+
+#[test]
+fn test_imulh_matches_i128() {
+    let code = mulh_code(true);
+    for a in boundary_values() {
+        for b in boundary_values() {
+            let expected = ((a as i64 as i128 * b as i64 as i128) >> 64) as u64;
+            assert_eq!(execute(&code, &input(a, b)), Ok(expected), "{a:#x} * {b:#x}");
+        }
+    }
+}
+
+// ============================================================================
+// Edge cases
+// ============================================================================
+
+#[test]
+fn test_mulh_max_squared() {
+    // (2^64 - 1)^2 = 2^128 - 2^65 + 1, high word 2^64 - 2
+    assert_eq!(execute(&mulh_code(false), &input(u64::MAX, u64::MAX)), Ok(u64::MAX - 1));
+}
+
+#[test]
+fn test_imulh_sign_extends() {
+    // -1 * 1 = -1: the high word is all ones for IMULH, zero for MULH
+    assert_eq!(execute(&mulh_code(true), &input(u64::MAX, 1)), Ok(u64::MAX));
+    assert_eq!(execute(&mulh_code(false), &input(u64::MAX, 1)), Ok(0));
+}
+
+#[test]
+fn test_imulh_min_squared_does_not_overflow() {
+    // i64::MIN^2 = 2^126, high word 2^62
+    let min = i64::MIN as u64;
+    assert_eq!(execute(&mulh_code(true), &input(min, min)), Ok(1 << 62));
+}
+
+#[test]
+fn test_mulh_with_low_word_gives_full_product() {
+    // MUL gives the low half, MULH the high half of the same product
+    let (a, b) = (0xDEAD_BEEF_CAFE_BABEu64, 0x0123_4567_89AB_CDEFu64);
+    let code = BytecodeBuilder::new()
+        .native_read(0).native_read(8).mul()
+        .native_read(0).native_read(8).mulh()
+        .xor()
+        .halt()
+        .build()
+        .unwrap();
+    let full = a as u128 * b as u128;
+    assert_eq!(execute(&code, &input(a, b)), Ok((full as u64) ^ (full >> 64) as u64));
+}
@@ -0,0 +1,126 @@
+//! Mutated Handler Equivalence Tests
+//!
+//! Each build-time mutated arithmetic handler must match its reference in
+//! `handlers::arithmetic` (result and flags) and the native wrapping
+//! operation, over edge cases and a long pseudo-random input sequence.
+
+#![cfg(feature = "handler_mutation")]
+
+use aegis_vm::{
+    VmResult, VmState,
+    handlers::{arithmetic, mutation},
+};
+
+type Handler = fn(&mut VmState) -> VmResult<()>;
+
+const EDGES: &[u64] = &[
+    0, 1, 2, 3, 0xFF, 0x100, 0xFFFF_FFFF, 0x1_0000_0000,
+    0x7FFF_FFFF_FFFF_FFFF, 0x8000_0000_0000_0000, u64::MAX - 1, u64::MAX,
+];
+
+const RANDOM_SAMPLES: usize = 10_000;
+
+/// SplitMix64, so every build checks the same sequence
+fn samples() -> impl Iterator<Item = u64> {
+    let mut x = 0x2367_u64;
+    core::iter::repeat_with(move || {
+        x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = x;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+    .take(RANDOM_SAMPLES)
+}
+
+/// Run a handler on fresh operands, return (result, flags)
+fn run(handler: Handler, operands: &[u64]) -> (u64, u8) {
+    let mut state = VmState::new(&[], &[]);
+    for &value in operands {
+        state.push(value).unwrap();
+    }
+    handler(&mut state).unwrap();
+    (state.pop().unwrap(), state.flags)
+}
+
+fn check_binary(name: &str, mutated: Handler, reference: Handler, native: fn(u64, u64) -> u64) {
+    let pairs = EDGES
+        .iter()
+        .flat_map(|&a| EDGES.iter().map(move |&b| (a, b)))
+        .chain(samples().zip(samples().skip(1)));
+    for (a, b) in pairs {
+        let got = run(mutated, &[a, b]);
+        assert_eq!(got, run(reference, &[a, b]), "{name}({a:#x}, {b:#x})");
+        assert_eq!(got.0, native(a, b), "{name}({a:#x}, {b:#x})");
+    }
+}
+
+fn check_unary(name: &str, mutated: Handler, reference: Handler, native: fn(u64) -> u64) {
+    for a in EDGES.iter().copied().chain(samples()) {
+        let got = run(mutated, &[a]);
+        assert_eq!(got, run(reference, &[a]), "{name}({a:#x})");
+        assert_eq!(got.0, native(a), "{name}({a:#x})");
+    }
+}
+
+// ============================================================================
+// Binary operations
+// ============================================================================
+
+#[test]
+fn test_mutated_add_matches_reference() {
+    check_binary("add", mutation::mutated_add, arithmetic::handle_add, u64::wrapping_add);
+}
+
+#[test]
+fn test_mutated_sub_matches_reference() {
+    check_binary("sub", mutation::mutated_sub, arithmetic::handle_sub, u64::wrapping_sub);
+}
+
+#[test]
+fn test_mutated_mul_matches_reference() {
+    check_binary("mul", mutation::mutated_mul, arithmetic::handle_mul, u64::wrapping_mul);
+}
+
+#[test]
+fn test_mutated_xor_matches_reference() {
+    check_binary("xor", mutation::mutated_xor, arithmetic::handle_xor, |a, b| a ^ b);
+}
+
+#[test]
+fn test_mutated_and_matches_reference() {
+    check_binary("and", mutation::mutated_and, arithmetic::handle_and, |a, b| a & b);
+}
+
+#[test]
+fn test_mutated_or_matches_reference() {
+    check_binary("or", mutation::mutated_or, arithmetic::handle_or, |a, b| a | b);
+}
+
+// ============================================================================
+// Unary operations
+// ============================================================================
+
+#[test]
+fn test_mutated_not_matches_reference() {
+    check_unary("not", mutation::mutated_not, arithmetic::handle_not, |a| !a);
+}
+
+#[test]
+fn test_mutated_inc_matches_reference() {
+    check_unary("inc", mutation::mutated_inc, arithmetic::handle_inc, |a| a.wrapping_add(1));
+}
+
+#[test]
+fn test_mutated_dec_matches_reference() {
+    check_unary("dec", mutation::mutated_dec, arithmetic::handle_dec, |a| a.wrapping_sub(1));
+}
+
+// ============================================================================
+// Runtime self-check
+// ============================================================================
+
+#[test]
+fn test_runtime_self_check_passes() {
+    assert!(mutation::verify_mutated_handlers());
+}
@@ -1,8 +1,8 @@
 //! Tests for native function bridging
 
 use aegis_vm::engine::execute_with_natives;
-use aegis_vm::native::{NativeRegistry, NativeRegistryBuilder, standard_ids};
-use aegis_vm::build_config::opcodes::{stack, native, exec};
+use aegis_vm::native::{NativeOverrides, NativeRegistry, NativeRegistryBuilder, standard_ids};
+use aegis_vm::build_config::opcodes::{stack, arithmetic, native, exec};
 
 // ============================================================================
 // Basic Native Call Tests
@@ -343,3 +343,45 @@ fn test_native_with_closure_state() {
     assert_eq!(result, 3);
     assert_eq!(counter.load(Ordering::SeqCst), 3);
 }
+
+// ============================================================================
+// Platform Overrides
+// ============================================================================
+
+/// Stub root detection that always reports a rooted device
+fn rooted_stub(_args: &[u64]) -> u64 {
+    1
+}
+
+#[test]
+fn test_override_check_root() {
+    let overrides = NativeOverrides::new()
+        .with_override(standard_ids::CHECK_ROOT, rooted_stub);
+
+    // CHECK_ROOT result * 10 + CHECK_DEBUGGER result
+    let code = vec![
+        native::NATIVE_CALL, standard_ids::CHECK_ROOT, 0,
+        stack::PUSH_IMM8, 10,
+        arithmetic::MUL,
+        native::NATIVE_CALL, standard_ids::CHECK_DEBUGGER, 0,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+
+    let result = execute_with_natives(&code, &[], &overrides.registry()).unwrap();
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn test_overrides_fall_back_to_defaults() {
+    let registry = NativeOverrides::new().registry();
+
+    let code = vec![
+        native::NATIVE_CALL, standard_ids::CHECK_ROOT, 0,
+        exec::HALT,
+    ];
+    assert_eq!(execute_with_natives(&code, &[], &registry).unwrap(), 0);
+    assert!(registry.is_registered(standard_ids::HASH_FNV1A));
+    assert!(registry.is_registered(standard_ids::GET_TIMESTAMP));
+    assert!(!registry.is_registered(standard_ids::CUSTOM_START));
+}
@@ -0,0 +1,100 @@
+//! Native Call Observer Tests
+//!
+//! `execute_with_native_observer` reports every NATIVE_CALL with the native
+//! index, its arguments in push order and the result, in call order.
+
+use aegis_vm::{
+    execute_with_native_observer, execute_with_native_table, natives, BytecodeBuilder, VmError,
+};
+
+type Call = (usize, Vec<u64>, u64);
+
+fn observe(code: &[u8], input: &[u8], table: &[fn(&[u64]) -> u64]) -> (Result<u64, VmError>, Vec<Call>) {
+    let mut calls = Vec::new();
+    let result = execute_with_native_observer(code, input, table, &mut |index, args, result| {
+        calls.push((index, args.to_vec(), result));
+    });
+    (result, calls)
+}
+
+/// check_debugger(); check_root(); then hash(input[0], 7) unless a check fired
+fn audited_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_call(0, 0)
+        .native_call(1, 0)
+        .or()
+        .jnz("tampered")
+        .native_read(0).push_imm8(7).native_call(2, 2)
+        .halt()
+        .label("tampered")
+        .push_imm8(0xDE)
+        .halt()
+        .build()
+        .unwrap()
+}
+
+// ============================================================================
+// Recorded sequence
+// ============================================================================
+
+#[test]
+fn test_records_calls_in_order() {
+    let table = natives![|_| 0, |_| 0, |args| args[0].wrapping_mul(31) ^ args[1]];
+    let (result, calls) = observe(&audited_code(), &5u64.to_le_bytes(), &table);
+
+    assert_eq!(result, Ok((5 * 31) ^ 7));
+    assert_eq!(calls, vec![(0, vec![], 0), (1, vec![], 0), (2, vec![5, 7], (5 * 31) ^ 7)]);
+}
+
+#[test]
+fn test_skipped_calls_are_not_recorded() {
+    // A positive check skips the hash call entirely
+    let table = natives![|_| 0, |_| 1, |_| unreachable!()];
+    let (result, calls) = observe(&audited_code(), &5u64.to_le_bytes(), &table);
+
+    assert_eq!(result, Ok(0xDE));
+    assert_eq!(calls, vec![(0, vec![], 0), (1, vec![], 1)]);
+}
+
+#[test]
+fn test_result_matches_unobserved_run() {
+    let table = natives![|_| 0, |_| 0, |args| args[0] + args[1]];
+    let input = 40u64.to_le_bytes();
+    let (result, _) = observe(&audited_code(), &input, &table);
+    assert_eq!(result, execute_with_native_table(&audited_code(), &input, &table));
+}
+
+// ============================================================================
+// Loops and failures
+// ============================================================================
+
+#[test]
+fn test_calls_in_loop() {
+    // for i in 3..0 { native0(i) }
+    let code = BytecodeBuilder::new()
+        .mov_imm(0, 3)
+        .label("loop")
+        .push_reg(0).native_call(0, 1).drop()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .push_imm8(0)
+        .halt()
+        .build()
+        .unwrap();
+    let table = natives![|args| args[0] * 10];
+    let (result, calls) = observe(&code, &[], &table);
+
+    assert_eq!(result, Ok(0));
+    assert_eq!(calls, vec![(0, vec![3], 30), (0, vec![2], 20), (0, vec![1], 10)]);
+}
+
+#[test]
+fn test_missing_native_is_not_recorded() {
+    let code = BytecodeBuilder::new().native_call(0, 0).native_call(5, 0).halt().build().unwrap();
+    let table = natives![|_| 1];
+    let (result, calls) = observe(&code, &[], &table);
+
+    assert_eq!(result, Err(VmError::UnknownNative));
+    assert_eq!(calls, vec![(0, vec![], 1)]);
+}
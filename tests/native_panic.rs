@@ -0,0 +1,73 @@
+//! Panicking Native Tests
+//!
+//! A native that panics must fail the run with `NativeCallFailed` instead
+//! of unwinding through the VM, whether it comes from a `NativeRegistry` or
+//! a native table.
+
+use aegis_vm::{
+    execute_with_natives, execute_with_native_table, run_with_natives, natives,
+    BytecodeBuilder, NativeRegistry, VmError, VmState,
+};
+
+fn panicking(args: &[u64]) -> u64 {
+    if args[0] == 0 {
+        panic!("native rejected zero");
+    }
+    100 / args[0]
+}
+
+/// native0(input[0]) + 1
+fn call_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0).native_call(0, 1)
+        .push_imm8(1).add()
+        .halt()
+        .build()
+        .unwrap()
+}
+
+fn word(v: u64) -> [u8; 8] {
+    v.to_le_bytes()
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+#[test]
+fn test_registry_panic_becomes_error() {
+    let registry = natives![registry: panicking];
+    assert_eq!(execute_with_natives(&call_code(), &word(0), &registry), Err(VmError::NativeCallFailed));
+}
+
+#[test]
+fn test_registry_call_reports_panic() {
+    let mut registry = NativeRegistry::new();
+    registry.register(7, |_| panic!("boom")).unwrap();
+    assert_eq!(registry.call(7, &[]), Err(VmError::NativeCallFailed));
+}
+
+#[test]
+fn test_registry_is_usable_after_panic() {
+    let registry = natives![registry: panicking];
+    let code = call_code();
+    let (bad, good) = (word(0), word(4));
+
+    let mut state = VmState::new(&code, &bad);
+    assert_eq!(run_with_natives(&mut state, &registry), Err(VmError::NativeCallFailed));
+
+    state.rebind(&code, &good);
+    run_with_natives(&mut state, &registry).unwrap();
+    assert_eq!(state.result, 26);
+}
+
+// ============================================================================
+// Native table
+// ============================================================================
+
+#[test]
+fn test_table_panic_becomes_error() {
+    let table = natives![panicking];
+    assert_eq!(execute_with_native_table(&call_code(), &word(0), &table), Err(VmError::NativeCallFailed));
+    assert_eq!(execute_with_native_table(&call_code(), &word(5), &table), Ok(21));
+}
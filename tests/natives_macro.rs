@@ -0,0 +1,119 @@
+//! natives! Macro Tests
+//!
+//! `natives![...]` builds a native table in NATIVE_CALL id order from
+//! functions and closures; `natives![registry: ...]` builds the boxed
+//! `NativeRegistry` equivalent, which also accepts capturing closures.
+
+use aegis_vm::{
+    natives, execute_with_native_table, execute_with_natives, execute_with_native_table_traced,
+    execute_with_natives_traced, run_with_native_table,
+    run_with_natives, BytecodeBuilder, VmError, VmState,
+    native::NativeOverride,
+};
+
+fn check_root(args: &[u64]) -> u64 {
+    let root = (args[0] as f64).sqrt() as u64;
+    (root * root == args[0]) as u64
+}
+
+/// check_root(mul(input[0], input[0])) + add(40, 2)
+fn host_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0).native_read(0).native_call(1, 2)
+        .native_call(2, 1)
+        .push_imm8(40).push_imm8(2).native_call(0, 2)
+        .add()
+        .halt()
+        .build()
+        .unwrap()
+}
+
+// ============================================================================
+// Function pointer table
+// ============================================================================
+
+#[test]
+fn test_table_mixes_closures_and_functions() {
+    let table = natives![
+        |args| args[0] + args[1],
+        |args| args[0].wrapping_mul(args[1]),
+        check_root,
+    ];
+    assert_eq!(table.len(), 3);
+    assert_eq!(execute_with_native_table(&host_code(), &7u64.to_le_bytes(), &table), Ok(43));
+}
+
+#[test]
+fn test_table_entries_are_callable_directly() {
+    let table: [NativeOverride; 2] = natives![|_| 42, check_root];
+    assert_eq!(table[0](&[]), 42);
+    assert_eq!(table[1](&[49]), 1);
+    assert_eq!(table[1](&[50]), 0);
+}
+
+#[test]
+fn test_table_order_is_call_id() {
+    let table = natives![|_| 10, |_| 20, |_| 30];
+    let code = BytecodeBuilder::new().native_call(2, 0).halt().build().unwrap();
+    assert_eq!(execute_with_native_table(&code, &[], &table), Ok(30));
+}
+
+#[test]
+fn test_index_past_table_reports_index() {
+    let table = natives![|_| 10, |_| 20, |_| 30];
+    let code = BytecodeBuilder::new().native_call(0, 0).native_call(3, 0).halt().build().unwrap();
+    assert_eq!(execute_with_native_table(&code, &[], &table), Err(VmError::UnknownNative));
+
+    let mut state = VmState::new(&code, &[]);
+    state.set_native_table(&table);
+    let err = run_with_native_table(&mut state).unwrap_err();
+    assert_eq!(err.code(), 26);
+    assert_eq!(format!("{err:?}"), "E26");
+    assert_eq!(state.unknown_native, Some(3));
+
+    assert_eq!(
+        execute_with_native_table_traced(&code, &[], &table),
+        Err((VmError::UnknownNative, Some(3)))
+    );
+}
+
+// ============================================================================
+// Boxed registry
+// ============================================================================
+
+#[test]
+fn test_registry_accepts_capturing_closures() {
+    let bias = 100;
+    let registry = natives![
+        registry:
+        move |args| args[0] + args[1] + bias,
+        |args| args[0].wrapping_mul(args[1]),
+        check_root,
+    ];
+    assert_eq!(execute_with_natives(&host_code(), &7u64.to_le_bytes(), &registry), Ok(143));
+    assert!(registry.is_registered(2));
+    assert!(!registry.is_registered(3));
+}
+
+#[test]
+fn test_unregistered_index_reports_index() {
+    let registry = natives![registry: |_| 1];
+    let code = BytecodeBuilder::new().native_call(200, 0).halt().build().unwrap();
+    assert_eq!(execute_with_natives(&code, &[], &registry), Err(VmError::UnknownNative));
+
+    let mut state = VmState::new(&code, &[]);
+    assert_eq!(run_with_natives(&mut state, &registry), Err(VmError::UnknownNative));
+    assert_eq!(state.unknown_native, Some(200));
+
+    assert_eq!(
+        execute_with_natives_traced(&code, &[], &registry),
+        Err((VmError::UnknownNative, Some(200)))
+    );
+
+    // Other failures carry no index
+    let underflow = BytecodeBuilder::new().native_call(0, 0).add().halt().build().unwrap();
+    assert_eq!(
+        execute_with_natives_traced(&underflow, &[], &registry),
+        Err((VmError::StackUnderflow, None))
+    );
+}
@@ -0,0 +1,97 @@
+//! Output Buffer Tests
+//!
+//! `VmState::with_output_capacity` preallocates the output so known-size
+//! writes never reallocate, and `take_output` moves the buffer out without
+//! cloning it.
+
+use aegis_vm::{
+    run, BytecodeBuilder, VmState,
+    state::MAX_OUTPUT_SIZE,
+    build_config::opcodes::{stack, memory, exec},
+};
+
+const OUTPUT_LEN: u64 = 4096;
+
+/// Append the low byte of a countdown from `count` to 1, one byte per step
+fn countdown_code(count: u64) -> Vec<u8> {
+    BytecodeBuilder::new()
+        .mov_imm(0, count)
+        .label("loop")
+        .push_reg(0).native_write(0)
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .push_imm8(0)
+        .halt()
+        .build()
+        .unwrap()
+}
+
+fn expected_output(count: u64) -> Vec<u8> {
+    (1..=count).rev().map(|v| v as u8).collect()
+}
+
+// ============================================================================
+// Preallocation
+// ============================================================================
+
+#[test]
+fn test_large_output_within_capacity_never_reallocates() {
+    let code = countdown_code(OUTPUT_LEN);
+    let mut state = VmState::with_output_capacity(&code, &[], OUTPUT_LEN as usize);
+    let ptr = state.output.as_ptr();
+    let capacity = state.output.capacity();
+    assert!(capacity >= OUTPUT_LEN as usize);
+
+    run(&mut state).unwrap();
+
+    assert_eq!(state.output.len(), OUTPUT_LEN as usize);
+    assert_eq!(state.output.capacity(), capacity);
+    assert_eq!(state.output.as_ptr(), ptr);
+    assert_eq!(state.output, expected_output(OUTPUT_LEN));
+}
+
+#[test]
+fn test_fixed_offset_writes_within_capacity() {
+    let code = [
+        stack::PUSH_IMM, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11,
+        memory::STORE64, 0x00, 0x01, // offset 256
+        stack::PUSH_IMM8, 0,
+        exec::HALT,
+    ];
+    let mut state = VmState::with_output_capacity(&code, &[], 264);
+    let ptr = state.output.as_ptr();
+    run(&mut state).unwrap();
+    assert_eq!(state.output.as_ptr(), ptr);
+    assert_eq!(state.output[256..], 0x1122_3344_5566_7788u64.to_le_bytes());
+}
+
+#[test]
+fn test_capacity_is_capped() {
+    let state = VmState::with_output_capacity(&[], &[], usize::MAX);
+    assert!(state.output.capacity() <= MAX_OUTPUT_SIZE);
+}
+
+// ============================================================================
+// take_output
+// ============================================================================
+
+#[test]
+fn test_take_output_moves_buffer() {
+    let code = countdown_code(OUTPUT_LEN);
+    let mut state = VmState::with_output_capacity(&code, &[], OUTPUT_LEN as usize);
+    run(&mut state).unwrap();
+
+    let ptr = state.output.as_ptr();
+    let output = state.take_output();
+    assert_eq!(output.as_ptr(), ptr);
+    assert_eq!(output, expected_output(OUTPUT_LEN));
+}
+
+#[test]
+fn test_take_output_of_default_state() {
+    let code = countdown_code(3);
+    let mut state = VmState::new(&code, &[]);
+    run(&mut state).unwrap();
+    assert_eq!(state.take_output(), [3, 2, 1]);
+}
@@ -0,0 +1,67 @@
+//! Tests for the POW opcode
+//!
+//! POW wraps on overflow exactly like `u64::wrapping_pow`.
+
+use aegis_vm::engine::execute;
+use aegis_vm::build_config::opcodes::{arithmetic, control, exec, stack};
+
+/// Build `base.pow(exp)` bytecode
+fn pow_code(base: u64, exp: u64) -> Vec<u8> {
+    let mut code = vec![stack::PUSH_IMM];
+    code.extend_from_slice(&base.to_le_bytes());
+    code.push(stack::PUSH_IMM);
+    code.extend_from_slice(&exp.to_le_bytes());
+    code.extend_from_slice(&[arithmetic::POW, exec::HALT]);
+    code
+}
+
+#[test]
+fn test_pow_two_to_ten() {
+    assert_eq!(execute(&pow_code(2, 10), &[]), Ok(1024));
+}
+
+#[test]
+fn test_pow_zero_exponent() {
+    assert_eq!(execute(&pow_code(12345, 0), &[]), Ok(1));
+    // 0^0 == 1, matching u64::pow
+    assert_eq!(execute(&pow_code(0, 0), &[]), Ok(1));
+}
+
+#[test]
+fn test_pow_zero_base() {
+    assert_eq!(execute(&pow_code(0, 5), &[]), Ok(0));
+}
+
+#[test]
+fn test_pow_overflow_wraps() {
+    assert_eq!(execute(&pow_code(2, 64), &[]), Ok(0));
+    assert_eq!(execute(&pow_code(3, 50), &[]), Ok(3u64.wrapping_pow(50)));
+    assert_eq!(execute(&pow_code(7, 1000), &[]), Ok(7u64.wrapping_pow(1000)));
+}
+
+#[test]
+fn test_pow_large_exponent() {
+    // Exponent beyond u32 range is handled, not truncated:
+    // 3^(2^32 + 3) = 3^(2^32) * 3^3, and 3^(2^32) is 3 squared 32 times
+    let mut squared = 3u64;
+    for _ in 0..32 {
+        squared = squared.wrapping_mul(squared);
+    }
+    let expected = squared.wrapping_mul(27);
+    assert_eq!(execute(&pow_code(3, (1u64 << 32) + 3), &[]), Ok(expected));
+}
+
+#[test]
+fn test_pow_sets_zero_flag() {
+    // 2^64 wraps to 0, JZ taken
+    let mut code = pow_code(2, 64);
+    code.pop(); // drop HALT
+    code.extend_from_slice(&[
+        control::JZ, 0x03, 0x00,
+        stack::PUSH_IMM8, 0,
+        exec::HALT,
+        stack::PUSH_IMM8, 1,
+        exec::HALT,
+    ]);
+    assert_eq!(execute(&code, &[]), Ok(1));
+}
@@ -0,0 +1,54 @@
+//! Protection Overhead Smoke Test
+//!
+//! Coarse timing check that protection levels keep their cost ordering;
+//! `benches/protection_levels.rs` has the detailed numbers.
+
+use aegis_vm::vm_protect;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[vm_protect(level = "debug")]
+fn sum_debug(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+#[vm_protect(level = "paranoid")]
+fn sum_paranoid(n: u64) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
+
+/// Fastest of several batches, to filter out scheduler noise
+fn best_batch_time(f: fn(u64) -> u64) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..200 {
+                black_box(f(black_box(50)));
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn test_paranoid_slower_than_debug() {
+    assert_eq!(sum_debug(50), 1225);
+    assert_eq!(sum_paranoid(50), 1225);
+
+    // Warm up lazily initialized tables before timing
+    sum_debug(1);
+    sum_paranoid(1);
+
+    let debug = best_batch_time(sum_debug);
+    let paranoid = best_batch_time(sum_paranoid);
+    assert!(paranoid > debug, "paranoid {:?} <= debug {:?}", paranoid, debug);
+}
@@ -0,0 +1,133 @@
+//! Protection Stats Tests
+//!
+//! `BytecodeStats` summarizes code size and opcode mix. Paranoid output must
+//! stay measurably larger and more varied than the same program without
+//! obfuscation, so a level that silently stops applying its transforms is
+//! caught here.
+
+use aegis_vm::{
+    execute, BytecodeBuilder, BytecodeStats, ProtectionLevel,
+    bytecode::BytecodeFlags,
+    opcodes as base,
+    passes::{paranoid_warning, PassPipeline},
+};
+
+/// sum(1..=input[0]) * 2, with a loop and a subroutine
+fn source_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0).pop_reg(0)
+        .push_imm8(0)
+        .label("loop")
+        .push_reg(0).add()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .call("double")
+        .halt()
+        .label("double")
+        .dup().add().ret()
+        .build()
+        .unwrap()
+}
+
+// ============================================================================
+// Stats
+// ============================================================================
+
+#[test]
+fn test_stats_of_plain_code() {
+    let code = source_code();
+    let stats = BytecodeStats::of(&code);
+    assert_eq!(stats.len, code.len());
+    assert_eq!(stats.undecoded, 0);
+    assert_eq!(stats.instruction_count(), 19);
+    assert_eq!(stats.count(base::arithmetic::ADD), 2);
+    assert_eq!(stats.count(base::stack::PUSH_REG), 3);
+    assert_eq!(stats.count(base::special::NOP), 0);
+}
+
+#[test]
+fn test_stats_of_empty_code() {
+    let stats = BytecodeStats::of(&[]);
+    assert_eq!(stats.len, 0);
+    assert_eq!(stats.instruction_count(), 0);
+    assert_eq!(stats.distinct_opcodes(), 0);
+}
+
+// ============================================================================
+// Paranoid vs debug
+// ============================================================================
+
+#[test]
+fn test_paranoid_is_larger_and_more_diverse() {
+    let debug = source_code();
+    let debug_stats = BytecodeStats::of(&debug);
+
+    for seed in 0..16 {
+        let paranoid = PassPipeline::paranoid(seed).run(debug.clone());
+        let stats = BytecodeStats::of(&paranoid);
+
+        assert!(
+            stats.len >= debug_stats.len * 5 / 4,
+            "seed {seed}: paranoid {} bytes vs debug {}",
+            stats.len,
+            debug_stats.len
+        );
+        assert!(
+            stats.distinct_opcodes() > debug_stats.distinct_opcodes(),
+            "seed {seed}: no new opcodes"
+        );
+        assert_eq!(execute(&paranoid, &10u64.to_le_bytes()), Ok(110), "seed {seed}");
+    }
+}
+
+// ============================================================================
+// Anti-analysis coverage
+// ============================================================================
+
+#[test]
+fn test_trivial_paranoid_function_warns() {
+    // fn answer() -> u64 { 42 }: no CMP, so no opaque predicate lands
+    let trivial = BytecodeBuilder::new().push_imm8(42).halt().build().unwrap();
+    for seed in 0..16 {
+        let paranoid = PassPipeline::paranoid(seed).run(trivial.clone());
+        assert_eq!(BytecodeStats::of(&paranoid).anti_analysis_count(), 0, "seed {seed}");
+        let warning = paranoid_warning(&paranoid).expect("trivial function should warn");
+        assert!(warning.contains("no opaque predicate"), "{warning}");
+        assert_eq!(execute(&paranoid, &[]), Ok(42), "seed {seed}");
+    }
+}
+
+#[test]
+fn test_substantive_paranoid_function_does_not_warn() {
+    let debug = source_code();
+    assert!(paranoid_warning(&debug).is_some());
+    for seed in 0..16 {
+        let paranoid = PassPipeline::paranoid(seed).run(debug.clone());
+        assert!(BytecodeStats::of(&paranoid).anti_analysis_count() > 0, "seed {seed}");
+        assert_eq!(paranoid_warning(&paranoid), None, "seed {seed}");
+    }
+}
+
+#[test]
+fn test_paranoid_passes_all_apply_after_opaque_predicates() {
+    // Junk behind opaque predicates decodes cleanly, so the padding pass
+    // that follows still rewrites the code
+    let debug = source_code();
+    for seed in 0..16 {
+        let paranoid = PassPipeline::paranoid(seed).run(debug.clone());
+        let stats = BytecodeStats::of(&paranoid);
+        assert_eq!(stats.undecoded, 0, "seed {seed}");
+        assert!(stats.count(base::special::NOP) + stats.count(base::special::NOP_N) > 0, "seed {seed}");
+    }
+}
+
+#[test]
+fn test_paranoid_flags_include_every_lower_level() {
+    let paranoid = ProtectionLevel::Paranoid.to_flags();
+    for level in [ProtectionLevel::Debug, ProtectionLevel::Low, ProtectionLevel::Medium, ProtectionLevel::High] {
+        assert_eq!(level.to_flags() & !paranoid, 0, "{level:?}");
+    }
+    assert_ne!(paranoid & BytecodeFlags::Paranoid as u16, 0);
+    assert_eq!(ProtectionLevel::Debug.to_flags(), 0);
+}
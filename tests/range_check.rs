@@ -0,0 +1,172 @@
+//! Range Check Tests
+//!
+//! Conjunctions of comparisons on a shared operand (`lo <= x && x <= hi`,
+//! `a < b && b < c`) must agree with native Rust for values below, inside
+//! and above the range, including the bounds themselves.
+
+use aegis_vm_macro::vm_protect;
+
+// ============================================================================
+// Protected functions
+// ============================================================================
+
+/// Inclusive range check with literal bounds
+#[vm_protect(level = "debug")]
+fn in_percent(x: u64) -> u64 {
+    if 0 <= x && x <= 100 {
+        return 1;
+    }
+    0
+}
+
+/// Inclusive range check with bounds from parameters
+#[vm_protect(level = "debug")]
+fn in_range(lo: u64, x: u64, hi: u64) -> u64 {
+    if lo <= x && x <= hi {
+        return 1;
+    }
+    0
+}
+
+#[vm_protect]
+fn in_range_standard(lo: u64, x: u64, hi: u64) -> u64 {
+    if lo <= x && x <= hi {
+        return 1;
+    }
+    0
+}
+
+#[vm_protect(level = "paranoid")]
+fn in_range_paranoid(lo: u64, x: u64, hi: u64) -> u64 {
+    if lo <= x && x <= hi {
+        return 1;
+    }
+    0
+}
+
+/// Strictly increasing chain
+#[vm_protect(level = "debug")]
+fn ascending(a: u64, b: u64, c: u64) -> u64 {
+    if a < b && b < c {
+        return 1;
+    }
+    0
+}
+
+/// Half-open range with an else branch
+#[vm_protect(level = "debug")]
+fn clamp_bucket(x: u64) -> u64 {
+    if 10 <= x && x < 20 {
+        return x - 10;
+    } else {
+        return 99;
+    }
+}
+
+const TOP: u64 = i64::MAX as u64;
+
+// ============================================================================
+// Native references
+// ============================================================================
+
+fn native_in_range(lo: u64, x: u64, hi: u64) -> u64 {
+    (lo <= x && x <= hi) as u64
+}
+
+fn native_ascending(a: u64, b: u64, c: u64) -> u64 {
+    (a < b && b < c) as u64
+}
+
+fn native_clamp_bucket(x: u64) -> u64 {
+    if (10..20).contains(&x) { x - 10 } else { 99 }
+}
+
+/// Values around 0, 10, 20, 100 and the top of the range
+///
+/// Ordered comparisons lower to the signed JLT/JGT family, so samples stay
+/// at or below `i64::MAX`.
+fn samples() -> Vec<u64> {
+    let mut values = vec![0, 1, TOP - 1, TOP];
+    for bound in [10u64, 20, 100] {
+        values.extend([bound - 1, bound, bound + 1]);
+    }
+    values
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_in_range_below_inside_above() {
+    assert_eq!(in_range(10, 9, 20), 0);
+    assert_eq!(in_range(10, 10, 20), 1);
+    assert_eq!(in_range(10, 15, 20), 1);
+    assert_eq!(in_range(10, 20, 20), 1);
+    assert_eq!(in_range(10, 21, 20), 0);
+}
+
+#[test]
+fn test_literal_bounds_match_native() {
+    for x in samples() {
+        assert_eq!(in_percent(x), native_in_range(0, x, 100), "x = {x}");
+    }
+}
+
+#[test]
+fn test_parameter_bounds_match_native() {
+    for lo in samples() {
+        for x in samples() {
+            for hi in [10u64, 20, 100, TOP] {
+                assert_eq!(in_range(lo, x, hi), native_in_range(lo, x, hi), "{lo} <= {x} <= {hi}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_empty_range_never_matches() {
+    for x in samples() {
+        assert_eq!(in_range(20, x, 10), 0, "x = {x}");
+    }
+}
+
+#[test]
+fn test_protection_levels_agree() {
+    for x in samples() {
+        let expected = native_in_range(10, x, 100);
+        assert_eq!(in_range_standard(10, x, 100), expected, "x = {x}");
+        assert_eq!(in_range_paranoid(10, x, 100), expected, "x = {x}");
+    }
+}
+
+#[test]
+fn test_strict_chain_matches_native() {
+    for a in samples() {
+        for b in samples() {
+            for c in [0u64, 10, 20, TOP] {
+                assert_eq!(ascending(a, b, c), native_ascending(a, b, c), "{a} < {b} < {c}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_half_open_range_matches_native() {
+    for x in samples() {
+        assert_eq!(clamp_bucket(x), native_clamp_bucket(x), "x = {x}");
+    }
+}
+
+#[test]
+#[ignore = "vm_protect lowers ordered u64 comparisons to the signed JLT/JGT family; \
+            needs unsigned lowering in aegis_vm_macro"]
+fn test_operands_above_i64_max() {
+    // Native Rust compares these as u64; the VM sees them as negative i64
+    let high = [TOP + 1, u64::MAX - 1, u64::MAX];
+    for x in high {
+        assert_eq!(in_range(10, x, u64::MAX), native_in_range(10, x, u64::MAX), "x = {x}");
+        assert_eq!(ascending(0, x, u64::MAX), native_ascending(0, x, u64::MAX), "x = {x}");
+        assert_eq!(clamp_bucket(x), native_clamp_bucket(x), "x = {x}");
+    }
+}
@@ -0,0 +1,143 @@
+//! Package Re-keying Tests
+//!
+//! `BytecodePackage::rekey` moves a sealed package to a new key without
+//! decrypting it. The result must open under the new key only, and a
+//! package that doesn't belong to the old key must be left alone.
+
+use aegis_vm::{
+    execute, BytecodeBuilder, VmError,
+    bytecode::{BytecodeFlags, BytecodeHeader, BytecodePackage},
+    crypto::{decrypt_bytecode, rekey_bytecode, CryptoContext},
+};
+
+const NEW_KEY: [u8; 32] = [0x5C; 32];
+
+/// sum(1..=input[0]) followed by `padding` NOPs
+fn padded_code(padding: usize) -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.native_read(0).pop_reg(0)
+        .push_imm8(0)
+        .label("loop")
+        .push_reg(0).add()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop");
+    for _ in 0..padding {
+        asm.nop();
+    }
+    asm.halt().build().unwrap()
+}
+
+fn source_code() -> Vec<u8> {
+    padded_code(13)
+}
+
+fn context() -> CryptoContext {
+    CryptoContext::new([0x42; 32])
+}
+
+fn sealed(ctx: &mut CryptoContext, code: &[u8]) -> BytecodePackage {
+    let (ciphertext, nonce, tag) = ctx.encrypt(code).unwrap();
+    let mut header = BytecodeHeader::new(ctx.build_id, 0, BytecodeFlags::Encrypted as u16);
+    header.nonce = nonce;
+    header.tag = tag;
+    header.code_len = ciphertext.len() as u32;
+    BytecodePackage { header, code: ciphertext }
+}
+
+fn run_with_key(package: &BytecodePackage, key: &[u8; 32]) -> Result<u64, VmError> {
+    let code = decrypt_bytecode(key, &package.header.nonce, &package.code, &package.header.tag)?;
+    execute(&code, &10u64.to_le_bytes())
+}
+
+// ============================================================================
+// Re-keying
+// ============================================================================
+
+#[test]
+fn test_rekeyed_package_runs_under_new_key_only() {
+    let mut ctx = context();
+    // Code lengths on and around AES block boundaries
+    for padding in [0, 1, 2, 3, 16, 17, 40] {
+        let code = padded_code(padding);
+        let mut package = sealed(&mut ctx, &code);
+        let old = ctx.wbc().unwrap();
+        let expected = execute(&code, &10u64.to_le_bytes());
+        assert_eq!(run_with_key(&package, old.bytecode_key()), expected);
+
+        package.rekey(old, &NEW_KEY).unwrap();
+
+        assert_eq!(run_with_key(&package, &NEW_KEY), expected, "len {}", code.len());
+        assert_eq!(run_with_key(&package, old.bytecode_key()), Err(VmError::DecryptionFailed));
+        assert_eq!(package.unpack(&ctx), Err(VmError::DecryptionFailed));
+    }
+}
+
+#[test]
+fn test_rekey_keeps_header_except_tag() {
+    let mut ctx = context();
+    let original = sealed(&mut ctx, &source_code());
+    let old = ctx.wbc().unwrap();
+    let mut package = original.clone();
+    package.rekey(old, &NEW_KEY).unwrap();
+
+    assert_eq!(package.code.len(), original.code.len());
+    assert_ne!(package.code, original.code);
+    assert_ne!(package.header.tag, original.header.tag);
+    assert_eq!(package.header.nonce, original.header.nonce);
+    assert_eq!(package.header.flags, original.header.flags);
+    assert_eq!(package.header.code_len, original.header.code_len);
+}
+
+#[test]
+fn test_rekey_back_restores_original() {
+    let mut ctx = context();
+    let original = sealed(&mut ctx, &source_code());
+    let old = ctx.wbc().unwrap();
+    let mut package = original.clone();
+    package.rekey(old, &NEW_KEY).unwrap();
+
+    // Same nonce, old key again: byte-for-byte the sealed original
+    let header = &mut package.header;
+    header.tag = rekey_bytecode(&NEW_KEY, old.bytecode_key(), &header.nonce, &mut package.code, &header.tag).unwrap();
+    assert_eq!(package.code, original.code);
+    assert_eq!(package.header.tag, original.header.tag);
+    assert_eq!(package.unpack(&ctx), Ok(source_code()));
+}
+
+#[test]
+fn test_rekey_survives_serialization() {
+    let mut ctx = context();
+    let mut package = sealed(&mut ctx, &source_code());
+    let old = ctx.wbc().unwrap();
+    package.rekey(old, &NEW_KEY).unwrap();
+
+    let restored = BytecodePackage::from_bytes(&package.to_bytes()).unwrap();
+    assert_eq!(run_with_key(&restored, &NEW_KEY), Ok(55));
+}
+
+// ============================================================================
+// Failures
+// ============================================================================
+
+#[test]
+fn test_rekey_rejects_tampered_package_unchanged() {
+    let mut ctx = context();
+    let mut package = sealed(&mut ctx, &source_code());
+    let old = ctx.wbc().unwrap();
+    package.code[3] ^= 1;
+    let tampered = package.clone();
+
+    assert_eq!(package.rekey(old, &NEW_KEY), Err(VmError::DecryptionFailed));
+    assert_eq!(package.code, tampered.code);
+    assert_eq!(package.header.tag, tampered.header.tag);
+}
+
+#[test]
+fn test_rekey_rejects_plaintext_package() {
+    let ctx = context();
+    let old = ctx.wbc().unwrap();
+    let mut package = BytecodePackage::new_plaintext(source_code(), 0);
+    assert_eq!(package.rekey(old, &NEW_KEY), Err(VmError::InvalidBytecode));
+    assert_eq!(package.code, source_code());
+}
@@ -0,0 +1,196 @@
+//! Sandbox Tests
+//!
+//! `Sandbox` is the entry point for third-party bytecode: malformed code
+//! is rejected before it runs, resource exhaustion stops at the configured
+//! limit, and every failure comes back as a `VmError`, never a panic.
+
+use aegis_vm::{
+    BytecodeBuilder, NativeRegistry, Sandbox, VmError,
+    bytecode::{instruction_length, validate},
+    build_config::{OPCODE_DECODE, opcodes::{control, exec, stack}},
+    state::{MAX_INSTRUCTIONS, MAX_STACK_SIZE},
+};
+
+/// sum(1..=input[0])
+fn sum_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .native_read(0).pop_reg(0)
+        .push_imm8(0)
+        .label("loop")
+        .push_reg(0).add()
+        .push_reg(0).dec().pop_reg(0)
+        .push_reg(0).push_imm8(0).cmp().drop().drop()
+        .jnz("loop")
+        .halt()
+        .build()
+        .unwrap()
+}
+
+/// A byte that doesn't decode to any opcode in this build
+fn undecodable_byte() -> u8 {
+    (0..=u8::MAX)
+        .find(|&b| instruction_length(OPCODE_DECODE[b as usize]) == 0)
+        .expect("every byte decodes")
+}
+
+// ============================================================================
+// Well-formed code
+// ============================================================================
+
+#[test]
+fn test_runs_valid_code() {
+    let code = sum_code();
+    assert_eq!(validate(&code), Ok(()));
+    assert_eq!(Sandbox::new().run(&code, &10u64.to_le_bytes()), Ok(55));
+    assert_eq!(Sandbox::default().run(&code, &100u64.to_le_bytes()), Ok(5050));
+}
+
+#[test]
+fn test_runs_with_natives() {
+    let mut registry = NativeRegistry::new();
+    registry.register(7, |args| args[0] * 3).unwrap();
+    let code = BytecodeBuilder::new().push_imm8(14).native_call(7, 1).halt().build().unwrap();
+    assert_eq!(Sandbox::new().run_with_natives(&code, &[], &registry), Ok(42));
+}
+
+#[test]
+fn test_limits_clamp_to_vm_maximums() {
+    let clamped = Sandbox::new().stack_limit(usize::MAX).instruction_budget(u64::MAX);
+    let explicit = Sandbox::new().stack_limit(MAX_STACK_SIZE).instruction_budget(MAX_INSTRUCTIONS);
+    assert_eq!(clamped, explicit);
+    assert_eq!(Sandbox::new(), Sandbox::default());
+}
+
+// ============================================================================
+// Malformed code
+// ============================================================================
+
+#[test]
+fn test_rejects_undecodable_byte() {
+    let code = [stack::PUSH_IMM8, 1, undecodable_byte(), exec::HALT];
+    assert_eq!(Sandbox::new().run(&code, &[]), Err(VmError::InvalidBytecode));
+}
+
+#[test]
+fn test_rejects_truncated_instruction() {
+    let code = [stack::PUSH_IMM8, 1, exec::HALT, stack::PUSH_IMM32, 1, 2];
+    assert_eq!(Sandbox::new().run(&code, &[]), Err(VmError::InvalidBytecode));
+}
+
+#[test]
+fn test_rejects_branch_into_operand() {
+    // JMP lands on the immediate of PUSH_IMM8
+    let mut code = vec![control::JMP];
+    code.extend_from_slice(&1i16.to_le_bytes());
+    code.extend_from_slice(&[stack::PUSH_IMM8, exec::HALT, exec::HALT]);
+    assert_eq!(Sandbox::new().run(&code, &[]), Err(VmError::InvalidJumpTarget));
+}
+
+#[test]
+fn test_rejects_branch_outside_code() {
+    for offset in [-100i16, 100, i16::MIN, i16::MAX] {
+        let mut code = vec![control::JMP];
+        code.extend_from_slice(&offset.to_le_bytes());
+        code.push(exec::HALT);
+        assert_eq!(Sandbox::new().run(&code, &[]), Err(VmError::InvalidJumpTarget), "offset {offset}");
+    }
+}
+
+#[test]
+fn test_jump_guard_without_validation() {
+    // Jumps back into the PUSH_IMM payload, which hides PUSH_IMM8 42; HALT
+    let code = [
+        stack::PUSH_IMM, stack::PUSH_IMM8, 42, exec::HALT, 0, 0, 0, 0, 0,
+        stack::DROP,
+        control::JMP, 0xF4, 0xFF,
+        exec::HALT,
+    ];
+    assert_eq!(Sandbox::new().validate(false).run(&code, &[]), Err(VmError::InvalidJumpTarget));
+}
+
+#[test]
+fn test_branch_to_end_is_valid() {
+    let mut code = vec![stack::PUSH_IMM8, 9, control::JMP];
+    code.extend_from_slice(&1i16.to_le_bytes());
+    code.push(exec::HALT);
+    assert_eq!(validate(&code), Ok(()));
+}
+
+#[test]
+fn test_unvalidated_code_still_fails_cleanly() {
+    let code = [stack::PUSH_IMM8, 1, undecodable_byte(), exec::HALT];
+    assert_eq!(Sandbox::new().validate(false).run(&code, &[]), Err(VmError::InvalidOpcode));
+}
+
+#[test]
+fn test_random_bytes_never_panic() {
+    let mut rng = 0x2545F4914F6CDD1Du64;
+    for len in 0..400 {
+        let code: Vec<u8> = (0..len % 64)
+            .map(|_| {
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+                rng as u8
+            })
+            .collect();
+        let sandbox = Sandbox::new().instruction_budget(10_000);
+        let _ = sandbox.run(&code, &[0; 16]);
+        let _ = sandbox.validate(false).run(&code, &[0; 16]);
+    }
+}
+
+// ============================================================================
+// Resource exhaustion
+// ============================================================================
+
+#[test]
+fn test_instruction_budget() {
+    let spin = BytecodeBuilder::new().label("top").jmp("top").build().unwrap();
+    assert_eq!(Sandbox::new().instruction_budget(1000).run(&spin, &[]), Err(VmError::MaxInstructionsExceeded));
+
+    // sum(1..=10) takes well over 50 instructions
+    let code = sum_code();
+    let input = 10u64.to_le_bytes();
+    assert_eq!(Sandbox::new().instruction_budget(50).run(&code, &input), Err(VmError::MaxInstructionsExceeded));
+    assert_eq!(Sandbox::new().instruction_budget(500).run(&code, &input), Ok(55));
+}
+
+#[test]
+fn test_stack_limit() {
+    let flood = BytecodeBuilder::new().label("top").push_imm8(1).jmp("top").build().unwrap();
+    assert_eq!(Sandbox::new().stack_limit(16).run(&flood, &[]), Err(VmError::StackOverflow));
+
+    let mut asm = BytecodeBuilder::new();
+    for _ in 0..16 {
+        asm.push_imm8(1);
+    }
+    let sixteen = asm.halt().build().unwrap();
+    assert_eq!(Sandbox::new().stack_limit(16).run(&sixteen, &[]), Ok(1));
+    assert_eq!(Sandbox::new().stack_limit(15).run(&sixteen, &[]), Err(VmError::StackOverflow));
+}
+
+#[test]
+fn test_heap_limit() {
+    let alloc = |size: u32| BytecodeBuilder::new().push_imm32(size).heap_alloc().halt().build().unwrap();
+    let sandbox = Sandbox::new().heap_limit(4096);
+    assert!(sandbox.run(&alloc(1024), &[]).is_ok());
+    assert_eq!(sandbox.run(&alloc(64 * 1024), &[]), Err(VmError::HeapOutOfMemory));
+
+    let leak = BytecodeBuilder::new().label("top").push_imm16(512).heap_alloc().drop().jmp("top").build().unwrap();
+    assert_eq!(sandbox.run(&leak, &[]), Err(VmError::HeapOutOfMemory));
+}
+
+#[test]
+fn test_panicking_native_is_contained() {
+    let mut registry = NativeRegistry::new();
+    registry.register(0, |_| panic!("native blew up")).unwrap();
+    let code = BytecodeBuilder::new().native_call(0, 0).halt().build().unwrap();
+    assert_eq!(Sandbox::new().run_with_natives(&code, &[], &registry), Err(VmError::NativeCallFailed));
+}
+
+#[test]
+fn test_unknown_native() {
+    let code = BytecodeBuilder::new().native_call(42, 0).halt().build().unwrap();
+    assert_eq!(Sandbox::new().run(&code, &[]), Err(VmError::UnknownNative));
+}
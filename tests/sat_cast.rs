@@ -0,0 +1,117 @@
+//! Saturating Cast Tests
+//!
+//! `aegis_vm::saturating_cast` clamps to the target range where `as`
+//! truncates, and agrees with the equivalent clamp-then-truncate bytecode.
+
+use aegis_vm::{execute, saturating_cast, BytecodeBuilder};
+
+/// Conversion step appended after the clamp (a `TRUNC*` or `SEXT*`)
+type Convert = fn(&mut BytecodeBuilder) -> &mut BytecodeBuilder;
+
+/// Native `saturating_cast` to the target type, widened back to u64
+type Expected = fn(i64) -> u64;
+
+/// Bounds, values just past them, and far outliers for every target width
+fn source_values() -> Vec<i64> {
+    let mut values = vec![0, 1, -1, 44, 255, 256, 300, -300, i64::MIN, i64::MAX];
+    for bits in [7u32, 8, 15, 16, 31, 32] {
+        let bound = 1i64 << bits;
+        values.extend([bound - 1, bound, bound + 1, -bound - 1, -bound, -bound + 1]);
+    }
+    values
+}
+
+/// `input[0] as i64` clamped to `[min, max]`, then `convert` applied
+fn clamp_code(min: i64, max: i64, convert: Convert) -> Vec<u8> {
+    let mut asm = BytecodeBuilder::new();
+    asm.native_read(0)
+        .push_imm(max as u64).cmp().drop()
+        .jle("above_min")
+        .drop().push_imm(max as u64)
+        .jmp("convert")
+        .label("above_min")
+        .push_imm(min as u64).cmp().drop()
+        .jge("convert")
+        .drop().push_imm(min as u64)
+        .label("convert");
+    convert(&mut asm).halt().build().unwrap()
+}
+
+// ============================================================================
+// Native semantics
+// ============================================================================
+
+#[test]
+fn test_saturates_where_as_truncates() {
+    let x = 300u32;
+    assert_eq!(saturating_cast::<u8>(x), 255);
+    assert_eq!(x as u8, 44);
+    assert_eq!(saturating_cast::<u8>(300), 255);
+
+    assert_eq!(saturating_cast::<u8>(-5), 0);
+    assert_eq!(saturating_cast::<i8>(200), i8::MAX);
+    assert_eq!(saturating_cast::<i8>(-200), i8::MIN);
+    assert_eq!(saturating_cast::<u32>(-1i64), 0);
+    assert_eq!(saturating_cast::<u32>(u64::MAX), u32::MAX);
+    assert_eq!(saturating_cast::<i64>(u64::MAX), i64::MAX);
+    assert_eq!(saturating_cast::<u64>(i64::MIN), 0);
+}
+
+#[test]
+fn test_pointer_sized_sources() {
+    assert_eq!(saturating_cast::<u8>(300usize), 255);
+    assert_eq!(saturating_cast::<u8>(usize::MAX), u8::MAX);
+    assert_eq!(saturating_cast::<u32>(42usize), 42);
+    assert_eq!(saturating_cast::<i8>(-300isize), i8::MIN);
+    assert_eq!(saturating_cast::<u64>(-1isize), 0);
+    assert_eq!(saturating_cast::<usize>(isize::MIN), 0);
+    assert_eq!(saturating_cast::<isize>(usize::MAX), isize::MAX);
+    assert_eq!(saturating_cast::<u64>(u128::MAX), u64::MAX);
+}
+
+#[test]
+fn test_in_range_values_match_as() {
+    for v in source_values() {
+        if let Ok(narrow) = u16::try_from(v) {
+            assert_eq!(saturating_cast::<u16>(v), narrow);
+            assert_eq!(saturating_cast::<u16>(v), v as u16);
+        }
+        if let Ok(narrow) = i32::try_from(v) {
+            assert_eq!(saturating_cast::<i32>(v), narrow);
+        }
+    }
+}
+
+// ============================================================================
+// Agreement with the lowered sequence
+// ============================================================================
+
+#[test]
+fn test_unsigned_targets_match_bytecode() {
+    let cases: [(i64, Convert, Expected); 3] = [
+        (u8::MAX as i64, BytecodeBuilder::trunc8, |v| saturating_cast::<u8>(v) as u64),
+        (u16::MAX as i64, BytecodeBuilder::trunc16, |v| saturating_cast::<u16>(v) as u64),
+        (u32::MAX as i64, BytecodeBuilder::trunc32, |v| saturating_cast::<u32>(v) as u64),
+    ];
+    for (max, convert, expected) in cases {
+        let code = clamp_code(0, max, convert);
+        for v in source_values() {
+            assert_eq!(execute(&code, &v.to_le_bytes()), Ok(expected(v)), "{v} clamped to {max}");
+        }
+    }
+}
+
+#[test]
+fn test_signed_targets_match_bytecode() {
+    let cases: [(i64, i64, Convert, Expected); 3] = [
+        (i8::MIN as i64, i8::MAX as i64, BytecodeBuilder::sext8, |v| saturating_cast::<i8>(v) as u64),
+        (i16::MIN as i64, i16::MAX as i64, BytecodeBuilder::sext16, |v| saturating_cast::<i16>(v) as u64),
+        (i32::MIN as i64, i32::MAX as i64, BytecodeBuilder::sext32, |v| saturating_cast::<i32>(v) as u64),
+    ];
+    for (min, max, convert, expected) in cases {
+        let code = clamp_code(min, max, convert);
+        for v in source_values() {
+            assert_eq!(execute(&code, &v.to_le_bytes()), Ok(expected(v)), "{v} clamped to {min}..={max}");
+        }
+    }
+}
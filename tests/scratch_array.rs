@@ -0,0 +1,165 @@
+//! Scratch Array Tests
+//!
+//! Local mutable arrays (`let mut tmp = [0u64; 8];`) live on the VM heap
+//! for the duration of the call: indexed writes and reads must behave like
+//! a native array, and every call starts from a fresh, zeroed buffer.
+
+use aegis_vm_macro::vm_protect;
+
+// ============================================================================
+// Protected functions
+// ============================================================================
+
+/// Sort four values with a 5-comparator sorting network, then pack the
+/// sorted bytes as `s0 | s1 << 8 | s2 << 16 | s3 << 24`
+#[vm_protect(level = "debug")]
+fn sort4_packed(a: u64, b: u64, c: u64, d: u64) -> u64 {
+    let mut v = [0u64; 4];
+    v[0] = a;
+    v[1] = b;
+    v[2] = c;
+    v[3] = d;
+
+    let mut t = 0;
+    if v[0] > v[1] { t = v[0]; v[0] = v[1]; v[1] = t; }
+    if v[2] > v[3] { t = v[2]; v[2] = v[3]; v[3] = t; }
+    if v[0] > v[2] { t = v[0]; v[0] = v[2]; v[2] = t; }
+    if v[1] > v[3] { t = v[1]; v[1] = v[3]; v[3] = t; }
+    if v[1] > v[2] { t = v[1]; v[1] = v[2]; v[2] = t; }
+
+    v[0] | (v[1] << 8) | (v[2] << 16) | (v[3] << 24)
+}
+
+/// Histogram of the low 3 bits of `x`'s bytes, weighted by bucket index
+#[vm_protect(level = "debug")]
+fn bucket_histogram(x: u64) -> u64 {
+    let mut buckets = [0u64; 8];
+    let mut i = 0;
+    while i < 8 {
+        let byte = (x >> (i * 8)) & 7;
+        buckets[byte] = buckets[byte] + 1;
+        i += 1;
+    }
+
+    let mut weighted = 0;
+    let mut j = 0;
+    while j < 8 {
+        weighted = weighted + buckets[j] * (j + 1);
+        j += 1;
+    }
+    weighted
+}
+
+/// Prefix sums of 1..=n (1 <= n <= 8), each slot read back to build the
+/// next: the last sum * 100 plus the first slot
+#[vm_protect(level = "debug")]
+fn prefix_sums(n: u64) -> u64 {
+    let mut acc = [0u64; 8];
+    acc[0] = 1;
+    let mut i = 1;
+    while i < n {
+        acc[i] = acc[i - 1] + i + 1;
+        i += 1;
+    }
+    acc[n - 1] * 100 + acc[0]
+}
+
+#[vm_protect]
+fn bucket_histogram_standard(x: u64) -> u64 {
+    let mut buckets = [0u64; 8];
+    let mut i = 0;
+    while i < 8 {
+        let byte = (x >> (i * 8)) & 7;
+        buckets[byte] = buckets[byte] + 1;
+        i += 1;
+    }
+
+    let mut weighted = 0;
+    let mut j = 0;
+    while j < 8 {
+        weighted = weighted + buckets[j] * (j + 1);
+        j += 1;
+    }
+    weighted
+}
+
+// ============================================================================
+// Native references
+// ============================================================================
+
+fn native_sort4_packed(a: u64, b: u64, c: u64, d: u64) -> u64 {
+    let mut v = [a, b, c, d];
+    v.sort_unstable();
+    v[0] | (v[1] << 8) | (v[2] << 16) | (v[3] << 24)
+}
+
+fn native_bucket_histogram(x: u64) -> u64 {
+    let mut buckets = [0u64; 8];
+    for byte in x.to_le_bytes() {
+        buckets[(byte & 7) as usize] += 1;
+    }
+    buckets.iter().zip(1..).map(|(count, weight)| count * weight).sum()
+}
+
+fn native_prefix_sums(n: u64) -> u64 {
+    let last: u64 = (1..=n).sum();
+    last * 100 + 1
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_sorting_network_matches_native() {
+    let values = [0u64, 1, 7, 42, 99, 200, 255];
+    for &a in &values {
+        for &b in &values {
+            for &c in &values {
+                for &d in &[0u64, 42, 255] {
+                    assert_eq!(
+                        sort4_packed(a, b, c, d),
+                        native_sort4_packed(a, b, c, d),
+                        "sort({a}, {b}, {c}, {d})"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sorting_network_all_permutations() {
+    let sorted = native_sort4_packed(1, 2, 3, 4);
+    for p in 0..256u64 {
+        let [a, b, c, d] = [p & 3, (p >> 2) & 3, (p >> 4) & 3, (p >> 6) & 3];
+        if (1u64 << a | 1 << b | 1 << c | 1 << d) != 0b1111 {
+            continue;
+        }
+        assert_eq!(sort4_packed(a + 1, b + 1, c + 1, d + 1), sorted, "{p:#010b}");
+    }
+}
+
+#[test]
+fn test_accumulation_matches_native() {
+    for x in [0u64, 1, 0x0706050403020100, 0xFFFF_FFFF_FFFF_FFFF, 0x0101010101010101, 0xDEADBEEFCAFEBABE] {
+        assert_eq!(bucket_histogram(x), native_bucket_histogram(x), "x = {x:#x}");
+        assert_eq!(bucket_histogram_standard(x), native_bucket_histogram(x), "x = {x:#x}");
+    }
+}
+
+#[test]
+fn test_prefix_sums_read_back_earlier_writes() {
+    for n in 1..=8 {
+        assert_eq!(prefix_sums(n), native_prefix_sums(n), "n = {n}");
+    }
+}
+
+#[test]
+fn test_each_call_gets_a_fresh_buffer() {
+    // A leftover buffer from the previous call would skew the counts
+    let x = 0x0303030303030303;
+    let first = bucket_histogram(x);
+    assert_eq!(bucket_histogram(x), first);
+    assert_eq!(bucket_histogram(0), native_bucket_histogram(0));
+}
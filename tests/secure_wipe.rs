@@ -0,0 +1,174 @@
+//! Secure Wipe Tests
+//!
+//! With the `zeroize` feature, `VmState::wipe` and dropping a `WipeOnDrop`
+//! guard must leave the register, heap, stack and output allocations zeroed. A wrapping allocator
+//! inspects the heap buffer at the moment it is freed.
+
+#![cfg(feature = "zeroize")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use aegis_vm::{
+    run, VmState, WipeOnDrop,
+    build_config::opcodes::{stack, register, heap, native, exec},
+};
+
+/// Records whether the watched block was all zeroes when deallocated
+struct WatchingAlloc;
+
+static WATCHED: AtomicPtr<u8> = AtomicPtr::new(core::ptr::null_mut());
+static WATCHED_ZEROED: AtomicBool = AtomicBool::new(false);
+
+unsafe impl GlobalAlloc for WatchingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if !ptr.is_null() && WATCHED.load(Ordering::SeqCst) == ptr {
+            let bytes = unsafe { core::slice::from_raw_parts(ptr, layout.size()) };
+            WATCHED_ZEROED.store(bytes.iter().all(|&b| b == 0), Ordering::SeqCst);
+            WATCHED.store(core::ptr::null_mut(), Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: WatchingAlloc = WatchingAlloc;
+
+const SECRET: u64 = 0x5EC2_E75E_C2E7_5EC2;
+
+/// Spread SECRET over registers, heap and stack (its low byte to output);
+/// optionally fail
+fn secret_code(fail: bool) -> Vec<u8> {
+    let mut code = vec![register::MOV_IMM, 3];
+    code.extend_from_slice(&SECRET.to_le_bytes());
+    code.extend_from_slice(&[
+        stack::PUSH_IMM8, 16,
+        heap::HEAP_ALLOC,
+        stack::PUSH_REG, 3,
+        heap::HEAP_STORE64,
+        stack::PUSH_REG, 3,
+        native::NATIVE_WRITE, 0, 0,
+        stack::PUSH_REG, 3,
+        stack::PUSH_REG, 3,
+    ]);
+    if fail {
+        code.extend_from_slice(&[exec::HALT_ERR, 1]);
+    } else {
+        code.push(exec::HALT);
+    }
+    code
+}
+
+/// Read a buffer's whole allocation through its raw pointer
+fn peek<T: Copy>(v: &Vec<T>) -> (*const T, usize) {
+    (v.as_ptr(), v.capacity())
+}
+
+fn all_zero<T: Copy + Default + PartialEq>((ptr, cap): (*const T, usize)) -> bool {
+    // The Vec keeps its allocation after wipe, so the memory is still live
+    unsafe { core::slice::from_raw_parts(ptr, cap) }.iter().all(|v| *v == T::default())
+}
+
+fn contains_secret<T: Copy>((ptr, cap): (*const T, usize)) -> bool {
+    let bytes = unsafe {
+        core::slice::from_raw_parts(ptr as *const u8, cap * core::mem::size_of::<T>())
+    };
+    bytes.windows(8).any(|w| w == SECRET.to_le_bytes())
+}
+
+// ============================================================================
+// Explicit wipe
+// ============================================================================
+
+#[test]
+fn test_wipe_zeroes_buffers() {
+    let code = secret_code(false);
+    let mut state = VmState::new(&code, &[]);
+    state.disable_anti_analysis();
+    run(&mut state).unwrap();
+
+    let regs = peek(&state.regs);
+    let heap = peek(&state.heap);
+    let stack = peek(&state.stack);
+    let output = peek(&state.output);
+    assert!(contains_secret(regs));
+    assert!(contains_secret(heap));
+    assert!(contains_secret(stack));
+    assert!(!all_zero(output));
+
+    state.wipe();
+
+    // Same allocations, now zeroed across the full capacity
+    assert_eq!(peek(&state.regs).0, regs.0);
+    assert_eq!(peek(&state.heap).0, heap.0);
+    assert!(all_zero(regs));
+    assert!(all_zero(heap));
+    assert!(all_zero(stack));
+    assert!(all_zero(output));
+    assert!(state.regs.is_empty() && state.heap.is_empty());
+    assert_eq!(state.result, 0);
+}
+
+#[test]
+fn test_rebind_after_wipe() {
+    let rerun = secret_code(false);
+    let code = secret_code(false);
+    let mut state = VmState::new(&code, &[]);
+    state.disable_anti_analysis();
+    run(&mut state).unwrap();
+    state.wipe();
+
+    assert_eq!(aegis_vm::run_in_state(&mut state, &rerun, &[]).unwrap(), SECRET);
+}
+
+// ============================================================================
+// Drop
+// ============================================================================
+
+#[test]
+fn test_drop_wipes_heap_on_error_path() {
+    let code = secret_code(true);
+    let mut state = WipeOnDrop::new(VmState::new(&code, &[]));
+    state.disable_anti_analysis();
+    assert!(run(&mut state).is_err());
+    assert!(contains_secret(peek(&state.heap)));
+
+    WATCHED_ZEROED.store(false, Ordering::SeqCst);
+    WATCHED.store(state.heap.as_ptr() as *mut u8, Ordering::SeqCst);
+    drop(state);
+
+    assert!(WATCHED.load(Ordering::SeqCst).is_null(), "heap buffer was not freed");
+    assert!(WATCHED_ZEROED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_take_output_wipes_heap() {
+    let code = secret_code(false);
+    let mut state = VmState::new(&code, &[]);
+    state.disable_anti_analysis();
+    run(&mut state).unwrap();
+    assert!(contains_secret(peek(&state.heap)));
+
+    WATCHED_ZEROED.store(false, Ordering::SeqCst);
+    WATCHED.store(state.heap.as_ptr() as *mut u8, Ordering::SeqCst);
+    let output = state.take_output();
+
+    assert!(WATCHED.load(Ordering::SeqCst).is_null(), "heap buffer was not freed");
+    assert!(WATCHED_ZEROED.load(Ordering::SeqCst));
+    assert_eq!(output, [SECRET as u8]);
+}
+
+#[test]
+fn test_plain_state_fields_move_out() {
+    // Without drop glue on VmState the buffers can be moved out directly
+    let code = secret_code(false);
+    let mut state = VmState::new(&code, &[]);
+    state.disable_anti_analysis();
+    run(&mut state).unwrap();
+    let output = state.output;
+    assert_eq!(output, [SECRET as u8]);
+}
@@ -0,0 +1,95 @@
+//! Signed Remainder Tests
+//!
+//! IMOD must match Rust's truncated `%` and IMOD_EUCLID must match
+//! `i64::rem_euclid`, for every sign combination of dividend and divisor.
+//! The i64::MIN / -1 overflow must wrap for IDIV as well.
+
+use aegis_vm::{
+    execute,
+    build_config::opcodes::{stack, arithmetic, exec},
+};
+
+fn push(code: &mut Vec<u8>, value: i64) {
+    code.push(stack::PUSH_IMM);
+    code.extend_from_slice(&value.to_le_bytes());
+}
+
+fn binop(op: u8, a: i64, b: i64) -> i64 {
+    let mut code = Vec::new();
+    push(&mut code, a);
+    push(&mut code, b);
+    code.extend_from_slice(&[op, exec::HALT]);
+    execute(&code, &[]).unwrap() as i64
+}
+
+const DIVIDENDS: &[i64] = &[0, 1, 2, 3, 6, 7, 100, i64::MAX, -1, -2, -3, -6, -7, -100, i64::MIN + 1];
+const DIVISORS: &[i64] = &[1, 2, 3, 7, 10, i64::MAX, -1, -2, -3, -7, -10, i64::MIN];
+
+// ============================================================================
+// Truncated remainder (%)
+// ============================================================================
+
+#[test]
+fn test_imod_examples() {
+    assert_eq!(binop(arithmetic::IMOD, -7, 3), -1);
+    assert_eq!(binop(arithmetic::IMOD, 7, -3), 1);
+    assert_eq!(binop(arithmetic::IMOD, -7, -3), -1);
+    assert_eq!(binop(arithmetic::IMOD, 7, 3), 1);
+}
+
+#[test]
+fn test_imod_matches_rust() {
+    for &a in DIVIDENDS {
+        for &b in DIVISORS {
+            assert_eq!(binop(arithmetic::IMOD, a, b), a % b, "{a} % {b}");
+        }
+    }
+}
+
+// ============================================================================
+// Euclidean remainder (rem_euclid)
+// ============================================================================
+
+#[test]
+fn test_imod_euclid_examples() {
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, -7, 3), 2);
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, 7, -3), 1);
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, -7, -3), 2);
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, 7, 3), 1);
+}
+
+#[test]
+fn test_imod_euclid_matches_rust() {
+    for &a in DIVIDENDS {
+        for &b in DIVISORS {
+            let r = binop(arithmetic::IMOD_EUCLID, a, b);
+            assert_eq!(r, a.rem_euclid(b), "{a}.rem_euclid({b})");
+            assert!(r >= 0);
+        }
+    }
+}
+
+// ============================================================================
+// Edge cases
+// ============================================================================
+
+#[test]
+fn test_min_by_minus_one_wraps_to_zero() {
+    // Rust traps on i64::MIN % -1; the VM returns the wrapping result
+    assert_eq!(binop(arithmetic::IMOD, i64::MIN, -1), i64::MIN.wrapping_rem(-1));
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, i64::MIN, -1), i64::MIN.wrapping_rem_euclid(-1));
+}
+
+#[test]
+fn test_idiv_min_by_minus_one_wraps() {
+    // Used to panic with "attempt to divide with overflow"
+    assert_eq!(binop(arithmetic::IDIV, i64::MIN, -1), i64::MIN.wrapping_div(-1));
+    assert_eq!(binop(arithmetic::IDIV, i64::MIN, 1), i64::MIN);
+    assert_eq!(binop(arithmetic::IDIV, -7, 2), -3);
+}
+
+#[test]
+fn test_remainder_by_zero_is_zero() {
+    assert_eq!(binop(arithmetic::IMOD, -7, 0), 0);
+    assert_eq!(binop(arithmetic::IMOD_EUCLID, -7, 0), 0);
+}
@@ -5,7 +5,7 @@
 
 use aegis_vm::{
     execute,
-    smc::{SmcConfig, execute_smc, encrypt_bytecode, decrypt_bytecode},
+    smc::{SmcConfig, execute_smc, execute_smc_with_stats, encrypt_bytecode, decrypt_bytecode},
     build_config::opcodes::{stack, arithmetic, control, exec},
 };
 
@@ -270,6 +270,19 @@ fn test_different_seeds_different_encryption() {
     assert_ne!(encrypted1, encrypted2, "Different seeds should produce different encryption");
 }
 
+#[test]
+fn test_config_from_explicit_key() {
+    let seeded = SmcConfig::from_build_seed(77777);
+    let config = SmcConfig::new(seeded.key).with_window(4);
+    assert_eq!(config.window_size, 4);
+    assert!(!config.zero_on_free);
+
+    // Same key, same keystream
+    let mut code = vec![stack::PUSH_IMM8, 42, exec::HALT];
+    encrypt_bytecode(&mut code, &config);
+    assert_eq!(execute_smc(code, &[], &seeded), Ok(42));
+}
+
 #[test]
 fn test_smc_after_execution_re_encrypted() {
     let original = vec![
@@ -505,3 +518,92 @@ fn test_smc_fibonacci_10() {
     let result = execute_smc(code, &[], &config).unwrap();
     assert_eq!(result, 55); // F(10) = 55
 }
+
+// =============================================================================
+// SMC Profiling Tests
+// =============================================================================
+
+/// Sum 1..10 with a counted loop (same shape as test_smc_simple_loop)
+fn smc_loop_code() -> Vec<u8> {
+    vec![
+        stack::PUSH_IMM8, 1,     // 0-1
+        stack::POP_REG, 0,       // 2-3: R0 = 1
+        stack::PUSH_IMM8, 0,     // 4-5
+        stack::POP_REG, 1,       // 6-7: R1 = 0
+        // Loop start (addr 8): R1 += R0
+        stack::PUSH_REG, 1,      // 8-9
+        stack::PUSH_REG, 0,      // 10-11
+        arithmetic::ADD,         // 12
+        stack::POP_REG, 1,       // 13-14
+        // R0 += 1
+        stack::PUSH_REG, 0,      // 15-16
+        arithmetic::INC,         // 17
+        stack::POP_REG, 0,       // 18-19
+        // if R0 < 10 goto loop
+        stack::PUSH_REG, 0,      // 20-21
+        stack::PUSH_IMM8, 10,    // 22-23
+        control::CMP,            // 24
+        control::JLT, 0xEC, 0xFF, // 25-27: jump -20 to loop start
+        stack::PUSH_REG, 1,      // 28-29
+        exec::HALT,              // 30
+    ]
+}
+
+#[test]
+fn test_smc_stats_count_every_byte() {
+    let code = smc_loop_code();
+    let mut encrypted = code.clone();
+    let config = SmcConfig::from_build_seed(424242);
+    encrypt_bytecode(&mut encrypted, &config);
+
+    let (result, stats) = execute_smc_with_stats(encrypted.clone(), &[], &config).unwrap();
+    assert_eq!(result, 45);
+    assert_eq!(execute_smc(encrypted, &[], &config), Ok(45));
+
+    // 4 setup + 9 iterations * 11 loop instructions + PUSH_REG + HALT
+    assert_eq!(stats.instructions, 4 + 9 * 11 + 2);
+    // Window 1: every instruction is decrypted and re-encrypted in full
+    assert!(stats.decrypt_ops > stats.instructions);
+    assert_eq!(stats.decrypt_ops, stats.encrypt_ops);
+}
+
+#[test]
+fn test_smc_stats_loop_matches_normal() {
+    let code = smc_loop_code();
+    assert_eq!(execute(&code, &[]), Ok(45));
+
+    let mut encrypted = code.clone();
+    let config = SmcConfig::from_build_seed(424242).with_window(16);
+    encrypt_bytecode(&mut encrypted, &config);
+
+    let (result, stats) = execute_smc_with_stats(encrypted, &[], &config).unwrap();
+    assert_eq!(result, 45);
+    // Everything decrypted is re-encrypted before returning
+    assert_eq!(stats.decrypt_ops, stats.encrypt_ops);
+}
+
+#[test]
+fn test_smc_stats_window_reduces_crypto_ops() {
+    let run = |window: usize| {
+        let mut code = smc_loop_code();
+        let config = SmcConfig::from_build_seed(31337).with_window(window);
+        encrypt_bytecode(&mut code, &config);
+        let (result, stats) = execute_smc_with_stats(code, &[], &config).unwrap();
+        assert_eq!(result, 45);
+        stats
+    };
+
+    let narrow = run(1);
+    let wide = run(16);
+
+    // Same program, same instruction count
+    assert_eq!(narrow.instructions, wide.instructions);
+    // Window 1 re-decrypts the loop body on every iteration
+    assert!(
+        narrow.decrypt_ops > wide.decrypt_ops * 5,
+        "window=1: {:?}, window=16: {:?}",
+        narrow,
+        wide
+    );
+    assert!(narrow.encrypt_ops > wide.encrypt_ops * 5);
+}
@@ -0,0 +1,65 @@
+//! Stable Opcode Tests
+//!
+//! With the `stable_opcodes` feature the build keeps the canonical opcode
+//! numbering, so bytecode written by hand or by an external tool against
+//! `aegis_vm::opcodes` runs as-is.
+
+#![cfg(feature = "stable_opcodes")]
+
+use aegis_vm::{execute, build_config::{opcode_aliases, OPCODE_DECODE, OPCODE_ENCODE}, opcodes};
+
+// ============================================================================
+// Encoding tables
+// ============================================================================
+
+#[test]
+fn test_tables_are_identity() {
+    for byte in 0..=u8::MAX {
+        assert_eq!(OPCODE_ENCODE[byte as usize], byte, "encode {byte:#04x}");
+        assert_eq!(OPCODE_DECODE[byte as usize], byte, "decode {byte:#04x}");
+    }
+    assert!(opcode_aliases::ALL.is_empty());
+}
+
+#[test]
+fn test_build_config_matches_canonical() {
+    use aegis_vm::build_config::opcodes as built;
+    assert_eq!(built::stack::PUSH_IMM8, opcodes::stack::PUSH_IMM8);
+    assert_eq!(built::arithmetic::ADD, opcodes::arithmetic::ADD);
+    assert_eq!(built::control::JNZ, opcodes::control::JNZ);
+    assert_eq!(built::convert::BSWAP, opcodes::convert::BSWAP);
+}
+
+// ============================================================================
+// Externally produced bytecode
+// ============================================================================
+
+#[test]
+fn test_runs_literal_canonical_bytes() {
+    // PUSH_IMM8 40; PUSH_IMM8 2; ADD; HALT
+    let code = [0x02, 40, 0x02, 2, 0x20, 0xFF];
+    assert_eq!(execute(&code, &[]), Ok(42));
+}
+
+#[test]
+fn test_runs_canonical_loop() {
+    use opcodes::{arithmetic, control, exec, native, stack};
+
+    // sum(1..=input[0]) with a backward JNZ
+    let mut code = vec![
+        native::NATIVE_READ, 0, 0,
+        stack::POP_REG, 0,
+        stack::PUSH_IMM8, 0,
+        // loop:
+        stack::PUSH_REG, 0, arithmetic::ADD,
+        stack::PUSH_REG, 0, arithmetic::DEC, stack::POP_REG, 0,
+        stack::PUSH_REG, 0, stack::PUSH_IMM8, 0, control::CMP, stack::DROP, stack::DROP,
+        control::JNZ,
+    ];
+    let loop_start = 7;
+    let offset = loop_start as i16 - (code.len() as i16 + 2);
+    code.extend_from_slice(&offset.to_le_bytes());
+    code.push(exec::HALT);
+
+    assert_eq!(execute(&code, &10u64.to_le_bytes()), Ok(55));
+}
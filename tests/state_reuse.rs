@@ -0,0 +1,101 @@
+//! State Reuse Tests
+//!
+//! One pre-configured VmState runs several bytecode blobs via
+//! `run_in_state`; configuration must persist while nothing from a previous
+//! run leaks into the next.
+
+use aegis_vm::{
+    run_in_state, VmError, VmState,
+    build_config::opcodes::{stack, register, heap, native, exec},
+};
+
+fn triple(args: &[u64]) -> u64 {
+    args[0] * 3
+}
+
+/// Dirty registers, heap, output and stack, then halt with R5
+fn dirty_code() -> Vec<u8> {
+    vec![
+        register::MOV_IMM, 5, 0xEF, 0xBE, 0xAD, 0xDE, 0, 0, 0, 0,
+        stack::PUSH_IMM8, 32,
+        heap::HEAP_ALLOC,
+        stack::PUSH_IMM8, 0xFF,
+        heap::HEAP_STORE8,
+        stack::PUSH_IMM8, b'x',
+        native::NATIVE_WRITE, 0, 0,
+        stack::PUSH_IMM8, 1,
+        stack::PUSH_IMM8, 2,
+        stack::PUSH_REG, 5,
+        exec::HALT,
+    ]
+}
+
+// ============================================================================
+// Reuse
+// ============================================================================
+
+#[test]
+fn test_runs_share_configuration_not_state() {
+    let table: [fn(&[u64]) -> u64; 1] = [triple];
+    let dirty = dirty_code();
+    let read_r5 = [stack::PUSH_REG, 5, exec::HALT];
+    let heap_size = [heap::HEAP_SIZE, exec::HALT];
+    let call_native = [stack::PUSH_IMM8, 14, native::NATIVE_CALL, 0, 1, exec::HALT];
+
+    let mut state = VmState::with_heap_limit(&[], &[], 64);
+    state.set_native_table(&table);
+    state.disable_anti_analysis();
+
+    assert_eq!(run_in_state(&mut state, &dirty, &[]).unwrap(), 0xDEAD_BEEF);
+    assert_eq!(state.output, b"x");
+    assert_eq!(state.stack.len(), 2);
+
+    // Nothing from the first run is visible to the next ones
+    assert_eq!(run_in_state(&mut state, &read_r5, &[]).unwrap(), 0);
+    assert!(state.output.is_empty());
+    assert!(state.stack.is_empty());
+    assert_eq!(run_in_state(&mut state, &heap_size, &[]).unwrap(), 0);
+    assert_eq!(state.alloc_stats().total_allocs, 0);
+
+    // Configuration is kept
+    assert_eq!(run_in_state(&mut state, &call_native, &[]).unwrap(), 42);
+    assert!(!state.anti_analysis);
+    assert_eq!(state.heap_limit, 64);
+}
+
+#[test]
+fn test_heap_limit_kept_across_runs() {
+    let alloc_128 = [stack::PUSH_IMM8, 128, heap::HEAP_ALLOC, exec::HALT];
+    let alloc_16 = [stack::PUSH_IMM8, 16, heap::HEAP_ALLOC, exec::HALT];
+
+    let mut state = VmState::with_heap_limit(&[], &[], 64);
+    assert_eq!(run_in_state(&mut state, &alloc_16, &[]), Ok(8));
+    assert_eq!(run_in_state(&mut state, &alloc_128, &[]), Err(VmError::HeapOutOfMemory));
+    // A failed run doesn't poison the next one
+    assert_eq!(run_in_state(&mut state, &alloc_16, &[]), Ok(8));
+}
+
+#[test]
+fn test_input_rebound_per_run() {
+    let code = [native::NATIVE_READ, 0, 0, exec::HALT];
+    let inputs = [7u64.to_le_bytes(), 99u64.to_le_bytes()];
+
+    let mut state = VmState::new(&[], &[]);
+    for input in &inputs {
+        let expected = u64::from_le_bytes(*input);
+        assert_eq!(run_in_state(&mut state, &code, input).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_allocations_reused() {
+    let dirty = dirty_code();
+    let mut state = VmState::new(&[], &[]);
+    run_in_state(&mut state, &dirty, &[]).unwrap();
+    let heap_capacity = state.heap.capacity();
+    let stack_capacity = state.stack.capacity();
+
+    run_in_state(&mut state, &dirty, &[]).unwrap();
+    assert_eq!(state.heap.capacity(), heap_capacity);
+    assert_eq!(state.stack.capacity(), stack_capacity);
+}
@@ -0,0 +1,137 @@
+//! Streaming Executor Tests
+//!
+//! Bytecode fed through iterators and chunked readers must produce the
+//! same results as slice-based `execute`.
+
+use aegis_vm::{
+    execute, execute_reader,
+    error::VmError,
+    stream::{execute_stream, execute_stream_with_window},
+    build_config::opcodes::{stack, arithmetic, control, exec},
+};
+use std::io::Read;
+
+/// Reader that hands out at most `chunk` bytes per read call
+struct ChunkedReader {
+    data: Vec<u8>,
+    pos: usize,
+    chunk: usize,
+}
+
+impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Sum 1..=9 in a loop (R0 = counter, R1 = sum)
+fn loop_code() -> Vec<u8> {
+    vec![
+        stack::PUSH_IMM8, 1,     // 0-1
+        stack::POP_REG, 0,       // 2-3
+        stack::PUSH_IMM8, 0,     // 4-5
+        stack::POP_REG, 1,       // 6-7
+        // Loop start (8)
+        stack::PUSH_REG, 1,      // 8-9
+        stack::PUSH_REG, 0,      // 10-11
+        arithmetic::ADD,         // 12
+        stack::POP_REG, 1,       // 13-14
+        stack::PUSH_REG, 0,      // 15-16
+        arithmetic::INC,         // 17
+        stack::POP_REG, 0,       // 18-19
+        stack::PUSH_REG, 0,      // 20-21
+        stack::PUSH_IMM8, 10,    // 22-23
+        control::CMP,            // 24
+        control::JLT, 0xEC, 0xFF, // 25-27: jump -20 to 8
+        stack::PUSH_REG, 1,      // 28-29
+        exec::HALT,              // 30
+    ]
+}
+
+/// Large straight-line program: NOP-padded forward jump + many ADDs
+fn long_code() -> Vec<u8> {
+    let mut code = vec![stack::PUSH_IMM8, 0];
+    for _ in 0..2000 {
+        code.extend_from_slice(&[stack::PUSH_IMM8, 3, arithmetic::ADD]);
+    }
+    // Forward jump over 1000 bytes of garbage (would be HALT_ERR if executed)
+    code.extend_from_slice(&[control::JMP, 0xE8, 0x03]);
+    code.extend(std::iter::repeat_n(exec::HALT_ERR, 1000));
+    code.push(exec::HALT);
+    code
+}
+
+#[test]
+fn test_stream_simple() {
+    let code = vec![stack::PUSH_IMM8, 40, stack::PUSH_IMM8, 2, arithmetic::ADD, exec::HALT];
+    assert_eq!(execute_stream(code.iter().copied(), &[]), execute(&code, &[]));
+}
+
+#[test]
+fn test_stream_loop_matches_execute() {
+    let code = loop_code();
+    assert_eq!(execute(&code, &[]), Ok(45));
+    assert_eq!(execute_stream(code.clone(), &[]), Ok(45));
+}
+
+#[test]
+fn test_stream_long_code_small_window() {
+    let code = long_code();
+    let expected = execute(&code, &[]);
+    assert_eq!(expected, Ok(6000));
+    assert_eq!(execute_stream_with_window(code, &[], 64), expected);
+}
+
+#[test]
+fn test_reader_chunked() {
+    for chunk in [1, 3, 7, 64] {
+        for code in [loop_code(), long_code()] {
+            let expected = execute(&code, &[]);
+            let reader = ChunkedReader { data: code, pos: 0, chunk };
+            assert_eq!(execute_reader(reader, &[]), expected, "chunk = {}", chunk);
+        }
+    }
+}
+
+#[test]
+fn test_stream_backward_jump_outside_window() {
+    // Loop body starts ~60 bytes back, window of 16 bytes can't reach it
+    let mut code = vec![stack::PUSH_IMM8, 0];
+    for _ in 0..20 {
+        code.extend_from_slice(&[stack::PUSH_IMM8, 1, arithmetic::ADD]);
+    }
+    let back = -(code.len() as i16 + 3 - 2);
+    code.push(control::JMP);
+    code.extend_from_slice(&back.to_le_bytes());
+    code.push(exec::HALT);
+
+    assert_eq!(
+        execute_stream_with_window(code, &[], 16),
+        Err(VmError::InvalidJumpTarget)
+    );
+}
+
+#[test]
+fn test_stream_call_ret_survive_window() {
+    // CALL a subroutine far ahead; the return address must stay buffered
+    let mut code = vec![
+        stack::PUSH_IMM8, 5,
+        control::CALL, 0x00, 0x00, // patched below
+        stack::PUSH_IMM8, 1,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+    let sub_start = code.len() + 500;
+    let offset = (sub_start - 5) as i16;
+    code[3..5].copy_from_slice(&offset.to_le_bytes());
+    code.extend(std::iter::repeat_n(exec::HALT_ERR, 500));
+    // Subroutine: doubles top of stack
+    code.extend_from_slice(&[stack::DUP, arithmetic::ADD, control::RET]);
+
+    let expected = execute(&code, &[]);
+    assert_eq!(expected, Ok(11));
+    assert_eq!(execute_stream_with_window(code, &[], 8), expected);
+}
@@ -0,0 +1,182 @@
+//! Strict Heap Tests
+//!
+//! By default heap accesses are only checked against the reserved heap, so
+//! freed blocks stay readable. With `strict_heap`, accesses must fall inside
+//! a live allocation and anything else fails with `UseAfterFree`.
+
+use aegis_vm::{
+    run, BytecodeBuilder, VmError, VmResult, VmState,
+    build_config::opcodes::{stack, arithmetic, heap, vector, exec},
+};
+
+fn run_code(code: &[u8], strict: bool) -> VmResult<u64> {
+    let mut state = VmState::new(code, &[]);
+    state.set_strict_heap(strict);
+    run(&mut state).map(|()| state.result)
+}
+
+/// R0 = alloc(8); heap[R0] = 42; free(R0); return heap[R0]
+fn read_after_free_code() -> Vec<u8> {
+    BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc().pop_reg(0)
+        .push_reg(0).push_imm8(42).heap_store64()
+        .push_reg(0).heap_free()
+        .push_reg(0).heap_load64()
+        .halt()
+        .build()
+        .unwrap()
+}
+
+// ============================================================================
+// Freed and never-allocated memory
+// ============================================================================
+
+#[test]
+fn test_read_after_free_legacy_succeeds() {
+    assert_eq!(run_code(&read_after_free_code(), false), Ok(42));
+}
+
+#[test]
+fn test_read_after_free_strict_errors() {
+    assert_eq!(run_code(&read_after_free_code(), true), Err(VmError::UseAfterFree));
+}
+
+#[test]
+fn test_write_after_free_strict_errors() {
+    let code = BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc().pop_reg(0)
+        .push_reg(0).heap_free()
+        .push_reg(0).push_imm8(1).heap_store64()
+        .push_imm8(0)
+        .halt()
+        .build()
+        .unwrap();
+    assert_eq!(run_code(&code, false), Ok(0));
+    assert_eq!(run_code(&code, true), Err(VmError::UseAfterFree));
+}
+
+#[test]
+fn test_allocation_header_is_not_addressable() {
+    // The header sits just below the user pointer, inside the reserved heap
+    let code = BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc()
+        .push_imm8(8).sub()
+        .heap_load64()
+        .halt()
+        .build()
+        .unwrap();
+    assert!(run_code(&code, false).is_ok());
+    assert_eq!(run_code(&code, true), Err(VmError::UseAfterFree));
+}
+
+#[test]
+fn test_access_past_end_of_block() {
+    // 8-byte block: a u64 at +4 straddles the next block's header
+    let code = BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc().pop_reg(0)
+        .push_imm8(8).heap_alloc().drop()
+        .push_reg(0).push_imm8(4).add()
+        .heap_load64()
+        .halt()
+        .build()
+        .unwrap();
+    assert!(run_code(&code, false).is_ok());
+    assert_eq!(run_code(&code, true), Err(VmError::UseAfterFree));
+}
+
+#[test]
+fn test_beyond_reserved_heap_is_still_out_of_bounds() {
+    let code = BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc().drop()
+        .push_imm32(0x10000).heap_load64()
+        .halt()
+        .build()
+        .unwrap();
+    assert_eq!(run_code(&code, true), Err(VmError::HeapOutOfBounds));
+}
+
+// ============================================================================
+// Valid programs are unaffected
+// ============================================================================
+
+#[test]
+fn test_reused_block_is_live_again() {
+    let code = BytecodeBuilder::new()
+        .push_imm8(16).heap_alloc().heap_free()
+        .push_imm8(16).heap_alloc().pop_reg(0)
+        .push_reg(0).push_imm8(7).heap_store64()
+        .push_reg(0).push_imm8(8).add().push_imm8(35).heap_store64()
+        .push_reg(0).heap_load64()
+        .push_reg(0).push_imm8(8).add().heap_load64()
+        .add()
+        .halt()
+        .build()
+        .unwrap();
+    assert_eq!(run_code(&code, true), Ok(42));
+}
+
+#[test]
+fn test_vector_ops_in_strict_mode() {
+    let code = [
+        stack::PUSH_IMM8, 4,
+        stack::PUSH_IMM8, 8,
+        vector::VEC_NEW,
+        stack::DUP,
+        stack::PUSH_IMM8, 40,
+        vector::VEC_PUSH,
+        stack::DUP,
+        stack::PUSH_IMM8, 2,
+        vector::VEC_PUSH,
+        stack::DUP,
+        stack::PUSH_IMM8, 1,
+        vector::VEC_GET,
+        stack::SWAP,
+        stack::PUSH_IMM8, 0,
+        vector::VEC_GET,
+        arithmetic::ADD,
+        exec::HALT,
+    ];
+    assert_eq!(run_code(&code, true), Ok(42));
+}
+
+#[test]
+fn test_grown_regions_are_live() {
+    // Two one-byte grow stores, then a u16 load spanning both
+    let code = [
+        stack::PUSH_IMM8, 0,
+        stack::PUSH_IMM8, 0x34,
+        heap::HEAP_STORE8_GROW,
+        stack::PUSH_IMM8, 1,
+        stack::PUSH_IMM8, 0x12,
+        heap::HEAP_STORE8_GROW,
+        stack::PUSH_IMM8, 0,
+        heap::HEAP_LOAD16,
+        exec::HALT,
+    ];
+    assert_eq!(run_code(&code, true), Ok(0x1234));
+}
+
+#[test]
+fn test_double_free_still_reported() {
+    let code = BytecodeBuilder::new()
+        .push_imm8(8).heap_alloc().pop_reg(0)
+        .push_reg(0).heap_free()
+        .push_reg(0).heap_free()
+        .push_imm8(0)
+        .halt()
+        .build()
+        .unwrap();
+    assert_eq!(run_code(&code, true), Err(VmError::DoubleFree));
+}
+
+#[test]
+fn test_rebind_keeps_strict_mode() {
+    let code = read_after_free_code();
+    let mut state = VmState::new(&code, &[]);
+    state.set_strict_heap(true);
+    assert_eq!(run(&mut state), Err(VmError::UseAfterFree));
+
+    state.rebind(&code, &[]);
+    assert!(state.live_ranges.is_empty());
+    assert_eq!(run(&mut state), Err(VmError::UseAfterFree));
+}
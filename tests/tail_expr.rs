@@ -0,0 +1,247 @@
+//! Tail Expression Tests
+//!
+//! The last expression of a block (no `;`) is the block's value. Nested
+//! `if`/`match`/`{}` at the end of a protected function must produce the
+//! same result as native Rust without any explicit `return`.
+
+use aegis_vm_macro::vm_protect;
+
+// ============================================================================
+// Protected functions
+// ============================================================================
+
+/// if/else chain whose arms end in nested blocks
+#[vm_protect(level = "debug")]
+fn sign_bucket(x: u64, y: u64) -> u64 {
+    if x > y {
+        if x - y > 100 {
+            3
+        } else {
+            2
+        }
+    } else if x == y {
+        {
+            let base = 10;
+            base + x
+        }
+    } else {
+        1
+    }
+}
+
+/// match arms ending in if/else and inner blocks
+#[vm_protect(level = "debug")]
+fn classify(tag: u64, x: u64) -> u64 {
+    match tag {
+        0 => {
+            if x & 1 == 0 { x / 2 } else { x * 3 + 1 }
+        }
+        1 => {
+            let doubled = x * 2;
+            {
+                let tripled = x * 3;
+                doubled + tripled
+            }
+        }
+        2 => match x {
+            0 => 7,
+            1 => 11,
+            _ => x ^ 0xFF,
+        },
+        _ => 999,
+    }
+}
+
+/// Three levels of nesting, each contributing to the tail value
+#[vm_protect(level = "debug")]
+fn deep(a: u64, b: u64, c: u64) -> u64 {
+    let offset = a + 1;
+    if a < 10 {
+        match b {
+            0 => {
+                if c > 5 { offset + c } else { offset * c }
+            }
+            _ => {
+                let inner = {
+                    let t = b * 100;
+                    t + c
+                };
+                inner + offset
+            }
+        }
+    } else {
+        {
+            {
+                a ^ b ^ c
+            }
+        }
+    }
+}
+
+/// Tail value computed after an early `return` on another path
+#[vm_protect(level = "debug")]
+fn guarded(x: u64) -> u64 {
+    if x == 0 {
+        return 42;
+    }
+    match x % 3 {
+        0 => x / 3,
+        1 => {
+            if x > 50 { x - 50 } else { 50 - x }
+        }
+        _ => x + 1,
+    }
+}
+
+#[vm_protect]
+fn classify_standard(tag: u64, x: u64) -> u64 {
+    match tag {
+        0 => {
+            if x & 1 == 0 { x / 2 } else { x * 3 + 1 }
+        }
+        1 => {
+            let doubled = x * 2;
+            {
+                let tripled = x * 3;
+                doubled + tripled
+            }
+        }
+        2 => match x {
+            0 => 7,
+            1 => 11,
+            _ => x ^ 0xFF,
+        },
+        _ => 999,
+    }
+}
+
+#[vm_protect(level = "paranoid")]
+fn deep_paranoid(a: u64, b: u64, c: u64) -> u64 {
+    let offset = a + 1;
+    if a < 10 {
+        match b {
+            0 => {
+                if c > 5 { offset + c } else { offset * c }
+            }
+            _ => {
+                let inner = {
+                    let t = b * 100;
+                    t + c
+                };
+                inner + offset
+            }
+        }
+    } else {
+        {
+            {
+                a ^ b ^ c
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Native references
+// ============================================================================
+
+fn native_sign_bucket(x: u64, y: u64) -> u64 {
+    if x > y {
+        if x - y > 100 { 3 } else { 2 }
+    } else if x == y {
+        10 + x
+    } else {
+        1
+    }
+}
+
+fn native_classify(tag: u64, x: u64) -> u64 {
+    match tag {
+        0 if x & 1 == 0 => x / 2,
+        0 => x * 3 + 1,
+        1 => x * 5,
+        2 => match x {
+            0 => 7,
+            1 => 11,
+            _ => x ^ 0xFF,
+        },
+        _ => 999,
+    }
+}
+
+fn native_deep(a: u64, b: u64, c: u64) -> u64 {
+    let offset = a + 1;
+    match (a < 10, b) {
+        (true, 0) if c > 5 => offset + c,
+        (true, 0) => offset * c,
+        (true, _) => b * 100 + c + offset,
+        (false, _) => a ^ b ^ c,
+    }
+}
+
+fn native_guarded(x: u64) -> u64 {
+    if x == 0 {
+        return 42;
+    }
+    match x % 3 {
+        0 => x / 3,
+        1 => x.abs_diff(50),
+        _ => x + 1,
+    }
+}
+
+const SAMPLES: [u64; 10] = [0, 1, 2, 5, 6, 9, 10, 49, 150, 1000];
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_if_else_chain_tail() {
+    for x in SAMPLES {
+        for y in SAMPLES {
+            assert_eq!(sign_bucket(x, y), native_sign_bucket(x, y), "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn test_match_arm_tails() {
+    for tag in 0..4 {
+        for x in SAMPLES {
+            assert_eq!(classify(tag, x), native_classify(tag, x), "tag {tag}, x {x}");
+        }
+    }
+}
+
+#[test]
+fn test_deeply_nested_tails() {
+    for a in SAMPLES {
+        for b in [0u64, 1, 7] {
+            for c in SAMPLES {
+                assert_eq!(deep(a, b, c), native_deep(a, b, c), "({a}, {b}, {c})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tail_after_early_return() {
+    for x in SAMPLES.into_iter().chain([3, 4, 51, 52, 53]) {
+        assert_eq!(guarded(x), native_guarded(x), "x = {x}");
+    }
+}
+
+#[test]
+fn test_protection_levels_agree() {
+    for tag in 0..4 {
+        for x in SAMPLES {
+            assert_eq!(classify_standard(tag, x), native_classify(tag, x), "tag {tag}, x {x}");
+        }
+    }
+    for a in [0u64, 9, 10] {
+        for c in [0u64, 5, 6] {
+            assert_eq!(deep_paranoid(a, 0, c), native_deep(a, 0, c), "({a}, 0, {c})");
+            assert_eq!(deep_paranoid(a, 3, c), native_deep(a, 3, c), "({a}, 3, {c})");
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Execution Timeout Tests
+//!
+//! `execute_with_timeout` / `run_with_timeout` stop a run with
+//! `VmError::Timeout` once the wall-clock limit has passed, even when the
+//! time goes into native calls rather than instructions.
+
+use std::time::{Duration, Instant};
+
+use aegis_vm::{
+    execute_with_timeout, run_with_timeout, NativeRegistry, VmError, VmState,
+    engine::TIMEOUT_CHECK_INTERVAL,
+    build_config::opcodes::{stack, control, native, special, exec},
+};
+
+fn slow_native(args: &[u64]) -> u64 {
+    std::thread::sleep(Duration::from_millis(1));
+    args[0]
+}
+
+/// Call native 0 forever
+fn native_loop() -> Vec<u8> {
+    let mut code = vec![
+        stack::PUSH_IMM8, 1,
+        native::NATIVE_CALL, 0, 1,
+        stack::DROP,
+        control::JMP,
+    ];
+    code.extend_from_slice(&(-9i16).to_le_bytes());
+    code
+}
+
+/// Spin forever without natives
+fn spin_loop() -> Vec<u8> {
+    let mut code = vec![special::NOP, control::JMP];
+    code.extend_from_slice(&(-4i16).to_le_bytes());
+    code
+}
+
+// ============================================================================
+// Timeout
+// ============================================================================
+
+#[test]
+fn test_slow_native_times_out() {
+    let table: [fn(&[u64]) -> u64; 1] = [slow_native];
+    let code = native_loop();
+    let mut state = VmState::new(&code, &[]);
+    state.set_native_table(&table);
+
+    let start = Instant::now();
+    let result = run_with_timeout(&mut state, &NativeRegistry::new(), Duration::from_millis(10));
+    assert_eq!(result, Err(VmError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    // Far below the instruction limit: time, not work, stopped the run
+    assert!(state.instruction_count <= 4 * TIMEOUT_CHECK_INTERVAL);
+}
+
+#[test]
+fn test_spin_loop_times_out() {
+    let result = execute_with_timeout(&spin_loop(), &[], Duration::ZERO);
+    assert_eq!(result, Err(VmError::Timeout));
+}
+
+#[test]
+fn test_instruction_limit_still_applies() {
+    let result = execute_with_timeout(&spin_loop(), &[], Duration::from_secs(3600));
+    assert_eq!(result, Err(VmError::MaxInstructionsExceeded));
+}
+
+// ============================================================================
+// Completion
+// ============================================================================
+
+#[test]
+fn test_fast_code_completes() {
+    let code = [stack::PUSH_IMM8, 42, exec::HALT];
+    assert_eq!(execute_with_timeout(&code, &[], Duration::from_secs(1)), Ok(42));
+}
+
+#[test]
+fn test_short_run_is_not_sampled() {
+    // Time is only checked every TIMEOUT_CHECK_INTERVAL instructions
+    let code = [stack::PUSH_IMM8, 7, exec::HALT];
+    assert_eq!(execute_with_timeout(&code, &[], Duration::ZERO), Ok(7));
+}
+
+#[test]
+fn test_timeout_error_code() {
+    assert_eq!(VmError::Timeout.code(), 24);
+}
@@ -2,7 +2,8 @@
 //!
 //! Tests all opcodes and edge cases for the anticheat VM.
 
-use aegis_vm::{execute, execute_with_state, VmError};
+use aegis_vm::{execute, execute_deterministic, execute_with_state, run, VmError, VmState};
+use aegis_vm::state::MAX_CALL_DEPTH;
 // Use shuffled opcodes from build config for tests
 use aegis_vm::build_config::opcodes::{stack, register, arithmetic, control, special, native, exec};
 
@@ -119,6 +120,44 @@ fn test_multiple_registers() {
     assert_eq!(result, 36);
 }
 
+#[test]
+fn test_register_cap_enforced() {
+    // R3 fits under a cap of 4, R4 does not
+    let ok = [
+        register::MOV_IMM, 3, 9, 0, 0, 0, 0, 0, 0, 0,
+        stack::PUSH_REG, 3,
+        exec::HALT,
+    ];
+    let mut state = VmState::with_max_registers(&ok, &[], 4);
+    run(&mut state).unwrap();
+    assert_eq!(state.result, 9);
+
+    let too_high = [
+        stack::PUSH_IMM8, 1,
+        stack::POP_REG, 4,
+        exec::HALT,
+    ];
+    let mut state = VmState::with_max_registers(&too_high, &[], 4);
+    assert_eq!(run(&mut state), Err(VmError::InvalidRegister));
+    assert!(state.reg_count() <= 4);
+}
+
+#[test]
+fn test_high_register_under_low_cap_does_not_grow() {
+    let code = [
+        register::MOV_IMM, 200, 1, 0, 0, 0, 0, 0, 0, 0,
+        exec::HALT,
+    ];
+    let mut state = VmState::with_max_registers(&code, &[], 16);
+    assert_eq!(run(&mut state), Err(VmError::InvalidRegister));
+    assert_eq!(state.reg_count(), 16);
+
+    // Same code is fine with the default cap
+    let mut state = VmState::new(&code, &[]);
+    run(&mut state).unwrap();
+    assert_eq!(state.reg_count(), 201);
+}
+
 // ============================================================================
 // Arithmetic Operations
 // ============================================================================
@@ -547,6 +586,42 @@ fn test_ret_from_main() {
     assert_eq!(result, 42);
 }
 
+#[test]
+fn test_unbounded_recursion_overflows_call_stack() {
+    // Subroutine that calls itself forever
+    let code = [
+        control::CALL, 0xFD, 0xFF,
+        exec::HALT,
+    ];
+    let state = &mut VmState::new(&code, &[]);
+    assert_eq!(run(state), Err(VmError::CallStackOverflow));
+    assert_eq!(state.call_stack.len(), MAX_CALL_DEPTH);
+}
+
+#[test]
+fn test_recursion_within_call_depth() {
+    // R0 counts down from MAX_CALL_DEPTH, recursing until it reaches zero
+    let [lo, hi] = (MAX_CALL_DEPTH as u16).to_le_bytes();
+    let code = [
+        register::MOV_IMM, 0, lo, hi, 0, 0, 0, 0, 0, 0,
+        control::CALL, 0x03, 0x00,   // 10: call 16
+        stack::PUSH_REG, 0,          // 13
+        exec::HALT,                  // 15
+        stack::PUSH_REG, 0,          // 16
+        arithmetic::DEC,
+        stack::POP_REG, 0,
+        stack::PUSH_REG, 0,
+        stack::PUSH_IMM8, 0,
+        control::CMP,
+        stack::DROP,
+        stack::DROP,
+        control::JZ, 0x03, 0x00,     // 28: to 34
+        control::CALL, 0xEE, 0xFF,   // 31: call 16
+        control::RET,                // 34
+    ];
+    assert_eq!(execute(&code, &[]).unwrap(), 0);
+}
+
 // ============================================================================
 // Special Operations
 // ============================================================================
@@ -596,6 +671,53 @@ fn test_opaque_false() {
     assert_eq!(result, 0);
 }
 
+#[test]
+fn test_hash_check_mismatch() {
+    let code = [
+        special::HASH_CHECK, 0x00, 0x00, 0x00, 0x00,   // wrong hash
+        stack::PUSH_IMM8, 42,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]), Err(VmError::IntegrityFailed));
+}
+
+#[test]
+fn test_deterministic_mode_skips_anti_analysis() {
+    // "Paranoid"-style bytecode: timing checks around an integrity check
+    let code = [
+        special::TIMING_CHECK,
+        special::HASH_CHECK, 0x00, 0x00, 0x00, 0x00,   // wrong hash
+        stack::PUSH_IMM8, 40,
+        special::TIMING_CHECK,
+        stack::PUSH_IMM8, 2,
+        arithmetic::ADD,
+        special::TIMING_CHECK,
+        exec::HALT,
+    ];
+    assert_eq!(execute(&code, &[]), Err(VmError::IntegrityFailed));
+
+    // Same result on every run, regardless of scheduling
+    for _ in 0..10 {
+        assert_eq!(execute_deterministic(&code, &[]), Ok(42));
+    }
+}
+
+#[test]
+fn test_deterministic_mode_ignores_stale_timing() {
+    let code = [
+        special::TIMING_CHECK,
+        stack::PUSH_IMM8, 42,
+        exec::HALT,
+    ];
+
+    // Last checkpoint long ago (as if single-stepped in a debugger)
+    let mut state = VmState::new(&code, &[]);
+    state.last_timing_ns = 1;
+    state.disable_anti_analysis();
+    assert_eq!(run(&mut state), Ok(()));
+    assert_eq!(state.result, 42);
+}
+
 // ============================================================================
 // Native Operations
 // ============================================================================
@@ -707,7 +829,7 @@ fn test_memory_out_of_bounds() {
     ];
     let input = [1, 2, 3];
     let result = execute(&code, &input);
-    assert_eq!(result, Err(VmError::MemoryOutOfBounds));
+    assert_eq!(result, Err(VmError::InputOutOfBounds));
 }
 
 #[test]
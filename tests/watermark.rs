@@ -0,0 +1,44 @@
+//! Tests for watermark extraction and verification
+
+use aegis_vm::build_config::{get_build_seed, BUILD_TIMESTAMP, CUSTOMER_ID, WATERMARK};
+use aegis_vm::watermark;
+
+#[test]
+fn test_current_matches_build_config() {
+    assert_eq!(watermark::current(), WATERMARK);
+}
+
+#[test]
+fn test_compute_matches_embedded() {
+    let expected = watermark::compute(CUSTOMER_ID, &get_build_seed(), BUILD_TIMESTAMP);
+    assert_eq!(expected, WATERMARK);
+}
+
+#[test]
+fn test_verify_accepts_build_inputs() {
+    assert!(watermark::verify(CUSTOMER_ID, &get_build_seed(), BUILD_TIMESTAMP));
+}
+
+#[test]
+fn test_verify_rejects_other_customer() {
+    assert!(!watermark::verify("someone-else", &get_build_seed(), BUILD_TIMESTAMP));
+}
+
+#[test]
+fn test_verify_rejects_other_seed_or_timestamp() {
+    let mut seed = get_build_seed();
+    seed[0] ^= 1;
+    assert!(!watermark::verify(CUSTOMER_ID, &seed, BUILD_TIMESTAMP));
+    assert!(!watermark::verify(CUSTOMER_ID, &get_build_seed(), BUILD_TIMESTAMP + 1));
+}
+
+#[test]
+fn test_compute_known_vector() {
+    // SHA256("" || [0; 32] || 0u64 || "watermark-v1")[..16]
+    let wm = watermark::compute("", &[0u8; 32], 0);
+    let mut data = vec![0u8; 40];
+    data.extend_from_slice(b"watermark-v1");
+    use sha2::{Digest, Sha256};
+    let hash = Sha256::digest(&data);
+    assert_eq!(&wm[..], &hash[..16]);
+}
@@ -8,6 +8,8 @@
 use aegis_vm::whitebox::{
     init_tables, init_tables_lite, whitebox_encrypt, whitebox_encrypt_lite,
     encrypt_block, encrypt_blocks, AES_BLOCK_SIZE, WHITEBOX_TABLE_SIZE,
+    cached_tables, derive_bytecode_key, derive_smc_key, derive_nonce,
+    WhiteboxCryptoContext,
 };
 
 #[test]
@@ -319,3 +321,28 @@ fn test_table_generation_deterministic() {
         "Tables generated from same key should produce same results"
     );
 }
+
+#[test]
+fn test_cached_tables_shared_instance() {
+    let a: *const _ = cached_tables();
+    let b: *const _ = cached_tables();
+    assert_eq!(a, b, "cached_tables() should return the same instance");
+}
+
+#[test]
+fn test_cached_tables_match_fresh() {
+    let fresh = init_tables();
+
+    let mut cached_block = [0x5A; AES_BLOCK_SIZE];
+    let mut fresh_block = [0x5A; AES_BLOCK_SIZE];
+    whitebox_encrypt(&mut cached_block, cached_tables());
+    whitebox_encrypt(&mut fresh_block, &fresh);
+    assert_eq!(cached_block, fresh_block);
+
+    // WhiteboxCryptoContext derives its keys from its own fresh tables
+    let mut ctx = WhiteboxCryptoContext::new();
+    assert_eq!(&derive_bytecode_key(), ctx.bytecode_key());
+    assert_eq!(&derive_smc_key(), ctx.smc_key());
+    assert_eq!(derive_nonce(0), ctx.next_nonce());
+    assert_eq!(derive_nonce(1), ctx.next_nonce());
+}